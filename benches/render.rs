@@ -0,0 +1,60 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+use termclock::bench::{draw_frame, parse_args, render_big_time, thermometer_layout, App, BigTextStyle};
+
+// 大字时间：相同字符串（命中整体缓存）与逐秒变化（命中字形缓存）
+fn big_time(c: &mut Criterion) {
+    let times: Vec<String> = (0..60).map(|s| format!("12:34:{:02}", s)).collect();
+    for scale in [1u16, 2, 4] {
+        let style = BigTextStyle::new(scale, scale);
+        c.bench_function(&format!("render_big_time/same/{}x", scale), |b| {
+            b.iter(|| render_big_time(black_box("12:34:56"), &style))
+        });
+        c.bench_function(&format!("render_big_time/ticking/{}x", scale), |b| {
+            let mut i = 0;
+            b.iter(|| {
+                i = (i + 1) % times.len();
+                render_big_time(black_box(&times[i]), &style)
+            })
+        });
+    }
+}
+
+fn thermometer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("thermometer_layout");
+    for width in [40usize, 120, 300] {
+        group.bench_with_input(BenchmarkId::from_parameter(width), &width, |b, &width| {
+            b.iter(|| thermometer_layout(black_box(width), black_box(Some(23))))
+        });
+    }
+    group.finish();
+}
+
+// 完整一帧（不含网络请求），使用默认配置和示例待办
+fn full_frame(c: &mut Criterion) {
+    // SAFETY: 基准测试单线程运行，此时没有其他线程读取环境变量
+    unsafe { std::env::set_var("TERMCLOCK_CONFIG", "/nonexistent/termclock.yml") };
+    let mut app = App::new(parse_args());
+    app.cached_temp = Some("23.5℃".to_string());
+    app.todos = vec![
+        "2024-10-18 | !1 写周报".to_string(),
+        "(B) 预约体检".to_string(),
+        "2024-10-20 | 交电费".to_string(),
+        "买牛奶".to_string(),
+    ];
+    let mut group = c.benchmark_group("draw_frame");
+    for (w, h) in [(80u16, 24u16), (160, 48), (320, 90)] {
+        let mut terminal = Terminal::new(TestBackend::new(w, h)).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(format!("{}x{}", w, h)), |b| {
+            b.iter(|| {
+                terminal.draw(|f| draw_frame(f, &mut app)).unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, big_time, thermometer, full_frame);
+criterion_main!(benches);
@@ -0,0 +1,165 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::model::{App, Config};
+
+// --accessible：不使用全屏界面、大字和边框，按行输出纯文本，适合屏幕阅读器。
+// 启动时输出全部状态，之后只输出变化的行（时间每分钟一行）。
+// 输入 : 命令面板中的命令（如 timer 5m、mute 30、todo add 买牛奶）或 snooze / dismiss 回车执行，events 列出事件记录，空行重新输出全部状态，q 退出
+const TICK: Duration = Duration::from_millis(200);
+
+pub fn run(config: Config) -> io::Result<()> {
+    let (ipc_tx, ipc_rx) = mpsc::channel();
+    let ipc_ok = crate::ipc::spawn_server(&config.ipc_socket, ipc_tx).is_ok();
+    let ipc_path = config.ipc_socket.clone();
+    let mut app = App::new(config);
+    app.start_workers();
+    app.tick();
+
+    let input = spawn_stdin_reader();
+    let mut out = io::stdout();
+    writeln!(out, "termclock accessible mode. Type a command and press Enter (for example: timer 5m), an empty line repeats the status, q quits.")?;
+    let mut shown: Vec<(String, String)> = Vec::new();
+    let mut full = true;
+    while !app.quit_requested {
+        while let Ok(request) = ipc_rx.try_recv() {
+            app.handle_ipc(request);
+        }
+        while let Ok(line) = input.try_recv() {
+            match line.as_deref().map(str::trim) {
+                // 标准输入关闭（Ctrl-D）
+                None => app.quit_requested = true,
+                Some("") => full = true,
+                Some("q" | "quit" | "exit") => app.quit_requested = true,
+                // 对应界面中的 s / Enter 键
+                Some("snooze") => app.snooze(),
+                Some("dismiss") => app.dismiss_alarm(),
+                // 事件记录，最新的在前
+                Some("events") => {
+                    for event in app.events.recent() {
+                        writeln!(out, "Event: {} {}: {}", event.at.format("%H:%M"), event.kind.label(), event.text)?;
+                    }
+                }
+                Some(command) => match app.parse_command(command.trim_start_matches(':')) {
+                    Ok(command) => app.run_command(command),
+                    Err(e) => writeln!(out, "Error: {}", e)?,
+                },
+            }
+        }
+        app.tick();
+        let status = status_lines(&mut app);
+        // 按标签比较，只输出新增或变化的行；消失的行（如闹钟结束）输出 "none"。完整输出时时间带秒
+        for (label, value) in &status {
+            if full && label == "Time" {
+                writeln!(out, "Time: {}", app.display_time().format("%H:%M:%S"))?;
+            } else if full || !shown.iter().any(|(l, v)| l == label && v == value) {
+                writeln!(out, "{}: {}", label, value)?;
+            }
+        }
+        if !full {
+            for (label, _) in shown.iter().filter(|(l, _)| removable(l) && !status.iter().any(|(s, _)| s == l)) {
+                writeln!(out, "{}: none", label)?;
+            }
+        }
+        out.flush()?;
+        shown = status;
+        full = false;
+        std::thread::sleep(TICK);
+    }
+    if ipc_ok {
+        let _ = std::fs::remove_file(&ipc_path);
+    }
+    Ok(())
+}
+
+// 逐行读取标准输入；None 表示已关闭。标准输入不是终端（如 /dev/null）时不读取，避免立即退出
+fn spawn_stdin_reader() -> mpsc::Receiver<Option<String>> {
+    let (tx, rx) = mpsc::channel();
+    if io::stdin().is_terminal() {
+        std::thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                if tx.send(line.ok()).is_err() {
+                    return;
+                }
+            }
+            let _ = tx.send(None);
+        });
+    }
+    rx
+}
+
+// 闹钟、计时器等消失时提示一次；时间、温度、待办和短暂提示不提示
+fn removable(label: &str) -> bool {
+    !matches!(label, "Time" | "Date" | "Calendar" | "Temperature" | "Notice") && !label.starts_with("Todo")
+}
+
+// 当前状态（标签，文本）；时间只精确到分钟，避免每秒朗读一次
+fn status_lines(app: &mut App) -> Vec<(String, String)> {
+    let now = app.display_time();
+    let mut lines = Vec::new();
+    let mut push = |label: &str, value: String| lines.push((label.to_string(), value));
+    push("Time", now.format("%H:%M").to_string());
+    let zone = app.display_tz.map(|tz| format!(" ({})", tz.name())).unwrap_or_default();
+    push("Date", format!("{}{}", now.format("%A, %B %-d, %Y"), zone));
+    if let Some(calendar) = app.config.secondary_calendar {
+        push("Calendar", calendar.format(now.date_naive()));
+    }
+    push("Temperature", plain_units(&app.temperature()));
+    if let Some(humidity) = app.cached_humidity {
+        push("Humidity", format!("{:.0}%", humidity));
+    }
+    if app.config.compare_indoor_outdoor
+        && let Some(outdoor) = &app.cached_outdoor_temp
+    {
+        push("Outdoor", plain_units(outdoor));
+    }
+    if let Some(alarm) = &app.alarm {
+        let state = if alarm.is_ringing() { "ringing, type snooze or dismiss" } else { "snoozed" };
+        push("Alarm", format!("{} ({})", alarm.label, state));
+    }
+    if let Some((until, label)) = &app.timer {
+        let mins = until.saturating_duration_since(std::time::Instant::now()).as_secs().div_ceil(60);
+        push("Timer", format!("{} min left{}", mins, if label.is_empty() { String::new() } else { format!(" ({})", label) }));
+    }
+    if let Some(meeting) = app.next_meeting() {
+        push("Next meeting", format!("{} at {}", meeting.title, meeting.start.format("%H:%M")));
+    }
+    if let Some(alert) = app.rain_alert() {
+        push("Rain", crate::weather::describe(&alert, app.now()).trim_start_matches('☂').trim().to_string());
+    }
+    if let Some(message) = app.message() {
+        push("Message", message.to_string());
+    }
+    if let Some((notice, until)) = &app.notice
+        && std::time::Instant::now() < *until
+    {
+        push("Notice", notice.clone());
+    }
+    if let Some(synced) = app.time_synced() {
+        push("Time sync", if synced { "synchronized" } else { "NOT synchronized, the time shown may be wrong" }.to_string());
+    }
+    if let Some(ms) = app.clock_drift() {
+        push("Clock drift", format!("system clock is off by {}", crate::ntp::format_offset(ms)));
+    }
+    if let Some(since) = app.offline_banner() {
+        push("Offline", format!("since {}", since.format("%H:%M")));
+    }
+    if let Some(error) = app.api_error() {
+        push("Error", error);
+    }
+    if app.is_muted() {
+        push("Sound", "muted".to_string());
+    }
+    let todos: Vec<String> = app.visible_todos().into_iter().map(|(_, t)| t.clone()).collect();
+    push("Todos", format!("{} open", todos.len()));
+    for (i, todo) in todos.into_iter().enumerate() {
+        push(&format!("Todo {}", i + 1), todo);
+    }
+    lines
+}
+
+// 温度单位改为纯文本（23℃ -> 23C），部分屏幕阅读器不朗读 ℃
+fn plain_units(text: &str) -> String {
+    text.replace('℃', "C").replace("°C", "C")
+}
@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+use crate::schedule::CronSchedule;
+
+// 响铃期间重复提示音的间隔
+const RING_REPEAT: Duration = Duration::from_secs(10);
+
+// 闹钟配置：触发时间（cron）+ 标签
+#[derive(Debug, Clone)]
+pub struct Alarm {
+    pub cron: CronSchedule,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlarmState {
+    Ringing { last_ring: Option<Instant> },
+    Snoozed { until: Instant },
+}
+
+// 当前触发中的闹钟（显示横幅，直到被关闭）
+#[derive(Debug, Clone)]
+pub struct ActiveAlarm {
+    pub label: String,
+    pub state: AlarmState,
+    pub snooze_count: u32,
+}
+
+impl ActiveAlarm {
+    pub fn new(label: String) -> Self {
+        Self { label, state: AlarmState::Ringing { last_ring: None }, snooze_count: 0 }
+    }
+
+    // 时长溢出时保持响铃
+    pub fn snooze(&mut self, minutes: u64) {
+        let Some(until) = minutes.checked_mul(60).and_then(|secs| Instant::now().checked_add(Duration::from_secs(secs))) else { return };
+        self.state = AlarmState::Snoozed { until };
+        self.snooze_count += 1;
+    }
+
+    pub fn is_ringing(&self) -> bool {
+        matches!(self.state, AlarmState::Ringing { .. })
+    }
+
+    // 推进状态：贪睡到期后重新响铃；返回本次是否需要发出提示音
+    pub fn tick(&mut self, now: Instant) -> bool {
+        match self.state {
+            AlarmState::Snoozed { until } if now >= until => {
+                self.state = AlarmState::Ringing { last_ring: Some(now) };
+                true
+            }
+            AlarmState::Snoozed { .. } => false,
+            AlarmState::Ringing { last_ring } => {
+                let due = last_ring.is_none_or(|t| now.duration_since(t) >= RING_REPEAT);
+                if due {
+                    self.state = AlarmState::Ringing { last_ring: Some(now) };
+                }
+                due
+            }
+        }
+    }
+}
+
+// 支持 "07:30" 形式的每日闹钟，转换为 cron
+pub fn parse_alarm_time(s: &str) -> Option<CronSchedule> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m): (u32, u32) = (h.parse().ok()?, m.parse().ok()?);
+    if h > 23 || m > 59 {
+        return None;
+    }
+    CronSchedule::parse(&format!("{} {} * * *", m, h))
+}
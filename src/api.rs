@@ -1,162 +1,283 @@
+use std::path::Path;
 use std::time::Duration;
-use crate::model::{ApiResponse, TemperatureData, TodoData};
+use crate::api_schema::Endpoint;
+use crate::api_schema::ApiSchema;
+use crate::model::{ApiResponse, TemperatureData, TodoData, TodoRow, TodoSummary};
 
-// 温度传感器API调用
-pub fn fetch_temperature_api(base_url: &str, device_code: &str) -> Option<String> {
-    let client = match reqwest::blocking::Client::builder()
+// 传感器最新读数：温度和同一条记录中的湿度（%）
+pub fn fetch_sensor_reading(base_url: &str, device_code: &str, endpoint: &Endpoint) -> Result<(String, Option<f64>), String> {
+    let client = reqwest::blocking::Client::builder()
         .timeout(Duration::from_secs(5))
         .build()
-    {
-        Ok(c) => c,
-        Err(_) => return None,
+        .map_err(|e| e.to_string())?;
+
+    let request_body = endpoint.render_body(&[("device_code", device_code.to_string())]);
+
+    let url = format!("{}{}", base_url, endpoint.path);
+    let _permit = crate::ratelimit::permit();
+    let resp = client.post(&url)
+        .header("Content-Type", "application/json")
+        .body(request_body)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| http_error("temperature API", e))?;
+    let json = resp.json::<serde_json::Value>().map_err(|e| format!("temperature decode error: {}", e))?;
+    let api_resp = serde_json::from_value::<ApiResponse<TemperatureData>>(endpoint.normalize_temperature(&json))
+        .map_err(|e| format!("temperature decode error: {}", e))?;
+    check_code("temperature API", &api_resp)?;
+    // 跳过缺少温度值的记录
+    api_resp.data.rows
+        .iter()
+        .find_map(|row| row.values.temp.map(|temp| (format!("{:.1}℃", temp), row.values.hum)))
+        .ok_or_else(|| "temperature API returned no readings".to_string())
+}
+
+// 待办事项API调用；by_created 时按 create_time 排序（无法解析的排最后），否则保持服务端顺序
+pub fn fetch_todos_api(base_url: &str, limit: usize, by_created: bool, endpoint: &Endpoint) -> Result<Vec<String>, String> {
+    let mut data = fetch_todo_list(base_url, 0, limit, endpoint)?;
+    if by_created {
+        let created = |row: &crate::model::TodoRow| {
+            let at = chrono::NaiveDateTime::parse_from_str(&row.create_time.replace('T', " "), "%Y-%m-%d %H:%M:%S").ok();
+            (at.is_none(), at)
+        };
+        data.rows.sort_by_key(created);
+    }
+    Ok(display_rows(data))
+}
+
+// 已完成的待办（最多 size 条），用于 termclock todo export
+pub fn fetch_done_todos_api(base_url: &str, size: usize, endpoint: &Endpoint) -> Result<Vec<String>, String> {
+    fetch_todo_list(base_url, 1, size, endpoint).map(display_rows)
+}
+
+// "截止时间 | 任务"，跳过空任务
+fn display_rows(data: TodoData) -> Vec<String> {
+    data.rows.iter().filter(|row| !row.task.is_empty()).map(display_row).collect()
+}
+
+fn display_row(row: &TodoRow) -> String {
+    if row.deadline.is_empty() { row.task.clone() } else { format!("{} | {}", row.deadline, row.task) }
+}
+
+// 添加待办："截止时间 | !优先级 任务" 拆成 {{deadline}} 和 {{task}}，优先级标记保留在任务内容中
+pub fn add_todo_api(base_url: &str, text: &str, schema: &ApiSchema) -> Result<(), String> {
+    let (deadline, task) = match text.split_once(" | ") {
+        Some((deadline, task)) => (deadline.trim(), task.trim()),
+        None => ("", text.trim()),
     };
+    post_todo_write(base_url, &schema.todo_add, &[("task", task.to_string()), ("deadline", deadline.to_string())])
+}
 
-    let request_body = serde_json::json!({
-        "device_code": device_code,
-        "page": {
-            "num": 1,
-            "size": 1
-        }
-    });
+// 完成或删除（endpoint 为 todo_done / todo_remove）：按列表序号或显示内容定位未完成的待办，返回被操作的待办
+pub fn update_todo_api(base_url: &str, index: usize, expected: Option<&str>, schema: &ApiSchema, endpoint: &Endpoint) -> Result<Option<String>, String> {
+    const FIND_PAGE_SIZE: usize = 500;
+    let data = fetch_todo_list(base_url, 0, FIND_PAGE_SIZE, &schema.todos)?;
+    let rows: Vec<&TodoRow> = data.rows.iter().filter(|row| !row.task.is_empty()).collect();
+    let found = match expected {
+        Some(text) => rows.iter().find(|row| display_row(row) == text),
+        None => rows.get(index),
+    };
+    let Some(row) = found else { return Ok(None) };
+    if row.id.is_empty() {
+        return Err("todo API rows have no id (check api_schema.todos.id)".to_string());
+    }
+    post_todo_write(base_url, endpoint, &[("id", row.id.clone())])?;
+    Ok(Some(display_row(row)))
+}
 
-    let url = format!("{}/habitat/raw/list", base_url);
-    match client.post(&url)
+fn post_todo_write(base_url: &str, endpoint: &Endpoint, vars: &[(&str, String)]) -> Result<(), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = format!("{}{}", base_url, endpoint.path);
+    let _permit = crate::ratelimit::permit();
+    let resp = client.post(&url)
         .header("Content-Type", "application/json")
-        .json(&request_body)
+        .body(endpoint.render_body(vars))
         .send()
         .and_then(|r| r.error_for_status())
-    {
-        Ok(resp) => {
-            match resp.json::<ApiResponse<TemperatureData>>() {
-                Ok(api_resp) => {
-                    if api_resp.code == 0 && !api_resp.data.rows.is_empty() {
-                        let temp = api_resp.data.rows[0].values.temp;
-                        Some(format!("{:.1}℃", temp))
-                    } else {
-                        None
-                    }
-                }
-                Err(_) => None,
-            }
-        }
-        Err(_) => None,
+        .map_err(|e| http_error("todo API", e))?;
+    // 空响应体视为成功
+    let text = resp.text().map_err(|e| format!("todo decode error: {}", e))?;
+    if text.trim().is_empty() {
+        return Ok(());
     }
+    let json = serde_json::from_str::<serde_json::Value>(&text).map_err(|e| format!("todo decode error: {}", e))?;
+    let api_resp = serde_json::from_value::<ApiResponse<serde_json::Value>>(endpoint.normalize_status(&json))
+        .map_err(|e| format!("todo decode error: {}", e))?;
+    check_code("todo API", &api_resp)
 }
 
-// 待办事项API调用
-pub fn fetch_todos_api(base_url: &str, limit: usize) -> Option<Vec<String>> {
-    let client = match reqwest::blocking::Client::builder()
+// 待办统计：今日完成数（按 completed_time）与剩余数（待办总数）
+pub fn fetch_todo_summary_api(base_url: &str, endpoint: &Endpoint) -> Result<TodoSummary, String> {
+    const DONE_PAGE_SIZE: usize = 100;
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let pending = fetch_todo_list(base_url, 0, 1, endpoint)?;
+    let done = fetch_todo_list(base_url, 1, DONE_PAGE_SIZE, endpoint)?;
+    let done_today = done
+        .rows
+        .iter()
+        .filter(|row| row.completed_time.as_deref().is_some_and(|t| t.replace('/', "-").starts_with(&today)))
+        .count();
+    Ok(TodoSummary { done_today, remaining: pending.total.max(pending.rows.len() as i64) as usize })
+}
+
+// status: 0-代办 1-完成 2-草稿
+fn fetch_todo_list(base_url: &str, status: i32, size: usize, endpoint: &Endpoint) -> Result<TodoData, String> {
+    let client = reqwest::blocking::Client::builder()
         .timeout(Duration::from_secs(5))
         .build()
-    {
-        Ok(c) => c,
-        Err(_) => return None,
-    };
+        .map_err(|e| e.to_string())?;
 
-    let request_body = serde_json::json!({
-        "status": [0], // 0-代办 1-完成 2-草稿
-        "page": {
-            "num": 1,
-            "size": limit
-        }
-    });
+    let request_body = endpoint.render_body(&[("status", status.to_string()), ("size", size.to_string())]);
 
-    let url = format!("{}/todo/list", base_url);
-    match client.post(&url)
+    let url = format!("{}{}", base_url, endpoint.path);
+    let _permit = crate::ratelimit::permit();
+    let resp = client.post(&url)
         .header("Content-Type", "application/json")
-        .json(&request_body)
+        .body(request_body)
         .send()
         .and_then(|r| r.error_for_status())
-    {
-        Ok(resp) => {
-            match resp.json::<ApiResponse<TodoData>>() {
-                Ok(api_resp) => {
-                    if api_resp.code == 0 {
-                        Some(api_resp.data.rows.into_iter().map(|row| {
-                            format!("{} | {}", row.deadline, row.task)
-                        }).collect())
-                    } else {
-                        None
-                    }
-                }
-                Err(_) => None,
-            }
-        }
-        Err(_) => None,
+        .map_err(|e| http_error("todo API", e))?;
+    let json = resp.json::<serde_json::Value>().map_err(|e| format!("todo decode error: {}", e))?;
+    let api_resp = serde_json::from_value::<ApiResponse<TodoData>>(endpoint.normalize_todos(&json))
+        .map_err(|e| format!("todo decode error: {}", e))?;
+    check_code("todo API", &api_resp)?;
+    Ok(api_resp.data)
+}
+
+// 需要重新登录或更新凭据的业务码 / HTTP 状态码
+const AUTH_EXPIRED_CODES: [i64; 2] = [401, 403];
+const AUTH_EXPIRED: &str = "authorization expired";
+
+// 业务码非 0 时返回服务端的 msg（此前只显示 --）
+fn check_code<T: Default>(api: &str, resp: &ApiResponse<T>) -> Result<(), String> {
+    if resp.code == 0 {
+        return Ok(());
     }
+    let msg = resp.msg.trim();
+    let auth = AUTH_EXPIRED_CODES.contains(&resp.code);
+    Err(match (auth, msg.is_empty()) {
+        (true, true) => format!("{}: {} (code {})", api, AUTH_EXPIRED, resp.code),
+        (true, false) => format!("{}: {}: {} (code {})", api, AUTH_EXPIRED, msg, resp.code),
+        (false, true) => format!("{} code {}", api, resp.code),
+        (false, false) => format!("{}: {} (code {})", api, msg, resp.code),
+    })
 }
 
-// 从配置获取温度数据（优先API，回退到网络服务）
-pub fn fetch_temperature_from_config(config: &crate::model::Config) -> Option<String> {
-    // 优先使用API
-    if let Some(base_url) = &config.api_base_url {
-        if let Some(temp) = fetch_temperature_api(base_url, &config.device_code) {
-            return Some(temp);
-        }
+fn http_error(api: &str, e: reqwest::Error) -> String {
+    match e.status() {
+        Some(status) if AUTH_EXPIRED_CODES.contains(&(status.as_u16() as i64)) => format!("{}: {} (HTTP {})", api, AUTH_EXPIRED, status.as_u16()),
+        _ => format!("{}: {}", api, e),
     }
-    
-    // 检查配置文件中的API设置
-    if let Some(file_cfg) = crate::config::load_yaml_config() {
-        if let Some(base_url) = file_cfg.api_base_url {
-            let device_code = file_cfg.device_code.unwrap_or_else(|| "SENS-FARM01".to_string());
-            if let Some(temp) = fetch_temperature_api(&base_url, &device_code) {
+}
+
+// 授权过期的错误：状态栏醒目显示，并放慢重试
+pub fn is_auth_error(message: &str) -> bool {
+    message.contains(AUTH_EXPIRED)
+}
+
+// 追加到 API 错误日志（每条新错误记录一次），写入失败时忽略
+pub fn log_error(path: &std::path::Path, message: &str) {
+    use std::io::Write;
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{} {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), message);
+    }
+}
+
+// 按 temperature_providers 的顺序获取温度，第一个成功的结果用于显示；
+// 第一个错误写入 error（状态栏显示），成功提供者的湿度写入 humidity
+pub fn fetch_temperature_from_config(config: &crate::model::Config, error: &mut Option<String>, humidity: &mut Option<f64>) -> Option<String> {
+    *error = None;
+    *humidity = None;
+    let max_stale = Duration::from_secs(config.stale_primary_secs);
+    for provider in &config.temperature_providers {
+        let name = provider.name();
+        match provider.fetch(config) {
+            Ok((temp, hum)) => {
+                crate::state::store_result(&config.state_file, &name, &(&temp, hum));
+                *humidity = hum;
                 return Some(temp);
             }
+            Err(e) => {
+                error.get_or_insert(e);
+                // 暂时失败时，stale_primary_secs 内的上次结果优先于后面的提供者
+                // （如按 IP 定位可能得到别的城市的 wttr.in）
+                if let Some(((temp, hum), _)) = crate::state::cached_result::<(String, Option<f64>)>(&config.state_file, &name, max_stale) {
+                    *humidity = hum;
+                    return Some(temp);
+                }
+            }
         }
     }
-    
-    // 最后回退到网络服务
-    let url = "https://wttr.in/?format=%t";
-    let client = match reqwest::blocking::Client::builder()
+    None
+}
+
+// wttr.in 当前气温（location 为空时按 IP 定位）；距上次请求不足 WTTR_MIN_SECS 时使用上次的结果，
+// 重启后也从状态文件读取，不重新请求
+pub fn fetch_wttr(state_file: &Path, location: Option<&str>) -> Result<String, String> {
+    let min = Duration::from_secs(crate::ratelimit::WTTR_MIN_SECS);
+    let key = location.map_or_else(|| "wttr.in".to_string(), |l| format!("wttr.in:{}", l));
+    if let Some((temp, _)) = crate::state::cached_result::<String>(state_file, &key, min) {
+        return Ok(temp);
+    }
+    let temp = crate::ratelimit::cached(&key, min, || fetch_wttr_now(location))?;
+    crate::state::store_result(state_file, &key, &temp);
+    Ok(temp)
+}
+
+pub fn fetch_wttr_now(location: Option<&str>) -> Result<String, String> {
+    // wttr.in 的地名中空格写作 +
+    let url = format!("https://wttr.in/{}?format=%t", location.unwrap_or_default().replace(' ', "+"));
+    let client = reqwest::blocking::Client::builder()
         .timeout(Duration::from_secs(5))
         .build()
-    {
-        Ok(c) => c,
-        Err(_) => return None,
-    };
-    match client.get(url).send().and_then(|r| r.error_for_status()) {
-        Ok(resp) => match resp.text() {
-            Ok(text) => Some(text.trim().replace("°C", "℃")),
-            Err(_) => None,
-        },
-        Err(_) => None,
-    }
+        .map_err(|e| e.to_string())?;
+    let _permit = crate::ratelimit::permit();
+    let text = client.get(&url).send().and_then(|r| r.error_for_status()).and_then(|r| r.text())
+        .map_err(|e| format!("wttr.in: {}", e))?;
+    Ok(text.trim().replace("°C", "℃"))
 }
 
-// 从配置获取待办事项数据（优先API，回退到文件）
-pub fn load_todos_from_config(config: &crate::model::Config) -> Vec<String> {
+// 从配置获取待办事项数据（优先API，回退到文件）；API 错误写入 error
+pub fn load_todos_from_config(config: &crate::model::Config, error: &mut Option<String>) -> Vec<String> {
+    *error = None;
+    // 配置了本地存储时只使用本地存储
+    if let Some(path) = &config.todo_db {
+        return crate::todo_db::TodoDb::open(path)
+            .and_then(|db| db.pending(config.todo_limit))
+            .unwrap_or_else(|e| {
+                *error = Some(format!("todo db: {}", e));
+                Vec::new()
+            });
+    }
     // Try YAML first
     if let Some(cfg) = crate::config::load_yaml_config() {
         // 优先使用API
         if let Some(base_url) = cfg.api_base_url.or_else(|| config.api_base_url.clone()) {
             let limit = cfg.todo_limit.or(config.todo_limit).unwrap_or(4);
-            if let Some(list) = fetch_todos_api(&base_url, limit) { 
-                return list; 
+            match fetch_todos_api(&base_url, limit, config.todo_sort == crate::todo::TodoSort::Created, &config.api_schema.todos) {
+                Ok(list) => return list,
+                Err(e) => *error = Some(e),
             }
         }
         
         // 如果指定了本地文件，加载文件
-        if let Some(path) = cfg.todos_file {
-            if let Ok(content) = std::fs::read_to_string(path) {
-                return content
-                    .lines()
-                    .map(|s| s.trim())
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_string())
-                    .collect();
-            }
+        if let Some(path) = cfg.todos_file
+            && let Ok(content) = std::fs::read_to_string(path)
+        {
+            return crate::todo_store::parse_file(&content);
         }
     }
     
     // 最后回退到默认文件
     const TODOS_FILE: &str = "todos.txt";
     match std::fs::read_to_string(TODOS_FILE) {
-        Ok(content) => content
-            .lines()
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string())
-            .collect(),
+        Ok(content) => crate::todo_store::parse_file(&content),
         Err(_) => Vec::new(),
     }
 }
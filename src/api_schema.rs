@@ -0,0 +1,126 @@
+use serde_json::{json, Value};
+
+use crate::jsonpath::JsonPath;
+
+// 传感器 / 待办 API 的请求格式与响应字段位置。默认与原后端一致；
+// 同一后端的不同部署可调整接口路径、请求体模板和字段的 JSONPath，无需修改代码
+
+#[derive(Debug, Clone)]
+pub struct ApiSchema {
+    pub temperature: Endpoint,
+    pub todos: Endpoint,
+    // 待办写入：添加支持 {{task}}、{{deadline}}，完成和删除支持 {{id}}（列表中该行的 id）
+    pub todo_add: Endpoint,
+    pub todo_done: Endpoint,
+    pub todo_remove: Endpoint,
+}
+
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    // 接在 api_base_url 后面
+    pub path: String,
+    // 请求体模板：温度支持 {{device_code}}，待办支持 {{status}}（0 待办 / 1 完成）和 {{size}}；
+    // 另外支持 {{now}}、{{date}}、{{timestamp}}、{{env.NAME}}
+    pub body: String,
+    // 响应字段名 -> JSONPath；rows 相对于整个响应，其余行字段相对于每一行
+    pub fields: Vec<(&'static str, JsonPath)>,
+}
+
+pub const TEMPERATURE_FIELDS: [(&str, &str); 5] = [
+    ("code", "$.code"),
+    ("msg", "$.msg"),
+    ("rows", "$.data.rows[*]"),
+    ("temp", "$.values.temp"),
+    ("humidity", "$.values.hum"),
+];
+
+pub const TODO_FIELDS: [(&str, &str); 9] = [
+    ("code", "$.code"),
+    ("msg", "$.msg"),
+    ("rows", "$.data.rows[*]"),
+    ("total", "$.data.total"),
+    ("task", "$.task"),
+    ("deadline", "$.deadline"),
+    ("created", "$.create_time"),
+    ("completed", "$.completed_time"),
+    ("id", "$.id"),
+];
+
+// 写入接口只检查业务码
+pub const TODO_WRITE_FIELDS: [(&str, &str); 2] = [("code", "$.code"), ("msg", "$.msg")];
+
+impl Default for ApiSchema {
+    fn default() -> Self {
+        Self {
+            temperature: Endpoint::new(
+                "/habitat/raw/list",
+                r#"{"device_code": "{{device_code}}", "page": {"num": 1, "size": 1}}"#,
+                &TEMPERATURE_FIELDS,
+            ),
+            todos: Endpoint::new("/todo/list", r#"{"status": [{{status}}], "page": {"num": 1, "size": {{size}}}}"#, &TODO_FIELDS),
+            todo_add: Endpoint::new("/todo/add", r#"{"task": "{{task}}", "deadline": "{{deadline}}"}"#, &TODO_WRITE_FIELDS),
+            todo_done: Endpoint::new("/todo/complete", r#"{"id": "{{id}}"}"#, &TODO_WRITE_FIELDS),
+            todo_remove: Endpoint::new("/todo/delete", r#"{"id": "{{id}}"}"#, &TODO_WRITE_FIELDS),
+        }
+    }
+}
+
+impl Endpoint {
+    fn new(path: &str, body: &str, fields: &[(&'static str, &str)]) -> Self {
+        let fields = fields.iter().filter_map(|&(name, path)| Some((name, JsonPath::parse(path)?))).collect();
+        Self { path: path.to_string(), body: body.to_string(), fields }
+    }
+
+    // 替换模板变量；字符串值按 JSON 转义。先展开 {{env.X}} 等通用变量，待办文本中的模板不会被展开
+    pub fn render_body(&self, vars: &[(&str, String)]) -> String {
+        let mut body = crate::source::render_template(&self.body);
+        for (name, value) in vars {
+            let escaped = Value::from(value.as_str()).to_string();
+            body = body.replace(&format!("{{{{{}}}}}", name), &escaped[1..escaped.len() - 1]);
+        }
+        body
+    }
+
+    fn pick(&self, name: &str, root: &Value) -> Value {
+        self.fields
+            .iter()
+            .find(|(n, _)| *n == name)
+            .and_then(|(_, path)| path.select(root).first().map(|v| (*v).clone()))
+            .unwrap_or(Value::Null)
+    }
+
+    fn rows<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        self.fields.iter().find(|(n, _)| *n == "rows").map(|(_, path)| path.select(root)).unwrap_or_default()
+    }
+
+    // 按字段位置整理成原后端的响应结构，再按原来的宽松规则解析
+    pub fn normalize_temperature(&self, json: &Value) -> Value {
+        let rows: Vec<Value> = self
+            .rows(json)
+            .into_iter()
+            .map(|row| json!({"values": {"temp": self.pick("temp", row), "hum": self.pick("humidity", row)}}))
+            .collect();
+        json!({"code": self.pick("code", json), "msg": self.pick("msg", json), "data": {"rows": rows}})
+    }
+
+    pub fn normalize_todos(&self, json: &Value) -> Value {
+        let rows: Vec<Value> = self
+            .rows(json)
+            .into_iter()
+            .map(|row| {
+                json!({
+                    "task": self.pick("task", row),
+                    "deadline": self.pick("deadline", row),
+                    "create_time": self.pick("created", row),
+                    "completed_time": self.pick("completed", row),
+                    "id": self.pick("id", row),
+                })
+            })
+            .collect();
+        json!({"code": self.pick("code", json), "msg": self.pick("msg", json), "data": {"rows": rows, "total": self.pick("total", json)}})
+    }
+
+    pub fn normalize_status(&self, json: &Value) -> Value {
+        json!({"code": self.pick("code", json), "msg": self.pick("msg", json)})
+    }
+}
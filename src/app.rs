@@ -0,0 +1,1580 @@
+use std::time::{Duration, Instant};
+
+use chrono::{Local, Timelike};
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::alarm::{ActiveAlarm, AlarmState};
+use crate::background::BackgroundEffect;
+use crate::countdown::CountdownPhase;
+use crate::markers::{self, CommandProvider, ListProvider, MarkerProvider};
+use crate::upcoming::{self, FeedState};
+use crate::transit::{self, TransitState};
+use crate::temperature::DailyRange;
+use crate::weather::{self, ForecastState, RainAlert};
+use crate::radar::{self, RadarFrame, RadarState};
+use crate::sensors::SensorReading;
+use crate::ble;
+use crate::serial;
+use crate::dht;
+use crate::script::{self, ScriptState};
+use crate::panel::{self, PanelState};
+use crate::provider::TemperatureProvider;
+use crate::power::LowPower;
+use crate::state::SavedTimer;
+use crate::events::EventKind;
+use crate::api::{fetch_temperature_from_config, load_todos_from_config};
+use crate::chime::{self, BeepPattern};
+use crate::gcal::{self, GcalStatus};
+use crate::history::{self, HistoryRecord};
+use crate::idle::IdleMode;
+use crate::ipc::{AlarmSnapshot, IpcCommand, IpcRequest, Push, Snapshot};
+use crate::model::{App, ClockStyle, Config, Meeting, Prompt, View};
+use crate::palette::{self, PaletteCommand};
+use crate::source::{self, SourceState};
+use crate::todo;
+use crate::todo_store::{self, TodoBackend};
+
+pub const FLASH_DURATION: Duration = Duration::from_secs(3);
+const TODOS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const NOTICE_DURATION: Duration = Duration::from_secs(2);
+// 越过整秒边界一点再重绘，避免提前醒来仍显示上一秒
+const FRAME_SLACK: Duration = Duration::from_millis(2);
+// 背景动画、庆祝动画帧间隔
+const BACKGROUND_FRAME: Duration = Duration::from_millis(100);
+// 翻页动画每帧时长（共 3 帧，只在每秒开头播放）
+const FLIP_FRAME: Duration = Duration::from_millis(80);
+const FLIP_FRAMES: u64 = 3;
+// 降水预报刷新间隔（Open-Meteo 分钟级数据每 15 分钟更新）
+const FORECAST_REFRESH_SECS: u64 = 600;
+// 天气状况（强调色）刷新间隔
+const CONDITION_REFRESH_SECS: u64 = 900;
+// 历史页面重新读取历史文件的间隔
+const HEATMAP_REFRESH: Duration = Duration::from_secs(60);
+// 省电模式 auto 时检查电源状态的间隔
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+impl App {
+    pub fn new(config: Config) -> Self {
+        let base_colors = (config.time_color, config.date_color, config.todos_color, config.todo_priority_colors);
+        let mut app = Self {
+            last_temp_fetch: None,
+            cached_temp: None,
+            cached_outdoor_temp: None,
+            cached_humidity: None,
+            temp_range: None,
+            humidity_range: None,
+            todos: Vec::new(),
+            config,
+            last_schedule_minute: None,
+            alarm: None,
+            gcal_status: None,
+            reminded_meeting: None,
+            flash_until: None,
+            muted: false,
+            unmute_at: None,
+            last_todos_refresh: None,
+            last_history_log: None,
+            sound_seq: 0,
+            last_sound: None,
+            play_sounds: true,
+            quit_requested: false,
+            selected_todo: None,
+            notice: None,
+            clipboard: None,
+            sources: Vec::new(),
+            scripts: Vec::new(),
+            panels: Vec::new(),
+            upcoming: Vec::new(),
+            transit: None,
+            ntp: None,
+            time_sync: None,
+            events: Default::default(),
+            forecast: None,
+            condition: None,
+            radar: None,
+            view: View::Clock,
+            last_input: None,
+            started_at: Instant::now(),
+            rotated_at: Instant::now(),
+            radar_area: None,
+            heatmap: None,
+            local_sensors: Default::default(),
+            pushed_reading: None,
+            pushed_todos: None,
+            message: None,
+            mqtt: None,
+            rain_alert_active: false,
+            rain_snoozed_until: None,
+            temp_error: None,
+            todos_error: None,
+            input: None,
+            todo_filter: None,
+            todos_file_mtime: None,
+            todo_summary: None,
+            todo_origins: std::collections::HashMap::new(),
+            offline_since: None,
+            offline_dismissed: false,
+            theme: None,
+            base_colors,
+            paused_at: None,
+            timer: None,
+            display_tz: None,
+            forward_commands: None,
+            data_updated: None,
+            markers: Vec::new(),
+            markers_date: None,
+            background: None,
+            low_power: false,
+            last_power_check: None,
+        };
+        // 使用配置的主题，否则恢复上次选择的主题（主题已从配置中删除时忽略）；恢复时区
+        let state = crate::state::load(&app.config.state_file);
+        app.theme = app.config.theme.clone().or(state.theme);
+        app.display_tz = state.tz.and_then(|tz| tz.parse().ok());
+        app.temp_range = state.temp_range;
+        app.humidity_range = state.humidity_range;
+        app.apply_theme();
+        app
+    }
+
+    // 启动后台数据线程（attach 前端不需要）
+    pub fn start_workers(&mut self) {
+        crate::ratelimit::configure(self.config.max_concurrent_requests);
+        // 重启后，刷新间隔内缓存的首选温度提供者结果直接使用，不立即重新请求
+        let interval = Duration::from_secs(self.config.temp_refresh_interval);
+        if let Some(provider) = self.config.temperature_providers.first()
+            && let Some(((temp, humidity), age)) =
+                crate::state::cached_result::<(String, Option<f64>)>(&self.config.state_file, &provider.name(), interval)
+        {
+            self.cached_temp = Some(temp);
+            self.cached_humidity = humidity;
+            self.last_temp_fetch = Instant::now().checked_sub(age);
+            self.data_updated = chrono::Duration::from_std(age).ok().map(|age| Local::now() - age);
+        }
+        self.gcal_status = self.config.gcal.clone().map(gcal::spawn_worker);
+        self.sources = self
+            .config
+            .custom_sources
+            .iter()
+            .map(|s| (s.name.clone(), source::spawn_worker(s.clone())))
+            .collect();
+        self.scripts = self
+            .config
+            .scripts
+            .iter()
+            .map(|s| (s.name.clone(), script::spawn_worker(s.clone())))
+            .collect();
+        self.panels = self
+            .config
+            .command_panels
+            .iter()
+            .map(|p| (p.title.clone(), panel::spawn_worker(p.clone())))
+            .collect();
+        self.upcoming = self
+            .config
+            .upcoming
+            .iter()
+            .map(|feed| (feed.name.clone(), feed.limit, upcoming::spawn_worker(feed.clone())))
+            .collect();
+        self.transit = self.config.transit.clone().map(transit::spawn_worker);
+        self.ntp = self.config.ntp.clone().map(crate::ntp::spawn_worker);
+        self.time_sync = self.config.time_sync.then(crate::timesync::spawn_worker);
+        if self.config.rain_alert
+            && let Some(location) = self.config.location
+        {
+            let provider = weather::OpenMeteo { base_url: self.config.forecast_url.clone() };
+            let cache = self.config.state_file.clone();
+            self.forecast = Some(weather::spawn_worker(Box::new(provider), location, FORECAST_REFRESH_SECS, cache));
+        }
+        if let (Some(_), Some(location)) = (self.config.weather_accents, self.config.location) {
+            self.condition = Some(weather::spawn_condition_worker(self.config.forecast_url.clone(), location, CONDITION_REFRESH_SECS));
+        }
+        if let (Some(radar), Some(location)) = (self.config.radar.clone(), self.config.location) {
+            self.radar = Some(radar::spawn_worker(radar, location));
+        }
+        if !self.config.ble_sensors.is_empty() {
+            ble::spawn_worker(self.config.ble_sensors.clone(), self.local_sensors.clone());
+        }
+        for port in &self.config.serial {
+            serial::spawn_worker(port.clone(), self.local_sensors.clone());
+        }
+        if let Some(sensor) = self.config.dht22.clone() {
+            dht::spawn_worker(sensor, self.local_sensors.clone());
+        }
+        self.mqtt = self.config.mqtt.as_ref().map(crate::mqtt::Mqtt::connect);
+        self.restore_timer();
+    }
+
+    // Home Assistant：执行发来的控制命令，状态有变化时发布
+    fn run_mqtt(&mut self) {
+        let Some(mqtt) = self.mqtt.clone().filter(|_| self.config.mqtt.as_ref().is_some_and(|m| m.home_assistant)) else {
+            return;
+        };
+        for command in mqtt.take_commands() {
+            match command {
+                crate::mqtt::Command::Mute(true) if !self.is_muted() => self.mute(self.config.mute_minutes),
+                crate::mqtt::Command::Mute(false) => self.unmute(),
+                crate::mqtt::Command::Mute(true) => {}
+                crate::mqtt::Command::Message(text) => self.apply_push(Push::Message { text, secs: None }),
+            }
+        }
+        let muted = self.is_muted();
+        mqtt.publish_state(serde_json::json!({
+            "temperature": self.cached_temp.as_deref().and_then(crate::temperature::parse_celsius),
+            "humidity": self.cached_humidity,
+            "mute": if muted { "ON" } else { "OFF" },
+            "message": self.message().unwrap_or_default(),
+        }));
+    }
+
+    fn publish(&self, event: &str, fields: serde_json::Value) {
+        if let Some(mqtt) = &self.mqtt {
+            mqtt.event(event, fields);
+        }
+    }
+
+    // 距离下一帧的时间：对齐到下一个整秒（秒数字准时跳变，空闲时约 1 fps）；
+    // 显示小数秒时对齐到 100ms/10ms，省电模式对齐到下一分钟；闪烁报时按 500ms 相位、提示到期时提前重绘
+    pub fn frame_delay(&self) -> Duration {
+        let now = Instant::now();
+        let local = Local::now();
+        let nanos = local.second() as u64 * 1_000_000_000 + local.timestamp_subsec_nanos().min(999_999_999) as u64;
+        let period = match self.config.show_subseconds.period() {
+            Some(period) if self.paused_at.is_none() && self.show_seconds() => period.as_nanos() as u64,
+            _ if self.low_power && !self.counting_seconds() => 60_000_000_000,
+            _ => 1_000_000_000,
+        };
+        let mut delay = Duration::from_nanos(period - nanos % period) + FRAME_SLACK;
+        if self.background_active() && self.paused_at.is_none() {
+            delay = delay.min(BACKGROUND_FRAME);
+        }
+        if !self.low_power && matches!(self.countdown(), Some(CountdownPhase::Celebrating { .. })) {
+            delay = delay.min(BACKGROUND_FRAME);
+        }
+        if self.flip_frame().is_some() {
+            let frame = FLIP_FRAME.as_nanos() as u64;
+            delay = delay.min(Duration::from_nanos(frame - nanos % frame) + FRAME_SLACK);
+        }
+        if let Some(until) = self.flash_until.filter(|&t| t > now) {
+            let phase = Duration::from_millis(((until - now).as_millis() % 500) as u64);
+            delay = delay.min(phase + FRAME_SLACK);
+        }
+        if let Some((_, until)) = &self.notice
+            && *until > now
+        {
+            delay = delay.min(*until - now);
+        }
+        if let Some((_, Some(until))) = &self.message
+            && *until > now
+        {
+            delay = delay.min(*until - now);
+        }
+        delay
+    }
+
+    // 特殊时刻倒计时的当前阶段
+    pub fn countdown(&self) -> Option<CountdownPhase> {
+        crate::countdown::phase(&self.config.countdowns, self.now(), self.config.countdown_celebrate_secs)
+    }
+
+    // 低带宽和省电模式不显示秒
+    pub fn show_seconds(&self) -> bool {
+        !self.config.low_bandwidth && !self.low_power
+    }
+
+    // 省电模式下仍需每秒重绘：计时器和倒计时最后一分钟显示剩余秒数
+    fn counting_seconds(&self) -> bool {
+        self.timer.is_some() || matches!(self.countdown(), Some(CountdownPhase::Counting { .. }))
+    }
+
+    // 省电模式：按配置开关；auto 时每分钟检查一次是否使用电池供电。
+    // 后台数据线程通过 power::stretch 读取当前状态
+    pub fn update_power(&mut self) {
+        let due = self.last_power_check.is_none_or(|t| t.elapsed() >= POWER_CHECK_INTERVAL);
+        self.low_power = match self.config.low_power {
+            LowPower::On => true,
+            LowPower::Off => false,
+            LowPower::Auto if due => {
+                self.last_power_check = Some(Instant::now());
+                crate::power::on_battery()
+            }
+            LowPower::Auto => self.low_power,
+        };
+        crate::power::set_active(self.low_power);
+    }
+
+    // 背景动画：低带宽、省电模式和 SSH 会话中自动关闭（每帧都要重绘大片区域）
+    pub fn background_active(&self) -> bool {
+        self.config.background_effect != BackgroundEffect::None
+            && !self.config.low_bandwidth
+            && !self.low_power
+            && std::env::var_os("SSH_CONNECTION").is_none()
+            && std::env::var_os("SSH_TTY").is_none()
+    }
+
+    // 翻页动画的当前帧（0..3），不在动画期间时为 None；暂停、低带宽和省电模式下不播放
+    pub fn flip_frame(&self) -> Option<usize> {
+        if self.config.clock_style != ClockStyle::Flip || self.paused_at.is_some() || self.config.low_bandwidth || self.low_power {
+            return None;
+        }
+        let frame = Local::now().timestamp_subsec_millis() as u64 / FLIP_FRAME.as_millis() as u64;
+        (frame < FLIP_FRAMES).then_some(frame as usize)
+    }
+
+    pub fn temperature(&self) -> String {
+        self.cached_temp.clone().unwrap_or_else(|| "--".into())
+    }
+
+    // 引擎主逻辑：数据刷新、报时、闹钟、会议提醒、历史记录
+    pub fn tick(&mut self) {
+        self.update_power();
+        // 暂停时不刷新显示的数据，报时和闹钟照常
+        if self.paused_at.is_none() {
+            self.refresh_data();
+        }
+        self.refresh_markers();
+        let had_alarm = self.alarm.is_some();
+        self.run_timer();
+        self.run_schedules();
+        self.run_rain_alert();
+        self.run_alarm();
+        self.run_meeting_reminder();
+        self.run_mqtt();
+        self.track_errors();
+        self.log_history();
+        self.run_auto_rotate();
+        // 闹钟响起时自动恢复
+        if !had_alarm && self.alarm.is_some() {
+            self.paused_at = None;
+        }
+    }
+
+    // 界面显示的时间（暂停时为暂停时刻）
+    pub fn now(&self) -> chrono::DateTime<Local> {
+        self.paused_at.unwrap_or_else(Local::now)
+    }
+
+    // 时钟显示的时间（:tz 设置的时区）
+    pub fn display_time(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        match self.display_tz {
+            Some(tz) => self.now().with_timezone(&tz).fixed_offset(),
+            None => self.now().fixed_offset(),
+        }
+    }
+
+    // 多时钟中某个时钟的时间（未设置时区的沿用 display_time）
+    pub fn clock_time(&self, tz: Option<chrono_tz::Tz>) -> chrono::DateTime<chrono::FixedOffset> {
+        match tz {
+            Some(tz) => self.now().with_timezone(&tz).fixed_offset(),
+            None => self.display_time(),
+        }
+    }
+
+    // 状态栏左侧的按键提示（随当前状态变化）
+    pub fn key_hints(&self) -> &'static str {
+        match &self.input {
+            Some((Prompt::Command, _)) => "Tab complete · Enter run · Esc cancel",
+            Some(_) => "Enter confirm · Esc cancel",
+            None if self.alarm.is_some() => "Enter dismiss · s snooze",
+            None if self.rain_alert().is_some() => "s snooze rain alert · q quit · : commands",
+            None if self.paused_at.is_some() => "space resume · q quit",
+            None if self.selected_todo.is_some() => "x done · Del delete · y copy · j/k move",
+            None => "q quit · r refresh · : commands · t theme · space pause · / filter · e events · h history",
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused_at = match self.paused_at {
+            Some(_) => {
+                self.refresh_now();
+                None
+            }
+            None => Some(Local::now()),
+        };
+    }
+
+    // 重新加载配置（SIGHUP），保留运行状态
+    pub fn reload_config(&mut self, config: Config) {
+        let history_enabled = self.config.history_enabled;
+        let low_bandwidth = self.config.low_bandwidth;
+        let low_power = self.config.low_power;
+        let ascii = self.config.ascii;
+        self.config = config;
+        // 守护进程强制开启的历史记录、命令行开启的低带宽、省电和 ASCII 模式不因重载而关闭
+        self.config.history_enabled |= history_enabled;
+        self.config.low_bandwidth |= low_bandwidth;
+        self.config.ascii |= ascii;
+        if low_power == LowPower::On {
+            self.config.low_power = LowPower::On;
+        }
+        self.last_power_check = None;
+        self.base_colors = (self.config.time_color, self.config.date_color, self.config.todos_color, self.config.todo_priority_colors);
+        if let Some(name) = &self.config.theme {
+            self.theme = Some(name.clone());
+        }
+        self.apply_theme();
+        self.markers_date = None;
+        self.refresh_now();
+    }
+
+    // 每天（及重载配置后）重新计算时刻标记
+    fn refresh_markers(&mut self) {
+        let today = Local::now().date_naive();
+        if self.markers_date == Some(today) {
+            return;
+        }
+        self.markers_date = Some(today);
+        let list = ListProvider { specs: &self.config.markers, location: self.config.location };
+        let command = self.config.markers_command.as_deref().map(|command| CommandProvider { command });
+        let mut providers: Vec<&dyn MarkerProvider> = vec![&list];
+        if let Some(command) = &command {
+            providers.push(command);
+        }
+        let (markers, errors) = markers::load(&providers, today);
+        self.markers = markers;
+        if let Some(e) = errors.into_iter().next() {
+            self.notify(e);
+        }
+    }
+
+    // 按当前主题设置颜色，未设置的颜色沿用配置文件；主题带状态颜色时，待办优先级也改用 alert / warn / info
+    fn apply_theme(&mut self) {
+        let (time, date, todos, priorities) = self.base_colors;
+        let theme = self.theme.as_ref().and_then(|name| crate::theme::find(&self.config.themes, name));
+        if theme.is_none() {
+            self.theme = None;
+        }
+        let theme = theme.as_ref();
+        self.config.time_color = theme.and_then(|t| t.time_color).unwrap_or(time);
+        self.config.date_color = theme.and_then(|t| t.date_color).unwrap_or(date);
+        self.config.todos_color = theme.and_then(|t| t.todos_color).unwrap_or(todos);
+        let palette = theme.and_then(|t| t.palette);
+        self.config.palette = palette.unwrap_or_default();
+        self.config.todo_priority_colors = palette.map_or(priorities, |p| [p.alert, p.warn, p.info]);
+    }
+
+    // 切换主题并写入状态文件；None 恢复配置文件中的颜色
+    pub fn set_theme(&mut self, name: Option<String>) {
+        self.theme = name;
+        self.apply_theme();
+        let theme = self.theme.clone();
+        let label = theme.clone().unwrap_or_else(|| "default".to_string());
+        match crate::state::update(&self.config.state_file, |state| state.theme = theme) {
+            Ok(()) => self.notify(format!("theme: {}", label)),
+            Err(e) => self.notify(format!("theme: {} (not saved: {})", label, e)),
+        }
+    }
+
+    // t 键：配置文件颜色 -> 各主题 -> 配置文件颜色
+    pub fn cycle_theme(&mut self) {
+        let themes = &self.config.themes;
+        let next = match self.theme.as_ref().and_then(|name| themes.iter().position(|t| &t.name == name)) {
+            Some(i) => themes.get(i + 1),
+            None => themes.first(),
+        };
+        self.set_theme(next.map(|t| t.name.clone()));
+    }
+
+    // 立即刷新待办和温度
+    pub fn refresh_now(&mut self) {
+        self.last_todos_refresh = None;
+        self.last_temp_fetch = None;
+    }
+
+    fn refresh_data(&mut self) {
+        let now = Instant::now();
+        let temp_error = self.temp_error.clone();
+        let todos_error = self.todos_error.clone();
+        // 离线或授权过期时降低请求频率，任一请求成功后恢复；省电模式下间隔拉长
+        let slow = |interval: Duration| match self.is_offline() || self.auth_expired() {
+            true => crate::power::stretch(interval).max(Duration::from_secs(self.config.offline_retry_secs)),
+            false => crate::power::stretch(interval),
+        };
+        let temp_fetch_interval = slow(Duration::from_secs(self.config.temp_refresh_interval));
+        let todos_refresh_interval = slow(TODOS_REFRESH_INTERVAL);
+        if self.last_temp_fetch.is_none_or(|ts| now.duration_since(ts) >= temp_fetch_interval) {
+            self.cached_temp = match self.pushed_reading().or_else(|| self.gpio_reading()) {
+                Some(reading) => {
+                    self.temp_error = reading.error;
+                    self.cached_humidity = reading.humidity;
+                    Some(reading.temp.map_or_else(|| "--".to_string(), |t| format!("{:.1}℃", t)))
+                }
+                None => Some(fetch_temperature_from_config(&self.config, &mut self.temp_error, &mut self.cached_humidity).unwrap_or_else(|| "--".to_string())),
+            };
+            self.last_temp_fetch = Some(now);
+            if self.temp_error.is_none() && self.cached_temp.as_deref() != Some("--") {
+                self.data_updated = Some(Local::now());
+            }
+            if self.config.api_base_url.is_some() {
+                self.record_network(self.temp_error.is_none());
+            }
+            // 对比模式：室内只用传感器（不回退到 wttr.in），室外单独获取
+            if self.config.compare_indoor_outdoor {
+                if self.temp_error.is_some() {
+                    self.cached_temp = Some("--".to_string());
+                }
+                // 提供者链中配置了 wttr.in 地名时使用该地名
+                let location = self.config.temperature_providers.iter().find_map(|p| match p {
+                    TemperatureProvider::Wttr { location } => location.as_deref(),
+                    _ => None,
+                });
+                self.cached_outdoor_temp =
+                    Some(crate::api::fetch_wttr(&self.config.state_file, location).unwrap_or_else(|_| "--".to_string()));
+            }
+            self.record_temp_range();
+            self.record_humidity_range();
+        }
+        // 待办文件被外部修改时立即重新加载
+        let mtime = todo_store::file_mtime(&self.config);
+        if mtime != self.todos_file_mtime {
+            self.todos_file_mtime = mtime;
+            self.last_todos_refresh = None;
+        }
+        if self.last_todos_refresh.is_none_or(|ts| now.duration_since(ts) >= todos_refresh_interval) {
+            let mut todos = if let Some(pushed) = &self.pushed_todos {
+                self.todo_origins.clear();
+                self.todos_error = None;
+                pushed.clone()
+            } else if self.config.todo_sources.is_empty() {
+                self.todo_origins.clear();
+                load_todos_from_config(&self.config, &mut self.todos_error)
+            } else {
+                let merged = todo_store::load_merged(&self.config.todo_sources, self.config.todo_limit, &mut self.todos_error);
+                self.todo_origins = merged.iter().cloned().collect();
+                merged.into_iter().map(|(t, _)| t).collect()
+            };
+            todo::sort_todos(&mut todos, self.config.todo_group, self.config.todo_sort);
+            self.set_todos(todos);
+            self.todo_summary = if self.config.todo_summary { self.load_todo_summary() } else { None };
+            self.last_todos_refresh = Some(now);
+            if self.todos_error.is_none() {
+                self.data_updated = Some(Local::now());
+            }
+            if self.uses_todo_api() {
+                self.record_network(self.todos_error.is_none());
+            }
+        }
+        for (before, after) in [(temp_error, self.temp_error.clone()), (todos_error, self.todos_error.clone())] {
+            if let Some(error) = after.filter(|e| before.as_ref() != Some(e)) {
+                crate::api::log_error(&self.api_log_path(), &error);
+            }
+        }
+    }
+
+    // API 错误日志与状态文件放在同一目录
+    fn api_log_path(&self) -> std::path::PathBuf {
+        self.config.state_file.with_file_name("api.log")
+    }
+
+    // 温度或待办 API 返回授权过期
+    pub fn auth_expired(&self) -> bool {
+        [&self.temp_error, &self.todos_error].into_iter().flatten().any(|e| crate::api::is_auth_error(e))
+    }
+
+    fn uses_todo_api(&self) -> bool {
+        let is_api = |b: &TodoBackend| matches!(b, TodoBackend::Api { .. });
+        match self.config.todo_sources.is_empty() {
+            true => is_api(&TodoBackend::from_config(&self.config)),
+            false => self.config.todo_sources.iter().any(|s| is_api(&s.backend)),
+        }
+    }
+
+    // 记录网络请求结果：连续失败的起始时间，成功则清除
+    fn record_network(&mut self, ok: bool) {
+        if ok {
+            self.offline_since = None;
+            self.offline_dismissed = false;
+        } else if self.offline_since.is_none() {
+            self.offline_since = Some(Local::now());
+        }
+    }
+
+    // 所有网络数据源连续失败超过 offline_after_mins
+    pub fn is_offline(&self) -> bool {
+        let Some(after) = i64::try_from(self.config.offline_after_mins).ok().and_then(chrono::Duration::try_minutes) else { return false };
+        self.offline_since.is_some_and(|since| Local::now() - since >= after)
+    }
+
+    // 离线横幅：未被关闭时显示离线起始时间
+    pub fn offline_banner(&self) -> Option<chrono::DateTime<Local>> {
+        self.offline_since.filter(|_| self.is_offline() && !self.offline_dismissed)
+    }
+
+    pub fn dismiss_offline_banner(&mut self) {
+        self.offline_dismissed = true;
+    }
+
+    // 多个来源时合计各来源的统计（不提供统计的来源跳过）
+    fn load_todo_summary(&self) -> Option<crate::model::TodoSummary> {
+        if self.config.todo_sources.is_empty() {
+            return TodoBackend::from_config(&self.config).summary().ok().flatten();
+        }
+        self.config
+            .todo_sources
+            .iter()
+            .filter_map(|s| s.backend.summary().ok().flatten())
+            .reduce(|a, b| crate::model::TodoSummary { done_today: a.done_today + b.done_today, remaining: a.remaining + b.remaining })
+    }
+
+    // Scheduled alarms and chimes: checked at second 0, at most once per minute
+    fn run_schedules(&mut self) {
+        let now = Local::now();
+        let minute_key = now.timestamp() / 60;
+        if now.second() != 0 || self.last_schedule_minute == Some(minute_key) {
+            return;
+        }
+        self.last_schedule_minute = Some(minute_key);
+        if let Some(alarm) = self.config.alarms.iter().find(|a| a.cron.matches(&now)) {
+            let label = alarm.label.clone();
+            self.start_alarm(label);
+        }
+        if now.minute() == 0
+            && let Some(command) = &self.config.on_hour_command
+        {
+            let muted = self.muted;
+            crate::hooks::run(command, &[
+                ("HOUR", now.hour().to_string()),
+                ("TIME", now.format("%H:%M").to_string()),
+                ("DATE", now.format("%Y-%m-%d").to_string()),
+                ("MUTED", u8::from(muted).to_string()),
+            ]);
+        }
+        if self.config.chime_enabled {
+            self.run_chime_rules(now);
+        }
+        // 每日时刻标记：单独设置了提示音或开启 markers_chime 时报时
+        let marker = self.markers.iter().find(|m| m.time.hour() == now.hour() && m.time.minute() == now.minute()).cloned();
+        if let Some(marker) = marker
+            && (marker.pattern.is_some() || self.config.markers_chime)
+            && !self.is_muted()
+        {
+            let pattern = marker
+                .pattern
+                .clone()
+                .or_else(|| self.config.chime_pattern.clone())
+                .unwrap_or_else(|| BeepPattern::parse("short short").expect("valid pattern"));
+            let name = marker.name.clone();
+            self.publish("marker", serde_json::json!({"name": name}));
+            self.events.push(EventKind::Chime, name.clone());
+            if self.config.chime_visual {
+                self.flash_until = Some(Instant::now() + FLASH_DURATION);
+            }
+            if self.config.chime_audible {
+                self.sound(pattern);
+            }
+            self.notify(name);
+        }
+    }
+
+    // 本分钟匹配的报时规则：消息和命令在静音时也执行，提示音和闪烁每分钟最多一次（第一条 sound 规则）
+    fn run_chime_rules(&mut self, now: chrono::DateTime<Local>) {
+        let rules: Vec<crate::model::ChimeRule> = self.config.chimes.iter().filter(|r| r.cron.matches(&now)).cloned().collect();
+        let mut chimed = self.is_muted();
+        for rule in rules {
+            if let Some(message) = rule.message {
+                self.events.push(EventKind::Chime, message.clone());
+                self.notify(message);
+            }
+            if let Some(command) = &rule.command {
+                crate::hooks::run(command, &[
+                    ("HOUR", now.hour().to_string()),
+                    ("MINUTE", now.minute().to_string()),
+                    ("TIME", now.format("%H:%M").to_string()),
+                    ("DATE", now.format("%Y-%m-%d").to_string()),
+                    ("MUTED", u8::from(self.muted).to_string()),
+                ]);
+            }
+            if chimed || !rule.sound {
+                continue;
+            }
+            chimed = true;
+            let pattern = rule
+                .pattern
+                .or_else(|| self.config.chime_pattern.clone())
+                .unwrap_or_else(|| chime::hour_pattern(now.hour()));
+            self.publish("chime", serde_json::json!({"hour": now.hour(), "minute": now.minute()}));
+            self.events.push(EventKind::Chime, format!("Chime {}", now.format("%H:%M")));
+            if rule.visual.unwrap_or(self.config.chime_visual) {
+                self.flash_until = Some(Instant::now() + FLASH_DURATION);
+            }
+            if self.config.chime_audible {
+                self.sound(pattern);
+            }
+        }
+    }
+
+    // 闹钟或倒计时开始响铃：发布 MQTT 事件并运行 on_alarm_command
+    fn start_alarm(&mut self, label: String) {
+        self.publish("alarm", serde_json::json!({"label": label}));
+        self.events.push(EventKind::Alarm, label.clone());
+        if let Some(command) = &self.config.on_alarm_command {
+            let now = Local::now();
+            let muted = self.muted;
+            crate::hooks::run(command, &[
+                ("ALARM_LABEL", label.clone()),
+                ("TIME", now.format("%H:%M").to_string()),
+                ("DATE", now.format("%Y-%m-%d").to_string()),
+                ("MUTED", u8::from(muted).to_string()),
+            ]);
+        }
+        self.alarm = Some(ActiveAlarm::new(label));
+    }
+
+    // Active alarm: re-ring while ringing, re-fire once a snooze expires
+    fn run_alarm(&mut self) {
+        let muted = self.is_muted();
+        if let Some(alarm) = self.alarm.as_mut()
+            && alarm.tick(Instant::now())
+            && !muted
+        {
+            self.sound(self.config.alarm_pattern.clone());
+        }
+    }
+
+    // Pre-meeting reminder: once per meeting, N minutes before it starts
+    fn run_meeting_reminder(&mut self) {
+        if let Some(gcal) = &self.config.gcal
+            && let Some(meeting) = self.next_meeting()
+            && self.reminded_meeting.as_deref() != Some(meeting.id.as_str())
+            && (meeting.start - Local::now()).num_seconds() <= i64::try_from(gcal.remind_minutes.saturating_mul(60)).unwrap_or(i64::MAX)
+        {
+            let pattern = gcal.pattern.clone();
+            self.events.push(EventKind::Alert, format!("Meeting {} at {}", meeting.title, meeting.start.format("%H:%M")));
+            self.reminded_meeting = Some(meeting.id);
+            if !self.is_muted() {
+                if self.config.chime_visual {
+                    self.flash_until = Some(Instant::now() + FLASH_DURATION);
+                }
+                self.sound(pattern);
+            }
+        }
+    }
+
+    // 更新当天温度范围，有变化时写入状态文件（跨天自动重新开始）
+    fn record_temp_range(&mut self) {
+        let Some(temp) = self.cached_temp.as_deref().and_then(crate::temperature::parse_celsius) else { return };
+        if !self.config.temp_min_max || !DailyRange::record(&mut self.temp_range, Local::now().date_naive(), temp) {
+            return;
+        }
+        let range = self.temp_range;
+        let _ = crate::state::update(&self.config.state_file, |state| state.temp_range = range);
+    }
+
+    // 更新当天湿度范围，有变化时写入状态文件
+    fn record_humidity_range(&mut self) {
+        let Some(humidity) = self.cached_humidity else { return };
+        if self.config.humidity_gauge.is_none() || !DailyRange::record(&mut self.humidity_range, Local::now().date_naive(), humidity) {
+            return;
+        }
+        let range = self.humidity_range;
+        let _ = crate::state::update(&self.config.state_file, |state| state.humidity_range = range);
+    }
+
+    // 今天的湿度范围
+    pub fn today_humidity_range(&self) -> Option<DailyRange> {
+        self.humidity_range.filter(|r| r.date == Local::now().date_naive())
+    }
+
+    // 今天的温度范围（昨天的记录在午夜后不再显示）
+    pub fn today_temp_range(&self) -> Option<DailyRange> {
+        self.temp_range.filter(|r| self.config.temp_min_max && r.date == Local::now().date_naive())
+    }
+
+    // v 键：时钟页面与雷达页面之间切换
+    pub fn toggle_radar_view(&mut self) {
+        if self.config.radar.is_none() {
+            return self.notify("radar is not configured".to_string());
+        }
+        self.view = match self.view {
+            View::Radar => View::Clock,
+            _ => View::Radar,
+        };
+    }
+
+    // e 键：事件页面
+    pub fn toggle_events_view(&mut self) {
+        self.view = match self.view {
+            View::Events => View::Clock,
+            _ => View::Events,
+        };
+    }
+
+    // h 键：历史页面（温度热力图）
+    pub fn toggle_history_view(&mut self) {
+        self.view = match self.view {
+            View::History => View::Clock,
+            _ => View::History,
+        };
+    }
+
+    // 历史页面显示时每分钟在后台重新读取一次历史文件
+    pub fn refresh_heatmap(&mut self) {
+        if self.heatmap.as_ref().is_some_and(|(at, _)| at.elapsed() < HEATMAP_REFRESH) {
+            return;
+        }
+        let state = self.heatmap.take().map(|(_, state)| state).unwrap_or_default();
+        history::load_heatmap(self.config.history_file.clone(), state.clone());
+        self.heatmap = Some((Instant::now(), state));
+    }
+
+    pub fn heatmap(&self) -> Option<history::Heatmap> {
+        self.heatmap.as_ref().and_then(|(_, state)| state.lock().ok()?.clone())
+    }
+
+    // 按键和鼠标操作：暂停自动轮换 grace 秒
+    pub fn note_input(&mut self) {
+        self.last_input = Some(Instant::now());
+    }
+
+    // 空闲变暗：超过 idle_dim 设置的时间没有操作时的模式（闹钟响起时不变暗）
+    pub fn idle_mode(&self) -> Option<IdleMode> {
+        let idle = self.config.idle_dim?;
+        let since = self.last_input.unwrap_or(self.started_at);
+        (since.elapsed().as_secs() >= idle.after_secs && self.alarm.is_none()).then_some(idle.mode)
+    }
+
+    // 自动轮换：无人操作时每 interval 秒切换到列表中的下一个页面（跳过未配置的雷达页面）
+    pub fn run_auto_rotate(&mut self) {
+        let Some(rotate) = &self.config.auto_rotate else { return };
+        let (interval, grace) = (Duration::from_secs(rotate.interval_secs), Duration::from_secs(rotate.grace_secs));
+        if self.rotated_at.elapsed() < interval || self.last_input.is_some_and(|at| at.elapsed() < grace) {
+            return;
+        }
+        let pages: Vec<View> = rotate.pages.iter().copied().filter(|&v| v != View::Radar || self.config.radar.is_some()).collect();
+        // 当前页面不在列表中时从第一个开始
+        let next = match pages.iter().position(|&v| v == self.view) {
+            Some(i) => pages[(i + 1) % pages.len()],
+            None => match pages.first() {
+                Some(&first) => first,
+                None => return,
+            },
+        };
+        self.view = next;
+        self.rotated_at = Instant::now();
+    }
+
+    pub fn radar_state(&self) -> Option<RadarState> {
+        self.radar.as_ref().and_then(|s| s.lock().ok().map(|s| s.clone()))
+    }
+
+    pub fn radar_frame(&self) -> Option<RadarFrame> {
+        match self.radar_state()? {
+            RadarState::Ready(frame) => Some(frame),
+            _ => None,
+        }
+    }
+
+    // 推送的温度在 STALE_AFTER_SECS 内优先于其他来源
+    fn pushed_reading(&self) -> Option<SensorReading> {
+        let reading = self.pushed_reading.as_ref()?;
+        let age = Local::now() - reading.updated?;
+        (age.num_seconds() < crate::sensors::STALE_AFTER_SECS).then(|| reading.clone())
+    }
+
+    // 当前显示的推送消息（已过期的不显示）
+    pub fn message(&self) -> Option<&str> {
+        let (text, until) = self.message.as_ref()?;
+        until.is_none_or(|t| Instant::now() < t).then_some(text.as_str())
+    }
+
+    fn apply_push(&mut self, push: Push) {
+        match push {
+            Push::Temperature { temp, humidity } => {
+                self.pushed_reading =
+                    Some(SensorReading { label: "push".to_string(), temp: Some(temp), humidity, updated: Some(Local::now()), error: None });
+                self.last_temp_fetch = None;
+            }
+            Push::Todos(todos) => {
+                self.pushed_todos = todos;
+                self.last_todos_refresh = None;
+            }
+            Push::Message { text, secs } => {
+                let text = text.trim().to_string();
+                if !text.is_empty() {
+                    self.events.push(EventKind::Alert, format!("Message: {}", text));
+                }
+                self.message = (!text.is_empty()).then(|| (text, secs.and_then(|s| Instant::now().checked_add(Duration::from_secs(s)))));
+            }
+        }
+    }
+
+    // 配置了 DHT22 而没有传感器 API 时，主温度直接使用 GPIO 读数，不访问网络
+    fn gpio_reading(&self) -> Option<SensorReading> {
+        let label = &self.config.dht22.as_ref().filter(|_| self.config.api_base_url.is_none())?.label;
+        self.local_sensor_readings().into_iter().find(|r| &r.label == label)
+    }
+
+    // 本地传感器（蓝牙温湿度计、串口、DHT22）的最新读数，按配置顺序
+    pub fn local_sensor_readings(&self) -> Vec<SensorReading> {
+        self.local_sensors.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    // 当前的降水提醒（推迟期间为 None）
+    pub fn rain_alert(&self) -> Option<RainAlert> {
+        if !self.config.rain_alert || self.rain_snoozed_until.is_some_and(|until| Local::now() < until) {
+            return None;
+        }
+        let state = self.forecast.as_ref()?.lock().ok()?.clone();
+        let ForecastState::Ready(slots) = state else { return None };
+        let lookahead = i64::try_from(self.config.rain_alert_minutes).unwrap_or(i64::MAX);
+        weather::next_rain(&slots, Local::now(), lookahead, self.config.rain_threshold_mm)
+    }
+
+    // 降水提醒出现时提示一次（可选提示音）
+    fn run_rain_alert(&mut self) {
+        let alert = self.rain_alert();
+        let starting = alert.is_some() && !self.rain_alert_active;
+        self.rain_alert_active = alert.is_some();
+        let Some(alert) = alert.filter(|_| starting) else { return };
+        if self.config.rain_alert_chime && self.config.chime_audible && !self.is_muted() {
+            let pattern = self.config.chime_pattern.clone().unwrap_or_else(|| BeepPattern::parse("short short short").expect("valid pattern"));
+            self.sound(pattern);
+        }
+        let text = weather::describe(&alert, Local::now());
+        self.events.push(EventKind::Alert, text.trim_start_matches('☂').trim().to_string());
+        self.notify(text);
+    }
+
+    // 新出现的数据源错误和时间同步问题记入事件记录
+    fn track_errors(&mut self) {
+        let mut errors: Vec<String> = self.api_error().into_iter().collect();
+        let named = |name: &str, error: Option<&String>| error.map(|e| format!("{}: {}", name, e));
+        errors.extend(self.source_states().iter().filter_map(|(name, s)| named(name, match s { SourceState::Error(e) => Some(e), _ => None })));
+        errors.extend(self.script_states().iter().filter_map(|(name, s)| named(name, match s { ScriptState::Error(e) => Some(e), _ => None })));
+        errors.extend(self.panel_states().iter().filter_map(|(name, s)| named(name, match s { PanelState::Error(e) => Some(e), _ => None })));
+        errors.extend(self.upcoming_states().iter().filter_map(|(name, _, s)| named(name, match s { FeedState::Error(e) => Some(e), _ => None })));
+        if let Some(state) = self.transit_state() {
+            errors.extend(named("transit", state.error.as_ref()));
+        }
+        // NTP 错误已带服务器前缀
+        errors.extend(self.ntp_state().and_then(|s| s.error));
+        if self.is_offline() {
+            errors.push("Offline: network data sources are failing".to_string());
+        }
+        if self.time_synced() == Some(false) {
+            errors.push("System clock is not synchronized".to_string());
+        }
+        if let (Some(_), Some(ntp)) = (self.clock_drift(), &self.config.ntp) {
+            errors.push(format!("System clock is off by more than {} ms", ntp.warn_ms));
+        }
+        self.events.track_errors(errors);
+    }
+
+    fn log_history(&mut self) {
+        if !self.config.history_enabled {
+            return;
+        }
+        let interval = Duration::from_secs(self.config.history_interval);
+        if self.last_history_log.is_some_and(|ts| ts.elapsed() < interval) {
+            return;
+        }
+        self.last_history_log = Some(Instant::now());
+        let record = HistoryRecord {
+            ts: Local::now(),
+            temp: self.cached_temp.as_deref().and_then(crate::temperature::parse_celsius),
+            humidity: self.cached_humidity,
+            todos: self.todos.len(),
+            done_today: self.todo_summary.map(|s| s.done_today),
+        };
+        let _ = history::append(&self.config.history_file, &record);
+    }
+
+    // 发声：本地终端播放，并记录供 attach 前端重放
+    fn sound(&mut self, pattern: BeepPattern) {
+        if self.play_sounds {
+            pattern.play();
+        }
+        self.sound_seq += 1;
+        self.last_sound = Some(pattern);
+    }
+
+    pub fn next_meeting(&self) -> Option<Meeting> {
+        let status = self.gcal_status.as_ref()?.lock().ok()?;
+        match &*status {
+            GcalStatus::Ready(meeting) => meeting.clone(),
+            _ => None,
+        }
+    }
+
+    // Do-not-disturb: auto-unmutes once the optional deadline passes
+    pub fn is_muted(&mut self) -> bool {
+        if self.muted && self.unmute_at.is_some_and(|t| Instant::now() >= t) {
+            self.unmute();
+        }
+        self.muted
+    }
+
+    pub fn mute(&mut self, minutes: Option<u64>) {
+        self.muted = true;
+        // 超出可表示范围时按不限时静音处理
+        self.unmute_at = minutes.and_then(|m| Instant::now().checked_add(Duration::from_secs(m.checked_mul(60)?)));
+        self.publish("mute", serde_json::json!({"minutes": minutes}));
+    }
+
+    pub fn unmute(&mut self) {
+        if self.muted {
+            self.publish("unmute", serde_json::json!({}));
+        }
+        self.muted = false;
+        self.unmute_at = None;
+    }
+
+    pub fn toggle_mute(&mut self) {
+        if self.is_muted() {
+            self.unmute();
+        } else {
+            self.mute(self.config.mute_minutes);
+        }
+    }
+
+    // s 键：响铃中的闹钟优先，否则推迟降水提醒
+    pub fn snooze(&mut self) {
+        if self.alarm.is_some() {
+            self.snooze_alarm();
+        } else if self.rain_alert().is_some() {
+            let minutes = self.config.snooze_minutes;
+            let until = i64::try_from(minutes).ok().and_then(chrono::Duration::try_minutes).and_then(|d| Local::now().checked_add_signed(d));
+            let Some(until) = until else { return };
+            self.rain_snoozed_until = Some(until);
+            self.notify(format!("rain alert snoozed for {} min", minutes));
+        }
+    }
+
+    pub fn snooze_alarm(&mut self) {
+        let minutes = self.config.snooze_minutes;
+        if let Some(alarm) = self.alarm.as_mut() {
+            alarm.snooze(minutes);
+            let (label, count) = (alarm.label.clone(), alarm.snooze_count);
+            self.publish("snooze", serde_json::json!({"label": label, "minutes": minutes, "count": count}));
+            self.events.push(EventKind::Alarm, format!("{} snoozed for {} min", label, minutes));
+        }
+    }
+
+    pub fn dismiss_alarm(&mut self) {
+        if let Some(alarm) = self.alarm.take() {
+            self.publish("dismiss", serde_json::json!({"label": alarm.label}));
+            self.events.push(EventKind::Alarm, format!("{} dismissed", alarm.label));
+        }
+    }
+
+    // 当前显示的待办（原列表序号，内容）：按过滤关键字不区分大小写匹配
+    pub fn visible_todos(&self) -> Vec<(usize, &String)> {
+        let filter = self.todo_filter.as_deref().map(str::to_lowercase).unwrap_or_default();
+        self.todos.iter().enumerate().filter(|(_, t)| t.to_lowercase().contains(&filter)).collect()
+    }
+
+    // 选择在显示的待办之间移动，selected_todo 保存原列表序号
+    pub fn select_next_todo(&mut self) {
+        let visible: Vec<usize> = self.visible_todos().into_iter().map(|(i, _)| i).collect();
+        let pos = self.selected_todo.and_then(|s| visible.iter().position(|&i| i == s));
+        let next = pos.map_or(0, |p| (p + 1).min(visible.len().saturating_sub(1)));
+        self.selected_todo = visible.get(next).copied();
+    }
+
+    pub fn select_prev_todo(&mut self) {
+        let visible: Vec<usize> = self.visible_todos().into_iter().map(|(i, _)| i).collect();
+        let pos = self.selected_todo.and_then(|s| visible.iter().position(|&i| i == s));
+        let prev = pos.map_or(0, |p| p.saturating_sub(1));
+        self.selected_todo = visible.get(prev).copied();
+    }
+
+    // 设置过滤关键字；选中项被过滤掉时取消选择
+    pub fn set_todo_filter(&mut self, filter: Option<String>) {
+        self.todo_filter = filter.filter(|f| !f.is_empty());
+        if let Some(selected) = self.selected_todo
+            && !self.visible_todos().iter().any(|(i, _)| *i == selected)
+        {
+            self.selected_todo = None;
+        }
+    }
+
+    pub fn start_filter_input(&mut self) {
+        self.input = Some((Prompt::Filter, self.todo_filter.clone().unwrap_or_default()));
+    }
+
+    pub fn start_command_input(&mut self) {
+        self.input = Some((Prompt::Command, String::new()));
+    }
+
+    // 配置的主题在前，其后是同名未被覆盖的内置主题（:theme 命令也能选择）
+    pub fn theme_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.config.themes.iter().map(|t| t.name.clone()).collect();
+        for theme in crate::theme::builtin_themes() {
+            if !names.contains(&theme.name) {
+                names.push(theme.name);
+            }
+        }
+        names
+    }
+
+    // 命令面板的补全候选
+    pub fn command_completions(&self) -> Vec<String> {
+        match &self.input {
+            Some((Prompt::Command, text)) => palette::completions(text, &self.theme_names()),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn parse_command(&self, line: &str) -> Result<PaletteCommand, String> {
+        palette::parse(line, &self.theme_names())
+    }
+
+    pub fn run_command(&mut self, command: PaletteCommand) {
+        if let Some(outbox) = &mut self.forward_commands
+            && let Some(line) = command.ipc_line()
+        {
+            outbox.push(line);
+            return;
+        }
+        let todos_changed = matches!(command, PaletteCommand::TodoAdd(_) | PaletteCommand::TodoDone(_) | PaletteCommand::TodoRm(_));
+        match command {
+            PaletteCommand::Theme(name) => self.set_theme(name),
+            PaletteCommand::Timer(duration) => self.set_timer(duration),
+            PaletteCommand::TodoAdd(text) => {
+                self.with_todo_backend(TodoBackend::from_config(&self.config), |b| b.add(&text).map(|_| Some(format!("added: {}", text))));
+            }
+            PaletteCommand::TodoDone(n) | PaletteCommand::TodoRm(n) if n > self.todos.len() => {
+                self.notify(format!("no todo #{}", n));
+            }
+            PaletteCommand::TodoDone(n) | PaletteCommand::TodoRm(n) => {
+                let selected = self.selected_todo.replace(n - 1);
+                if matches!(command, PaletteCommand::TodoDone(_)) {
+                    self.complete_selected_todo();
+                } else {
+                    self.remove_selected_todo();
+                }
+                self.selected_todo = selected;
+            }
+            PaletteCommand::Tz(tz) => self.set_display_tz(tz),
+            PaletteCommand::Mute(minutes) => self.mute(minutes),
+            PaletteCommand::Unmute => self.unmute(),
+            PaletteCommand::Refresh => self.refresh_now(),
+            PaletteCommand::Pause => self.toggle_pause(),
+            PaletteCommand::Quit => self.quit_requested = true,
+        }
+        // attach 前端直接修改待办后端，通知守护进程重新加载
+        if todos_changed && let Some(outbox) = &mut self.forward_commands {
+            outbox.push("refresh".to_string());
+        }
+    }
+
+    // 倒计时；None 取消
+    pub fn set_timer(&mut self, duration: Option<std::time::Duration>) {
+        match duration {
+            Some(d) => {
+                let Some(until) = Instant::now().checked_add(d).filter(|_| d <= crate::palette::MAX_TIMER) else {
+                    self.notify(format!("timer: too long (max {} days)", crate::palette::MAX_TIMER.as_secs() / 86400));
+                    return;
+                };
+                let secs = d.as_secs();
+                let label = match (secs / 3600, secs % 3600 / 60, secs % 60) {
+                    (0, m, 0) => format!("{}m", m),
+                    (0, 0, s) => format!("{}s", s),
+                    (0, m, s) => format!("{}m{}s", m, s),
+                    (h, 0, 0) => format!("{}h", h),
+                    (h, m, _) => format!("{}h{}m", h, m),
+                };
+                self.notify(format!("timer: {}", label));
+                let saved = chrono::Duration::from_std(d)
+                    .ok()
+                    .and_then(|d| Local::now().checked_add_signed(d))
+                    .map(|until| SavedTimer { until, label: label.clone() });
+                self.save_timer(saved);
+                self.timer = Some((until, label));
+            }
+            None => {
+                if self.timer.take().is_some() {
+                    self.save_timer(None);
+                    self.notify("timer cancelled".to_string());
+                }
+            }
+        }
+    }
+
+    // 写入状态文件，崩溃或重启后恢复；写入失败只影响恢复，不提示
+    fn save_timer(&self, timer: Option<SavedTimer>) {
+        let _ = crate::state::update(&self.config.state_file, |state| state.timer = timer);
+    }
+
+    // 恢复重启前运行中的倒计时：未到期的继续计时；停机期间到期的立即响铃，标签中注明到期时间
+    fn restore_timer(&mut self) {
+        let Some(saved) = crate::state::load(&self.config.state_file).timer else { return };
+        let now = Local::now();
+        let label = match now - saved.until {
+            overdue if overdue > chrono::Duration::minutes(1) => format!("{}, ended {}", saved.label, saved.until.format("%H:%M")),
+            _ => saved.label,
+        };
+        let remaining = (saved.until - now).to_std().unwrap_or_default();
+        // 状态文件损坏或被手工改成很远的将来时丢弃该倒计时
+        let Some(until) = Instant::now().checked_add(remaining).filter(|_| remaining <= crate::palette::MAX_TIMER) else {
+            self.save_timer(None);
+            return;
+        };
+        self.timer = Some((until, label));
+    }
+
+    // 倒计时到期：像闹钟一样响铃，Enter 关闭、s 贪睡
+    fn run_timer(&mut self) {
+        if let Some((until, label)) = &self.timer
+            && Instant::now() >= *until
+        {
+            let label = format!("Timer {}", label);
+            self.timer = None;
+            self.save_timer(None);
+            self.start_alarm(label);
+        }
+    }
+
+    fn set_display_tz(&mut self, tz: Option<chrono_tz::Tz>) {
+        self.display_tz = tz;
+        let name = tz.map(|tz| tz.name().to_string());
+        let label = name.clone().unwrap_or_else(|| "local".to_string());
+        match crate::state::update(&self.config.state_file, |state| state.tz = name) {
+            Ok(()) => self.notify(format!("time zone: {}", label)),
+            Err(e) => self.notify(format!("time zone: {} (not saved: {})", label, e)),
+        }
+    }
+
+    // 更新待办列表：选中项跟随原内容移动（列表被外部修改时），找不到则限制在范围内
+    fn set_todos(&mut self, todos: Vec<String>) {
+        let selected = self.selected_todo.and_then(|i| self.todos.get(i));
+        self.selected_todo = match (self.selected_todo, selected) {
+            (Some(_), _) if todos.is_empty() => None,
+            (Some(_), Some(text)) if todos.contains(text) => todos.iter().position(|t| t == text),
+            (Some(i), _) => Some(i.min(todos.len() - 1)),
+            (None, _) => None,
+        };
+        self.todos = todos;
+    }
+
+    // 复制选中的待办到系统剪贴板；未选中时复制当前显示的列表
+    pub fn copy_todos(&mut self) {
+        let visible: Vec<&str> = self.visible_todos().into_iter().map(|(_, t)| t.as_str()).collect();
+        let count = visible.len();
+        let text = match self.selected_todo.and_then(|i| self.todos.get(i)) {
+            Some(todo) => todo.clone(),
+            None => visible.join("\n"),
+        };
+        if text.is_empty() {
+            return;
+        }
+        if self.clipboard.is_none() {
+            self.clipboard = arboard::Clipboard::new().ok();
+        }
+        let result = match self.clipboard.as_mut() {
+            Some(clipboard) => clipboard.set_text(text).map_err(|e| e.to_string()),
+            None => Err("no clipboard available".to_string()),
+        };
+        let message = match result {
+            Ok(()) if self.selected_todo.is_some() => "copied todo".to_string(),
+            Ok(()) => format!("copied {} todos", count),
+            Err(e) => format!("clipboard error: {}", e),
+        };
+        self.notify(message);
+    }
+
+    // 待办操作（a 添加，x 完成，Delete 删除），作用于当前配置的待办后端
+    pub fn start_todo_input(&mut self) {
+        if TodoBackend::from_config(&self.config).is_writable() {
+            self.input = Some((Prompt::AddTodo, String::new()));
+        } else {
+            self.notify("the todo Redis backend is read-only".to_string());
+        }
+    }
+
+    // 输入框打开时处理按键，返回是否已处理
+    pub fn handle_input_key(&mut self, key: KeyEvent) -> bool {
+        let Some((prompt, input)) = self.input.as_mut() else { return false };
+        let prompt = *prompt;
+        match key.code {
+            KeyCode::Tab if prompt == Prompt::Command => {
+                let input = input.clone();
+                if let Some(line) = palette::complete(&input, &self.theme_names()) {
+                    self.input = Some((prompt, line));
+                }
+                return true;
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Esc => {
+                self.input = None;
+                if prompt == Prompt::Filter {
+                    self.set_todo_filter(None);
+                }
+                return true;
+            }
+            KeyCode::Enter => {
+                let (_, text) = self.input.take().unwrap_or((prompt, String::new()));
+                match prompt {
+                    Prompt::AddTodo if !text.trim().is_empty() => {
+                        self.with_todo_backend(TodoBackend::from_config(&self.config), |b| b.add(text.trim()).map(|_| Some(format!("added: {}", text.trim()))));
+                    }
+                    Prompt::AddTodo => {}
+                    Prompt::Filter => self.set_todo_filter(Some(text)),
+                    Prompt::Command => match self.parse_command(&text) {
+                        Ok(command) => self.run_command(command),
+                        Err(e) if e.is_empty() => {}
+                        Err(e) => self.notify(e),
+                    },
+                }
+                return true;
+            }
+            _ => {}
+        }
+        // 过滤随输入实时生效
+        if prompt == Prompt::Filter {
+            let text = self.input.as_ref().map(|(_, t)| t.clone());
+            self.set_todo_filter(text);
+        }
+        true
+    }
+
+    pub fn complete_selected_todo(&mut self) {
+        if let Some(index) = self.selected_todo {
+            let expected = self.todos.get(index).cloned();
+            let backend = self.todo_backend_for(expected.as_deref());
+            self.with_todo_backend(backend, |b| b.complete(index, expected.as_deref()).map(|t| t.map(|t| format!("done: {}", t))));
+        }
+    }
+
+    pub fn remove_selected_todo(&mut self) {
+        if let Some(index) = self.selected_todo {
+            let expected = self.todos.get(index).cloned();
+            let backend = self.todo_backend_for(expected.as_deref());
+            self.with_todo_backend(backend, |b| b.remove(index, expected.as_deref()).map(|t| t.map(|t| format!("deleted: {}", t))));
+        }
+    }
+
+    // 待办所属来源的后端（合并多个来源时）；新增待办使用第一个可写的来源
+    fn todo_backend_for(&self, todo: Option<&str>) -> TodoBackend {
+        todo.and_then(|t| self.todo_origins.get(t))
+            .and_then(|&i| self.config.todo_sources.get(i))
+            .map(|s| s.backend.clone())
+            .unwrap_or_else(|| TodoBackend::from_config(&self.config))
+    }
+
+    fn with_todo_backend(&mut self, backend: TodoBackend, op: impl FnOnce(&TodoBackend) -> Result<Option<String>, String>) {
+        match op(&backend) {
+            Ok(Some(message)) => self.notify(message),
+            Ok(None) => {}
+            Err(e) => self.notify(format!("todo error: {}", e)),
+        }
+        self.refresh_now();
+    }
+
+    pub fn notify(&mut self, message: String) {
+        self.notice = Some((message, Instant::now() + NOTICE_DURATION));
+    }
+
+    pub fn handle_ipc(&mut self, request: IpcRequest) {
+        match request.command {
+            IpcCommand::Mute(minutes) => self.mute(minutes),
+            IpcCommand::Unmute => self.unmute(),
+            IpcCommand::ToggleMute => self.toggle_mute(),
+            IpcCommand::Snooze => self.snooze(),
+            IpcCommand::Dismiss => self.dismiss_alarm(),
+            IpcCommand::Refresh => self.refresh_now(),
+            IpcCommand::Timer(secs) => self.set_timer(secs.map(Duration::from_secs)),
+            IpcCommand::Push(ref push) => {
+                self.apply_push(push.clone());
+                request.respond("ok");
+                return;
+            }
+            IpcCommand::Quit => {
+                self.quit_requested = true;
+                request.respond("ok bye");
+                return;
+            }
+            IpcCommand::Snapshot => {
+                let json = serde_json::to_string(&self.snapshot()).unwrap_or_default();
+                request.respond(json);
+                return;
+            }
+        }
+        let state = if self.muted { "muted" } else { "unmuted" };
+        request.respond(format!("ok {}", state));
+    }
+
+    pub fn snapshot(&mut self) -> Snapshot {
+        let now = Instant::now();
+        Snapshot {
+            now: Some(Local::now()),
+            temperature: self.cached_temp.clone(),
+            outdoor_temperature: self.cached_outdoor_temp.clone(),
+            temp_range: self.temp_range,
+            humidity: self.cached_humidity,
+            humidity_range: self.humidity_range,
+            forecast: self.forecast.as_ref().and_then(|s| s.lock().ok().map(|s| s.clone())),
+            rain_snoozed_until: self.rain_snoozed_until,
+            radar: self.radar_state(),
+            local_sensors: self.local_sensor_readings(),
+            message: self.message().map(str::to_string),
+            todos: self.todos.clone(),
+            muted: self.is_muted(),
+            unmute_in_secs: self.unmute_at.map(|t| t.saturating_duration_since(now).as_secs()),
+            alarm: self.alarm.as_ref().map(|a| AlarmSnapshot {
+                label: a.label.clone(),
+                snooze_count: a.snooze_count,
+                snoozed_secs: match a.state {
+                    AlarmState::Snoozed { until } => Some(until.saturating_duration_since(now).as_secs()),
+                    AlarmState::Ringing { .. } => None,
+                },
+            }),
+            flash_ms: self.flash_until.map(|t| t.saturating_duration_since(now).as_millis() as u64),
+            meeting: self.gcal_status.as_ref().and_then(|s| s.lock().ok().map(|s| s.clone())),
+            sound_seq: self.sound_seq,
+            last_sound: self.last_sound.clone(),
+            sources: self.source_states(),
+            scripts: self.script_states(),
+            panels: self.panel_states(),
+            upcoming: self.upcoming_states(),
+            transit: self.transit_state(),
+            ntp: self.ntp_state(),
+            condition: self.weather_condition(),
+            events: self.events.recent(),
+            time_synced: self.time_synced(),
+            api_error: self.api_error(),
+            todo_summary: self.todo_summary,
+            todo_origins: self.todo_origins.clone(),
+            offline_since: self.offline_since.filter(|_| self.is_offline()),
+            data_updated: self.data_updated,
+            markers: self.markers.clone(),
+            timer: self.timer.as_ref().map(|(until, label)| (until.saturating_duration_since(now).as_secs(), label.clone())),
+        }
+    }
+
+    // 状态栏显示的 API 错误（温度优先）
+    pub fn api_error(&self) -> Option<String> {
+        self.temp_error.clone().or_else(|| self.todos_error.clone())
+    }
+
+    pub fn transit_state(&self) -> Option<TransitState> {
+        self.transit.as_ref().and_then(|s| s.lock().ok().map(|s| s.clone()))
+    }
+
+    pub fn weather_condition(&self) -> Option<weather::Condition> {
+        self.condition.as_ref().and_then(|s| s.lock().ok().and_then(|s| *s))
+    }
+
+    // 天气强调色和说明（weather_accents）：按当前天气状况和显示的温度
+    pub fn weather_accent(&self) -> Option<(ratatui::style::Color, &'static str)> {
+        let accents = self.config.weather_accents.as_ref()?;
+        let celsius = crate::temperature::parse_celsius(&self.temperature());
+        crate::theme::weather_accent(accents, self.weather_condition(), celsius)
+    }
+
+    pub fn ntp_state(&self) -> Option<crate::ntp::NtpState> {
+        self.ntp.as_ref().and_then(|s| s.lock().ok().map(|s| s.clone()))
+    }
+
+    // 系统时间是否已同步（None 为未知）
+    pub fn time_synced(&self) -> Option<bool> {
+        self.time_sync.as_ref().and_then(|s| s.lock().ok().and_then(|s| *s))
+    }
+
+    // 超过 warn_ms 的 NTP 偏差（毫秒）
+    pub fn clock_drift(&self) -> Option<i64> {
+        let warn_ms = self.config.ntp.as_ref()?.warn_ms;
+        self.ntp_state()?.offset_ms.filter(|ms| ms.unsigned_abs() > warn_ms)
+    }
+
+    // 各“接下来”数据的名称、显示条数和状态
+    pub fn upcoming_states(&self) -> Vec<(String, usize, FeedState)> {
+        self.upcoming
+            .iter()
+            .map(|(name, limit, state)| (name.clone(), *limit, state.lock().map(|s| s.clone()).unwrap_or(FeedState::Loading)))
+            .collect()
+    }
+
+    pub fn script_states(&self) -> Vec<(String, ScriptState)> {
+        self.scripts
+            .iter()
+            .map(|(name, state)| (name.clone(), state.lock().map(|s| s.clone()).unwrap_or(ScriptState::Loading)))
+            .collect()
+    }
+
+    pub fn panel_states(&self) -> Vec<(String, PanelState)> {
+        self.panels
+            .iter()
+            .map(|(title, state)| (title.clone(), state.lock().map(|s| s.clone()).unwrap_or(PanelState::Loading)))
+            .collect()
+    }
+
+    pub fn source_states(&self) -> Vec<(String, SourceState)> {
+        self.sources
+            .iter()
+            .map(|(name, state)| (name.clone(), state.lock().map(|s| s.clone()).unwrap_or(SourceState::Loading)))
+            .collect()
+    }
+
+    // attach 前端：用守护进程快照覆盖本地显示状态
+    pub fn apply_snapshot(&mut self, snap: Snapshot) {
+        let now = Instant::now();
+        self.cached_temp = snap.temperature;
+        self.cached_outdoor_temp = snap.outdoor_temperature;
+        self.temp_range = snap.temp_range;
+        self.cached_humidity = snap.humidity;
+        self.humidity_range = snap.humidity_range;
+        self.forecast = snap.forecast.map(|s| std::sync::Arc::new(std::sync::Mutex::new(s)));
+        self.rain_snoozed_until = snap.rain_snoozed_until;
+        self.radar = snap.radar.map(|s| std::sync::Arc::new(std::sync::Mutex::new(s)));
+        if let Ok(mut readings) = self.local_sensors.lock() {
+            *readings = snap.local_sensors;
+        }
+        self.message = snap.message.map(|text| (text, None));
+        self.set_todos(snap.todos);
+        self.todo_summary = snap.todo_summary;
+        self.todo_origins = snap.todo_origins;
+        // 关闭状态只在本地前端保存，恢复在线后重置
+        if snap.offline_since.is_none() {
+            self.offline_dismissed = false;
+        }
+        self.offline_since = snap.offline_since;
+        self.data_updated = snap.data_updated;
+        self.markers = snap.markers;
+        self.transit = snap.transit.map(|s| std::sync::Arc::new(std::sync::Mutex::new(s)));
+        self.ntp = snap.ntp.map(|s| std::sync::Arc::new(std::sync::Mutex::new(s)));
+        self.condition = snap.condition.map(|c| std::sync::Arc::new(std::sync::Mutex::new(Some(c))));
+        self.events.replace(snap.events);
+        self.time_sync = snap.time_synced.map(|s| std::sync::Arc::new(std::sync::Mutex::new(Some(s))));
+        self.upcoming = snap
+            .upcoming
+            .into_iter()
+            .map(|(name, limit, state)| (name, limit, std::sync::Arc::new(std::sync::Mutex::new(state))))
+            .collect();
+        self.timer = snap.timer.map(|(secs, label)| (now + Duration::from_secs(secs), label));
+        self.muted = snap.muted;
+        self.unmute_at = snap.unmute_in_secs.map(|s| now + Duration::from_secs(s));
+        self.alarm = snap.alarm.map(|a| ActiveAlarm {
+            label: a.label,
+            snooze_count: a.snooze_count,
+            state: match a.snoozed_secs {
+                Some(secs) => AlarmState::Snoozed { until: now + Duration::from_secs(secs) },
+                None => AlarmState::Ringing { last_ring: Some(now) },
+            },
+        });
+        self.flash_until = snap.flash_ms.map(|ms| now + Duration::from_millis(ms));
+        self.gcal_status = snap.meeting.map(|m| std::sync::Arc::new(std::sync::Mutex::new(m)));
+        self.sound_seq = snap.sound_seq;
+        self.last_sound = snap.last_sound;
+        self.temp_error = snap.api_error;
+        self.todos_error = None;
+        self.sources = snap
+            .sources
+            .into_iter()
+            .map(|(name, state)| (name, std::sync::Arc::new(std::sync::Mutex::new(state))))
+            .collect();
+        self.scripts = snap
+            .scripts
+            .into_iter()
+            .map(|(name, state)| (name, std::sync::Arc::new(std::sync::Mutex::new(state))))
+            .collect();
+        self.panels = snap
+            .panels
+            .into_iter()
+            .map(|(title, state)| (title, std::sync::Arc::new(std::sync::Mutex::new(state))))
+            .collect();
+    }
+}
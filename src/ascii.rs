@@ -0,0 +1,79 @@
+use ratatui::buffer::Buffer;
+
+// ASCII 回退：终端或区域设置不支持方块字符、制表符时（旧式控制台、非 UTF-8 locale），
+// 绘制完成后把缓冲区中的这些字符替换为 # - | + 等，避免显示成乱码。其他字符（如中文）保持不变
+
+// 当前区域设置（LC_ALL > LC_CTYPE > LANG）
+pub fn locale() -> String {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|k| std::env::var(k).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_default()
+}
+
+// 自动检测：TERM 为 dumb / vt100 等旧终端，或 locale 明确设置为非 UTF-8 字符集（如 zh_CN.GBK、en_US.ISO-8859-1）。
+// 未设置 locale 或为 C / POSIX 时不切换（容器中常见，终端本身通常支持 UTF-8）
+pub fn detect() -> bool {
+    let term = std::env::var("TERM").unwrap_or_default();
+    if matches!(term.as_str(), "dumb" | "vt52" | "vt100" | "vt102" | "vt220") {
+        return true;
+    }
+    let locale = locale();
+    let utf8 = locale.to_lowercase().replace('-', "").contains("utf8");
+    !utf8 && !locale.is_empty() && locale != "C" && locale != "POSIX"
+}
+
+// 替换缓冲区中的方块、制表符和符号
+pub fn fallback(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        let mut chars = cell.symbol().chars();
+        if let (Some(c), None) = (chars.next(), chars.next())
+            && let Some(ascii) = ascii_char(c)
+        {
+            cell.set_char(ascii);
+        }
+    }
+}
+
+fn ascii_char(c: char) -> Option<char> {
+    let ascii = match c {
+        // 实心和大半格方块
+        '█' | '▓' | '▙' | '▛' | '▜' | '▟' | '▆' | '▇' | '▌' | '▐' | '▚' | '▞' => '#',
+        '▀' | '▔' => '"',
+        '▄' | '▅' => '=',
+        '▁' | '▂' | '▃' => '_',
+        '▖' | '▗' => '.',
+        '▘' | '▝' => '\'',
+        '▒' => '+',
+        '░' => ':',
+        // 制表符（含粗线、双线和圆角）
+        '─' | '━' | '═' | '╌' | '┄' | '−' => '-',
+        '│' | '┃' | '║' | '╎' | '┆' => '|',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' | '╭' | '╮' | '╰' | '╯' | '┏' | '┓' | '┗' | '┛' | '╔' | '╗' | '╚' | '╝' => '+',
+        // 圆点、箭头和单位
+        '●' | '◉' | '■' => 'O',
+        '○' | '◯' | '□' => 'o',
+        '•' | '∙' => '*',
+        '·' => '.',
+        '↑' | '▲' => '^',
+        '↓' | '▼' => 'v',
+        '→' | '▶' => '>',
+        '←' | '◀' => '<',
+        '▬' => '=',
+        // 盲文点阵：点数过半为 #，否则为 .
+        c @ '\u{2801}'..='\u{28ff}' => match (c as u32 - 0x2800).count_ones() {
+            4.. => '#',
+            _ => '.',
+        },
+        '\u{2800}' => ' ',
+        '℃' => 'C',
+        '°' => '\'',
+        '⚠' => '!',
+        '✓' => 'v',
+        '✖' => 'x',
+        // 图标（占两列，后一列原本就是空格）
+        '☀' | '☂' | '❄' | '⏰' | '⏱' | '📅' | '🔕' | '💤' | '🚏' | '🌧' | '💧' | '🌡' => '*',
+        _ => return None,
+    };
+    Some(ascii)
+}
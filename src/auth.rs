@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+const DEVICE_CODE_URL: &str = "https://oauth2.googleapis.com/device/code";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const DEVICE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+pub const CALENDAR_SCOPE: &str = "https://www.googleapis.com/auth/calendar.readonly";
+
+// OAuth 客户端信息（Google Cloud 中 "TV and Limited Input" 类型的客户端）
+#[derive(Debug, Clone)]
+pub struct OAuthClient {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+// 本地保存的令牌（expires_at 为 Unix 秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: i64,
+}
+
+impl StoredToken {
+    pub fn is_expired(&self) -> bool {
+        // 提前 60 秒视为过期，避免请求途中失效
+        chrono::Utc::now().timestamp() + 60 >= self.expires_at
+    }
+}
+
+// 设备授权：用户需在另一台设备打开 verification_url 并输入 user_code
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    #[serde(alias = "verification_uri")]
+    pub verification_url: String,
+    pub expires_in: u64,
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+fn default_interval() -> u64 { 5 }
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+    error: Option<String>,
+}
+
+pub enum PollResult {
+    Pending,
+    SlowDown,
+    Granted(StoredToken),
+    Failed(String),
+}
+
+fn http_client() -> Option<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .ok()
+}
+
+pub fn request_device_code(client: &OAuthClient, scope: &str) -> Option<DeviceCode> {
+    http_client()?
+        .post(DEVICE_CODE_URL)
+        .form(&[("client_id", client.client_id.as_str()), ("scope", scope)])
+        .send()
+        .and_then(|r| r.error_for_status())
+        .ok()?
+        .json()
+        .ok()
+}
+
+pub fn poll_device_token(client: &OAuthClient, device: &DeviceCode) -> PollResult {
+    let Some(http) = http_client() else { return PollResult::Failed("http client".into()) };
+    let resp = http
+        .post(TOKEN_URL)
+        .form(&[
+            ("client_id", client.client_id.as_str()),
+            ("client_secret", client.client_secret.as_str()),
+            ("device_code", device.device_code.as_str()),
+            ("grant_type", DEVICE_GRANT),
+        ])
+        .send()
+        .and_then(|r| r.json::<TokenResponse>());
+    match resp {
+        Ok(TokenResponse { access_token: Some(access_token), refresh_token, expires_in, .. }) => {
+            PollResult::Granted(StoredToken {
+                access_token,
+                refresh_token,
+                expires_at: chrono::Utc::now().timestamp() + expires_in.unwrap_or(3600),
+            })
+        }
+        Ok(TokenResponse { error: Some(e), .. }) => match e.as_str() {
+            "authorization_pending" => PollResult::Pending,
+            "slow_down" => PollResult::SlowDown,
+            _ => PollResult::Failed(e),
+        },
+        Ok(_) => PollResult::Failed("empty token response".into()),
+        Err(e) => PollResult::Failed(e.to_string()),
+    }
+}
+
+// 使用 refresh_token 换取新的 access_token（refresh_token 保持不变）
+pub fn refresh_token(client: &OAuthClient, token: &StoredToken) -> Option<StoredToken> {
+    let refresh = token.refresh_token.as_deref()?;
+    let resp: TokenResponse = http_client()?
+        .post(TOKEN_URL)
+        .form(&[
+            ("client_id", client.client_id.as_str()),
+            ("client_secret", client.client_secret.as_str()),
+            ("refresh_token", refresh),
+            ("grant_type", "refresh_token"),
+        ])
+        .send()
+        .and_then(|r| r.error_for_status())
+        .ok()?
+        .json()
+        .ok()?;
+    Some(StoredToken {
+        access_token: resp.access_token?,
+        refresh_token: resp.refresh_token.or_else(|| token.refresh_token.clone()),
+        expires_at: chrono::Utc::now().timestamp() + resp.expires_in.unwrap_or(3600),
+    })
+}
+
+// 默认令牌文件：~/.config/termclock/google_token.json
+pub fn default_token_path() -> PathBuf {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| Path::new(&h).join(".config")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    base.join("termclock").join("google_token.json")
+}
+
+pub fn load_token(path: &Path) -> Option<StoredToken> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn save_token(path: &Path, token: &StoredToken) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(token).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)?;
+    // 令牌仅限当前用户读取
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
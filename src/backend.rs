@@ -0,0 +1,89 @@
+use std::io::{self, Write};
+
+use ratatui::backend::{Backend, ClearType, CrosstermBackend, WindowSize};
+use ratatui::buffer::Cell;
+use ratatui::layout::Rect;
+
+// 终端后端包装：画面没有变化时不输出任何内容（crossterm 后端每帧都会写入颜色复位和隐藏光标序列），
+// 通过慢速 SSH 连接时空闲画面零流量
+pub struct QuietBackend<W: Write> {
+    inner: CrosstermBackend<W>,
+    cursor_visible: Option<bool>,
+}
+
+impl<W: Write> QuietBackend<W> {
+    pub fn new(writer: W) -> Self {
+        Self { inner: CrosstermBackend::new(writer), cursor_visible: None }
+    }
+}
+
+impl<W: Write> Write for QuietBackend<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(&mut self.inner)
+    }
+}
+
+impl<W: Write> Backend for QuietBackend<W> {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let mut content = content.peekable();
+        if content.peek().is_none() {
+            return Ok(());
+        }
+        self.inner.draw(content)
+    }
+
+    fn append_lines(&mut self, n: u16) -> io::Result<()> {
+        self.inner.append_lines(n)
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        if self.cursor_visible == Some(false) {
+            return Ok(());
+        }
+        self.cursor_visible = Some(false);
+        self.inner.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        if self.cursor_visible == Some(true) {
+            return Ok(());
+        }
+        self.cursor_visible = Some(true);
+        self.inner.show_cursor()
+    }
+
+    fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+        self.inner.get_cursor()
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.inner.set_cursor(x, y)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.inner.clear()
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+        self.inner.clear_region(clear_type)
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        self.inner.size()
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        self.inner.window_size()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Backend::flush(&mut self.inner)
+    }
+}
@@ -0,0 +1,205 @@
+use std::time::Instant;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+
+// 时钟背后的动画背景（代码雨 / 星空）：前景绘制完成后只填充空白单元格，
+// 相当于画在最底层
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BackgroundEffect {
+    #[default]
+    None,
+    Rain,
+    Stars,
+}
+
+impl BackgroundEffect {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" | "off" => Some(BackgroundEffect::None),
+            "rain" | "matrix" => Some(BackgroundEffect::Rain),
+            "stars" | "starfield" => Some(BackgroundEffect::Stars),
+            _ => None,
+        }
+    }
+}
+
+// 代码雨字符（半角片假名与数字，均为单列宽）
+const RAIN_GLYPHS: &[char] = &[
+    'ｱ', 'ｲ', 'ｳ', 'ｴ', 'ｵ', 'ｶ', 'ｷ', 'ｸ', 'ｹ', 'ｺ', 'ｻ', 'ｼ', 'ｽ', 'ｾ', 'ｿ', 'ﾀ', 'ﾁ', 'ﾂ', 'ﾃ', 'ﾄ', 'ﾅ', 'ﾆ', 'ﾇ',
+    'ﾈ', 'ﾉ', 'ﾊ', 'ﾋ', 'ﾌ', 'ﾍ', 'ﾎ', 'ﾏ', 'ﾐ', 'ﾑ', 'ﾒ', 'ﾓ', 'ﾔ', 'ﾕ', 'ﾖ', 'ﾗ', 'ﾘ', 'ﾙ', 'ﾚ', 'ﾛ', 'ﾜ', 'ﾝ', '0',
+    '1', '2', '3', '4', '5', '6', '7', '8', '9', 'Z', ':', '=', '*', '+', '<', '>',
+];
+const STAR_GLYPHS: &[char] = &['.', '·', '+', '*'];
+
+// 雨滴：所在列、头部行（可为负，尚未进入画面）、速度（行/秒）、拖尾长度
+struct Drop {
+    x: u16,
+    y: f32,
+    speed: f32,
+    len: u16,
+}
+
+// 星星：位置（列可为小数，向左漂移）、速度（列/秒）、闪烁相位
+struct Star {
+    x: f32,
+    y: u16,
+    speed: f32,
+    phase: u32,
+}
+
+pub struct Background {
+    effect: BackgroundEffect,
+    density: f64,
+    area: Rect,
+    drops: Vec<Drop>,
+    stars: Vec<Star>,
+    rng: u64,
+    elapsed: f32,
+    last: Option<Instant>,
+}
+
+impl Background {
+    pub fn new() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(1, |d| d.as_nanos() as u64);
+        Self {
+            effect: BackgroundEffect::None,
+            density: 0.0,
+            area: Rect::default(),
+            drops: Vec::new(),
+            stars: Vec::new(),
+            rng: seed | 1,
+            elapsed: 0.0,
+            last: None,
+        }
+    }
+
+    // xorshift64，背景动画不需要更好的随机数
+    fn next(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    fn uniform(&mut self) -> f32 {
+        (self.next() % 10_000) as f32 / 10_000.0
+    }
+
+    // 区域或参数变化时重新撒点
+    fn reset(&mut self, effect: BackgroundEffect, density: f64, area: Rect) {
+        self.effect = effect;
+        self.density = density;
+        self.area = area;
+        self.drops.clear();
+        self.stars.clear();
+        match effect {
+            BackgroundEffect::None => {}
+            BackgroundEffect::Rain => {
+                for x in 0..area.width {
+                    if self.uniform() as f64 >= density {
+                        continue;
+                    }
+                    let drop = self.new_drop(x, true);
+                    self.drops.push(drop);
+                }
+            }
+            BackgroundEffect::Stars => {
+                let count = (area.width as f64 * area.height as f64 * density * 0.1) as usize;
+                for _ in 0..count {
+                    let star = Star {
+                        x: self.uniform() * area.width as f32,
+                        y: (self.next() % area.height.max(1) as u64) as u16,
+                        speed: 0.5 + self.uniform() * 2.5,
+                        phase: self.next() as u32,
+                    };
+                    self.stars.push(star);
+                }
+            }
+        }
+    }
+
+    // anywhere 为 true 时雨滴随机出现在画面中（初次铺满），否则从顶部上方落下
+    fn new_drop(&mut self, x: u16, anywhere: bool) -> Drop {
+        let height = self.area.height as f32;
+        let len = 4 + (self.next() % (self.area.height.max(8) as u64 / 2)) as u16;
+        let y = match anywhere {
+            true => self.uniform() * height,
+            false => -(self.uniform() * height / 2.0),
+        };
+        Drop { x, y, speed: 6.0 + self.uniform() * 14.0, len }
+    }
+
+    // 推进动画并绘制到 buf 中 area 范围内的空白单元格；animate 为 false（暂停）时画面静止
+    pub fn render(&mut self, buf: &mut Buffer, area: Rect, effect: BackgroundEffect, density: f64, color: Option<Color>, animate: bool) {
+        if effect != self.effect || density != self.density || area != self.area {
+            self.reset(effect, density, area);
+        }
+        let now = Instant::now();
+        let dt = match (self.last, animate) {
+            (Some(last), true) => (now - last).as_secs_f32().min(1.0),
+            _ => 0.0,
+        };
+        self.last = Some(now);
+        self.elapsed += dt;
+
+        let mut put = |x: u16, y: u16, ch: char, style: Style| {
+            if x < area.width && y < area.height {
+                let cell = buf.get_mut(area.x + x, area.y + y);
+                if cell.symbol() == " " {
+                    cell.set_char(ch).set_style(style);
+                }
+            }
+        };
+        match effect {
+            BackgroundEffect::None => {}
+            BackgroundEffect::Rain => {
+                let color = color.unwrap_or(Color::Green);
+                // 字符每 150ms 变换一次
+                let tick = (self.elapsed / 0.15) as usize;
+                for i in 0..self.drops.len() {
+                    self.drops[i].y += self.drops[i].speed * dt;
+                    if self.drops[i].y - self.drops[i].len as f32 > area.height as f32 {
+                        let x = self.drops[i].x;
+                        self.drops[i] = self.new_drop(x, false);
+                    }
+                    let drop = &self.drops[i];
+                    let head = drop.y.floor() as i32;
+                    for k in 0..drop.len as i32 {
+                        let y = head - k;
+                        if y < 0 {
+                            break;
+                        }
+                        let hash = (drop.x as usize).wrapping_mul(2_654_435_761) ^ (y as usize).wrapping_mul(40_503);
+                        let glyph = RAIN_GLYPHS[hash.wrapping_add(tick) % RAIN_GLYPHS.len()];
+                        let style = match k {
+                            0 => Style::default().fg(Color::White),
+                            _ if k > drop.len as i32 / 2 => Style::default().fg(color).add_modifier(Modifier::DIM),
+                            _ => Style::default().fg(color),
+                        };
+                        put(drop.x, y as u16, glyph, style);
+                    }
+                }
+            }
+            BackgroundEffect::Stars => {
+                let style = Style::default().fg(color.unwrap_or(Color::Gray));
+                let width = area.width.max(1) as f32;
+                let twinkle = (self.elapsed * 2.0) as u32;
+                for star in &mut self.stars {
+                    star.x -= star.speed * dt;
+                    if star.x < 0.0 {
+                        star.x += width;
+                    }
+                    let glyph = STAR_GLYPHS[(star.phase.wrapping_add(twinkle) % STAR_GLYPHS.len() as u32) as usize];
+                    // 慢星暗、快星亮，形成远近层次
+                    let style = if star.speed < 1.5 { style.add_modifier(Modifier::DIM) } else { style };
+                    put(star.x as u16, star.y, glyph, style);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// 蓝牙温湿度计：被动监听广播（无需连接），支持
+// - 小米 LYWSD03MMC 等刷了 ATC / pvvx 固件的设备（0x181A 服务数据）
+// - BTHome v2（0xFCD2 服务数据，未加密）
+// - Govee H5075 / H5072（0xEC88 厂商数据）
+// 需启用 `ble` feature；未启用时各设备显示错误提示。
+
+#[cfg(feature = "ble")]
+mod imp {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use btleplug::api::bleuuid::uuid_from_u16;
+    use btleplug::api::{Central, CentralEvent, Manager as _, Peripheral as _, ScanFilter};
+    use btleplug::platform::Manager;
+    use futures::StreamExt;
+
+    use crate::model::BleSensor;
+    use crate::sensors::{self, SharedReadings};
+
+    // 蓝牙不可用时的重试间隔
+    const RETRY: Duration = Duration::from_secs(30);
+
+    pub fn spawn_worker(devices: Vec<BleSensor>, shared: SharedReadings) {
+        for device in &devices {
+            sensors::register(&shared, &device.label);
+        }
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime,
+                Err(e) => return report_error(&devices, &shared, format!("ble: {}", e)),
+            };
+            loop {
+                if let Err(e) = runtime.block_on(scan(&devices, &shared)) {
+                    report_error(&devices, &shared, format!("ble: {}", e));
+                }
+                std::thread::sleep(RETRY);
+            }
+        });
+    }
+
+    fn report_error(devices: &[BleSensor], shared: &SharedReadings, error: String) {
+        for device in devices {
+            sensors::record_error(shared, &device.label, error.clone());
+        }
+    }
+
+    // 持续扫描，直到适配器出错或事件流结束
+    async fn scan(devices: &[BleSensor], shared: &SharedReadings) -> Result<(), btleplug::Error> {
+        let manager = Manager::new().await?;
+        let adapter = manager
+            .adapters()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| btleplug::Error::Other("no Bluetooth adapter found".into()))?;
+        let mut events = adapter.events().await?;
+        adapter.start_scan(ScanFilter::default()).await?;
+        let labels: HashMap<String, &str> = devices.iter().map(|d| (d.address.to_ascii_uppercase(), d.label.as_str())).collect();
+        while let Some(event) = events.next().await {
+            let (id, reading) = match event {
+                CentralEvent::ServiceDataAdvertisement { id, service_data } => {
+                    let reading = service_data.iter().find_map(|(uuid, data)| {
+                        if *uuid == uuid_from_u16(0x181A) {
+                            parse_atc(data)
+                        } else if *uuid == uuid_from_u16(0xFCD2) {
+                            parse_bthome(data)
+                        } else {
+                            None
+                        }
+                    });
+                    (id, reading)
+                }
+                CentralEvent::ManufacturerDataAdvertisement { id, manufacturer_data } => {
+                    (id, manufacturer_data.get(&0xEC88).and_then(|data| parse_govee(data)))
+                }
+                _ => continue,
+            };
+            let Some((temp, humidity)) = reading else { continue };
+            // 被动扫描时设备可能在广播后立即消失，跳过这次广播，继续扫描
+            let Ok(peripheral) = adapter.peripheral(&id).await else { continue };
+            let address = peripheral.address().to_string();
+            if let Some(label) = labels.get(&address) {
+                sensors::record(shared, label, Some(temp), humidity);
+            }
+        }
+        Ok(())
+    }
+
+    // ATC 格式（13 字节，温度大端 0.1℃）或 pvvx 格式（15 字节，小端，温度 0.01℃、湿度 0.01%）
+    fn parse_atc(data: &[u8]) -> Option<(f64, Option<f64>)> {
+        match data.len() {
+            13 => Some((i16::from_be_bytes([data[6], data[7]]) as f64 / 10.0, Some(data[8] as f64))),
+            15 => Some((
+                i16::from_le_bytes([data[6], data[7]]) as f64 / 100.0,
+                Some(u16::from_le_bytes([data[8], data[9]]) as f64 / 100.0),
+            )),
+            _ => None,
+        }
+    }
+
+    // BTHome v2：首字节为设备信息（bit0 加密），之后为 (对象 id, 值) 序列
+    fn parse_bthome(data: &[u8]) -> Option<(f64, Option<f64>)> {
+        let (info, mut rest) = data.split_first()?;
+        if info & 1 != 0 {
+            return None;
+        }
+        let (mut temp, mut humidity) = (None, None);
+        while let Some((&id, tail)) = rest.split_first() {
+            // 未知对象无法得知长度，停止解析
+            let len = match id {
+                0x00 | 0x01 | 0x2E => 1,
+                0x02 | 0x03 | 0x0C | 0x45 => 2,
+                0x04 => 3,
+                _ => break,
+            };
+            let value = tail.get(..len)?;
+            match id {
+                0x02 => temp = Some(i16::from_le_bytes([value[0], value[1]]) as f64 / 100.0),
+                0x45 => temp = Some(i16::from_le_bytes([value[0], value[1]]) as f64 / 10.0),
+                0x03 => humidity = Some(u16::from_le_bytes([value[0], value[1]]) as f64 / 100.0),
+                0x2E => humidity = Some(value[0] as f64),
+                _ => {}
+            }
+            rest = &tail[len..];
+        }
+        Some((temp?, humidity))
+    }
+
+    // Govee：3 字节大端整数，最高位为负号，值 = 温度 × 10000 + 湿度 × 10
+    fn parse_govee(data: &[u8]) -> Option<(f64, Option<f64>)> {
+        let bytes = data.get(1..4)?;
+        let raw = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        let (negative, value) = (raw & 0x80_0000 != 0, raw & 0x7F_FFFF);
+        let temp = (value / 1000) as f64 / 10.0;
+        Some((if negative { -temp } else { temp }, Some((value % 1000) as f64 / 10.0)))
+    }
+}
+
+#[cfg(not(feature = "ble"))]
+mod imp {
+    use crate::model::BleSensor;
+    use crate::sensors::{self, SharedReadings};
+
+    pub fn spawn_worker(devices: Vec<BleSensor>, shared: SharedReadings) {
+        for device in &devices {
+            sensors::record_error(&shared, &device.label, "built without the ble feature".to_string());
+        }
+    }
+}
+
+pub use imp::spawn_worker;
@@ -0,0 +1,152 @@
+use chrono::{Datelike, NaiveDate};
+
+// 第二历法：在公历日期下方显示同一天在其他历法中的日期。
+// hijri 为伊斯兰历（表格历法，与按新月观测的宗教日期可能相差一两天），hebrew 为希伯来历，japanese 为日本年号纪年
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecondaryCalendar {
+    Hijri,
+    Hebrew,
+    Japanese,
+}
+
+impl SecondaryCalendar {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hijri" | "islamic" => Some(SecondaryCalendar::Hijri),
+            "hebrew" | "jewish" => Some(SecondaryCalendar::Hebrew),
+            "japanese" => Some(SecondaryCalendar::Japanese),
+            _ => None,
+        }
+    }
+
+    // 6 Jumada al-Ula 1448 AH / 6 Cheshvan 5787 / 令和8年10月17日
+    pub fn format(&self, date: NaiveDate) -> String {
+        match self {
+            SecondaryCalendar::Hijri => {
+                let (y, m, d) = hijri_from_fixed(fixed(date));
+                format!("{} {} {} AH", d, HIJRI_MONTHS[m as usize - 1], y)
+            }
+            SecondaryCalendar::Hebrew => {
+                let (y, m, d) = hebrew_from_fixed(fixed(date));
+                let name = match m {
+                    12 if hebrew_leap(y) => "Adar I",
+                    _ => HEBREW_MONTHS[m as usize - 1],
+                };
+                format!("{} {} {}", d, name, y)
+            }
+            SecondaryCalendar::Japanese => japanese_era(date),
+        }
+    }
+}
+
+const HIJRI_MONTHS: [&str; 12] = [
+    "Muharram", "Safar", "Rabi al-Awwal", "Rabi al-Thani", "Jumada al-Ula", "Jumada al-Akhirah",
+    "Rajab", "Shaban", "Ramadan", "Shawwal", "Dhu al-Qadah", "Dhu al-Hijjah",
+];
+
+// 尼散月为 1 月，提斯利月（新年）为 7 月，闰年的亚达月为 12 月（Adar I）和 13 月（Adar II）
+const HEBREW_MONTHS: [&str; 13] = [
+    "Nisan", "Iyar", "Sivan", "Tammuz", "Av", "Elul", "Tishrei", "Cheshvan", "Kislev", "Tevet", "Shevat", "Adar", "Adar II",
+];
+
+// 年号及其起始日（明治 6 年起使用公历）
+const JAPANESE_ERAS: [(&str, i32, u32, u32); 5] = [
+    ("令和", 2019, 5, 1),
+    ("平成", 1989, 1, 8),
+    ("昭和", 1926, 12, 25),
+    ("大正", 1912, 7, 30),
+    ("明治", 1868, 1, 25),
+];
+
+// 固定日序号：公元 1 年 1 月 1 日为 1
+fn fixed(date: NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce())
+}
+
+// 表格伊斯兰历：30 年一周期，其中 11 个闰年（12 月为 30 天）；奇数月 30 天，偶数月 29 天
+const HIJRI_EPOCH: i64 = 227015;
+
+fn hijri_from_fixed(date: i64) -> (i64, i64, i64) {
+    let year = (30 * (date - HIJRI_EPOCH) + 10646).div_euclid(10631);
+    let prior_days = date - hijri_to_fixed(year, 1, 1);
+    let month = (11 * prior_days + 330).div_euclid(325);
+    let day = date - hijri_to_fixed(year, month, 1) + 1;
+    (year, month, day)
+}
+
+fn hijri_to_fixed(year: i64, month: i64, day: i64) -> i64 {
+    HIJRI_EPOCH - 1 + (year - 1) * 354 + (3 + 11 * year).div_euclid(30) + 29 * (month - 1) + month / 2 + day
+}
+
+// 希伯来历：按《历法计算》(Calendrical Calculations) 中的算法，新年由朔望月推算并按延期规则调整
+const HEBREW_EPOCH: i64 = -1373427;
+
+fn hebrew_leap(year: i64) -> bool {
+    (7 * year + 1).rem_euclid(19) < 7
+}
+
+fn hebrew_last_month(year: i64) -> i64 {
+    if hebrew_leap(year) { 13 } else { 12 }
+}
+
+// 创世纪元到该年提斯利月 1 日（延期前）的天数
+fn hebrew_elapsed_days(year: i64) -> i64 {
+    let months = (235 * year - 234).div_euclid(19);
+    let parts = 12084 + 13753 * months;
+    let day = 29 * months + parts.div_euclid(25920);
+    if (3 * (day + 1)).rem_euclid(7) < 3 { day + 1 } else { day }
+}
+
+fn hebrew_new_year(year: i64) -> i64 {
+    let (ny0, ny1, ny2) = (hebrew_elapsed_days(year - 1), hebrew_elapsed_days(year), hebrew_elapsed_days(year + 1));
+    let delay = if ny2 - ny1 == 356 { 2 } else if ny1 - ny0 == 382 { 1 } else { 0 };
+    HEBREW_EPOCH + ny1 + delay
+}
+
+fn hebrew_month_days(year: i64, month: i64) -> i64 {
+    let year_days = hebrew_new_year(year + 1) - hebrew_new_year(year);
+    let short = match month {
+        2 | 4 | 6 | 10 | 13 => true,
+        12 => !hebrew_leap(year),
+        // 赫舍汪月在"完整"年份为 30 天，基斯流月在"欠缺"年份为 29 天
+        8 => year_days % 10 != 5,
+        9 => year_days % 10 == 3,
+        _ => false,
+    };
+    if short { 29 } else { 30 }
+}
+
+fn hebrew_from_fixed(date: i64) -> (i64, i64, i64) {
+    let mut year = (date - HEBREW_EPOCH) * 98496 / 35975351 + 1;
+    while hebrew_new_year(year) > date {
+        year -= 1;
+    }
+    while hebrew_new_year(year + 1) <= date {
+        year += 1;
+    }
+    // 从提斯利月开始逐月累加
+    let mut start = hebrew_new_year(year);
+    for month in (7..=hebrew_last_month(year)).chain(1..7) {
+        let days = hebrew_month_days(year, month);
+        if date < start + days {
+            return (year, month, date - start + 1);
+        }
+        start += days;
+    }
+    unreachable!("date lies within the Hebrew year")
+}
+
+// 元年写作"元年"；明治之前的日期不转换
+fn japanese_era(date: NaiveDate) -> String {
+    let era = JAPANESE_ERAS
+        .iter()
+        .find(|(_, y, m, d)| NaiveDate::from_ymd_opt(*y, *m, *d).is_some_and(|start| date >= start));
+    match era {
+        Some((name, start_year, _, _)) => {
+            let year = date.year() - start_year + 1;
+            let year = if year == 1 { "元".to_string() } else { year.to_string() };
+            format!("{}{}年{}月{}日", name, year, date.month(), date.day())
+        }
+        None => date.format("%Y-%m-%d").to_string(),
+    }
+}
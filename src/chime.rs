@@ -0,0 +1,115 @@
+use std::io::{self, Write};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+const LONG_MS: u64 = 1000;
+const SHORT_MS: u64 = 150;
+const GAP_MS: u64 = 200;
+// 每段响铃和间隔的上限，以及段数上限
+const MAX_SEGMENT_MS: u64 = 5000;
+const MAX_SEGMENTS: usize = 20;
+
+// 单段提示音：响铃时长 + 之后的间隔
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeepSegment {
+    pub beep: Duration,
+    pub gap: Duration,
+}
+
+// 提示音样式，例如 "long short short" 或 "long:800:300 short"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeepPattern {
+    pub segments: Vec<BeepSegment>,
+}
+
+impl BeepPattern {
+    // 每段格式为 kind[:beep_ms[:gap_ms]]，kind 为 long / short / beep；时长超过 5 秒或超过 20 段时无效
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut segments = Vec::new();
+        for token in spec.split_whitespace() {
+            let mut parts = token.split(':');
+            let default_beep = match parts.next()?.to_ascii_lowercase().as_str() {
+                "long" => LONG_MS,
+                "short" | "beep" => SHORT_MS,
+                _ => return None,
+            };
+            let beep = match parts.next() {
+                Some(ms) => ms.parse().ok()?,
+                None => default_beep,
+            };
+            let gap = match parts.next() {
+                Some(ms) => ms.parse().ok()?,
+                None => GAP_MS,
+            };
+            if parts.next().is_some() || beep > MAX_SEGMENT_MS || gap > MAX_SEGMENT_MS || segments.len() == MAX_SEGMENTS {
+                return None;
+            }
+            segments.push(BeepSegment { beep: Duration::from_millis(beep), gap: Duration::from_millis(gap) });
+        }
+        if segments.is_empty() { None } else { Some(Self { segments }) }
+    }
+
+    // 闹钟默认：四声短促提示
+    pub fn alarm_default() -> Self {
+        Self::parse("short short short short:150:600").expect("valid pattern")
+    }
+
+    // 在后台线程播放，不阻塞界面和 IPC；来自守护进程快照的样式未经 parse，同样按上限截断
+    pub fn play(&self) {
+        let segments = self.segments.clone();
+        std::thread::spawn(move || {
+            let max = Duration::from_millis(MAX_SEGMENT_MS);
+            let last = segments.len().min(MAX_SEGMENTS).saturating_sub(1);
+            for (i, seg) in segments.iter().take(MAX_SEGMENTS).enumerate() {
+                beep_for(seg.beep.min(max));
+                if i < last { std::thread::sleep(seg.gap.min(max)); }
+            }
+        });
+    }
+}
+
+// 整点默认报时：普通整点一声长音，12 点两声长音
+pub fn hour_pattern(hour24: u32) -> BeepPattern {
+    let spec = if hour24 == 12 { "long long" } else { "long" };
+    BeepPattern::parse(spec).expect("valid pattern")
+}
+
+fn beep_for(duration: Duration) {
+    // Emit BEL repeatedly to approximate a long beep; terminal decides the sound.
+    // If the terminal does not beep, no sound may be produced.
+    let mut out = io::stdout();
+    let step = Duration::from_millis(50);
+    let mut elapsed = Duration::from_millis(0);
+    loop {
+        let _ = write!(out, "\x07");
+        let _ = out.flush();
+        std::thread::sleep(step);
+        elapsed += step;
+        if elapsed >= duration { break; }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_segments() {
+        let pattern = BeepPattern::parse("long:800:300 short").unwrap();
+        assert_eq!(pattern.segments[0], BeepSegment { beep: Duration::from_millis(800), gap: Duration::from_millis(300) });
+        assert_eq!(pattern.segments[1], BeepSegment { beep: Duration::from_millis(SHORT_MS), gap: Duration::from_millis(GAP_MS) });
+        assert_eq!(BeepPattern::parse(""), None);
+        assert_eq!(BeepPattern::parse("buzz"), None);
+        assert_eq!(BeepPattern::parse("long:1:2:3"), None);
+    }
+
+    #[test]
+    fn parse_rejects_oversized_patterns() {
+        assert!(BeepPattern::parse("long:5000:5000").is_some());
+        assert_eq!(BeepPattern::parse("long:600000"), None);
+        assert_eq!(BeepPattern::parse("short:100:5001"), None);
+        assert!(BeepPattern::parse(&"short ".repeat(MAX_SEGMENTS)).is_some());
+        assert_eq!(BeepPattern::parse(&"short ".repeat(MAX_SEGMENTS + 1)), None);
+    }
+}
@@ -0,0 +1,52 @@
+// 湿度舒适度：按相对湿度和露点分为干燥、舒适、潮湿
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comfort {
+    Dry,
+    Comfortable,
+    Humid,
+}
+
+impl Comfort {
+    pub fn label(self) -> &'static str {
+        match self {
+            Comfort::Dry => "dry",
+            Comfort::Comfortable => "comfortable",
+            Comfort::Humid => "humid",
+        }
+    }
+}
+
+// 阈值：湿度低于 dry_below 为干燥；高于 humid_above 或露点高于 dew_point_max（闷热）为潮湿
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComfortThresholds {
+    pub dry_below: f64,
+    pub humid_above: f64,
+    pub dew_point_max: f64,
+}
+
+impl Default for ComfortThresholds {
+    fn default() -> Self {
+        Self { dry_below: 30.0, humid_above: 60.0, dew_point_max: 16.0 }
+    }
+}
+
+// 露点（Magnus 公式），temp 为摄氏度，humidity 为百分比
+pub fn dew_point(temp: f64, humidity: f64) -> f64 {
+    const A: f64 = 17.62;
+    const B: f64 = 243.12;
+    let gamma = (humidity.clamp(1.0, 100.0) / 100.0).ln() + A * temp / (B + temp);
+    B * gamma / (A - gamma)
+}
+
+// 没有温度时只按湿度判断
+pub fn classify(humidity: f64, temp: Option<f64>, thresholds: &ComfortThresholds) -> Comfort {
+    let muggy = temp.is_some_and(|t| dew_point(t, humidity) > thresholds.dew_point_max);
+    if humidity > thresholds.humid_above || muggy {
+        Comfort::Humid
+    } else if humidity < thresholds.dry_below {
+        Comfort::Dry
+    } else {
+        Comfort::Comfortable
+    }
+}
@@ -0,0 +1,38 @@
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+
+// 条件请求：记住上次响应的 ETag / Last-Modified，下次请求带上 If-None-Match /
+// If-Modified-Since。服务器返回 304 表示没有变化，调用方沿用上次的结果，省去下载和解析。
+// 每个轮询线程持有一份；不支持的服务器不返回这两个头，请求与原来相同
+#[derive(Debug, Default)]
+pub struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    // Ok(None) 表示 304 没有变化
+    pub fn send(&mut self, mut request: RequestBuilder) -> Result<Option<Response>, reqwest::Error> {
+        if let Some(etag) = &self.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(at) = &self.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, at);
+        }
+        let response = request.send()?;
+        if response.status() == StatusCode::NOT_MODIFIED && (self.etag.is_some() || self.last_modified.is_some()) {
+            return Ok(None);
+        }
+        let response = response.error_for_status()?;
+        let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok()).map(|s| s.to_string());
+        self.etag = header(ETAG);
+        self.last_modified = header(LAST_MODIFIED);
+        Ok(Some(response))
+    }
+
+    // 请求或解析失败后清除，下次重新完整获取（否则 304 会让错误一直保留）
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
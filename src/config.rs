@@ -1,46 +1,155 @@
 use std::fs;
 use std::env;
-use crate::model::{FileConfig, Config};
+use std::collections::HashMap;
+use crate::serial::{self, SerialConfig};
+use crate::dht::Dht22Config;
+use crate::mqtt::{self, MqttConfig};
+use crate::script::{self, ScriptSource};
+use crate::panel::CommandPanel;
+use crate::provider::TemperatureProvider;
+use crate::power::LowPower;
+use crate::ratelimit;
+use crate::model::{FileConfig, Config, AutoRotate, BigTemperature, HumidityGauge, View, BleSensor, ChimeRule, GcalConfig, ClockSpec, ClockStyle, LayoutSpacing, Subseconds, ThermometerStyle, TimeColors, TimeEffect, TimeFont, WidgetStyle};
+use crate::schedule::{CronSchedule, WorkHours};
+use crate::background::BackgroundEffect;
+use crate::calendar::SecondaryCalendar;
+use crate::countdown::Countdown;
+use crate::markers::{MarkerSpec, MarkerTime};
+use crate::upcoming::{FeedKind, UpcomingFeed};
+use crate::transit::{TransitConfig, TransitFeed};
+use crate::ntp::NtpConfig;
+use crate::frame::{parse_corners, ClockFrame, FrameStyle};
+use crate::idle::{IdleDim, IdleMode};
+use crate::radar::{Graphics, RadarConfig};
+use crate::chime::BeepPattern;
+use crate::alarm::{Alarm, parse_alarm_time};
+use crate::jsonpath::JsonPath;
+use crate::api_schema::ApiSchema;
+use crate::source::{CustomSource, Extract, Transport};
+use crate::todo::TodoSort;
+use crate::todo_store::{TodoBackend, TodoSource};
+use crate::theme::{Palette, Theme, WeatherAccents};
 use ratatui::style::Color;
 
 const DEFAULT_CONFIG_PATH: &str = "termclock.yml";
 
-pub fn load_yaml_config() -> Option<FileConfig> {
-    let path = env::var("TERMCLOCK_CONFIG").ok().unwrap_or_else(|| {
+// --profile 选择的配置档（重载配置时沿用）
+static PROFILE: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+pub fn active_profile() -> Option<String> {
+    PROFILE.get().cloned().or_else(|| env::var("TERMCLOCK_PROFILE").ok().filter(|p| !p.is_empty()))
+}
+
+pub fn config_path() -> String {
+    env::var("TERMCLOCK_CONFIG").ok().unwrap_or_else(|| {
         if fs::metadata(DEFAULT_CONFIG_PATH).is_ok() {
             DEFAULT_CONFIG_PATH.to_string()
         } else {
             "termclock.yml".to_string()
         }
-    });
-    let content = fs::read_to_string(path).ok()?;
+    })
+}
+
+fn read_config_file() -> Option<serde_yaml::Value> {
     // Parse via generic Value to avoid serde_derive runtime
-    let value: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
-    let map = value.as_mapping()?;
+    match fs::read_to_string(config_path()) {
+        Ok(content) => serde_yaml::from_str(&content).ok(),
+        Err(_) => Some(serde_yaml::Value::Mapping(Default::default())),
+    }
+}
+
+// 顶层配置作为默认值，选中的配置档逐项覆盖
+pub fn apply_profile(map: &mut serde_yaml::Mapping, profile: Option<&str>) -> Result<(), String> {
+    let profiles = map.remove("profiles");
+    let Some(name) = profile else { return Ok(()) };
+    let selected = profiles.as_ref().and_then(|p| p.get(name)).and_then(|p| p.as_mapping());
+    let Some(selected) = selected else {
+        let known: Vec<&str> = profiles.as_ref().and_then(|p| p.as_mapping())
+            .map(|m| m.keys().filter_map(|k| k.as_str()).collect())
+            .unwrap_or_default();
+        if known.is_empty() {
+            return Err(format!("unknown profile: {} (no profiles in config)", name));
+        }
+        return Err(format!("unknown profile: {} (available: {})", name, known.join(", ")));
+    };
+    for (key, value) in selected {
+        map.insert(key.clone(), value.clone());
+    }
+    Ok(())
+}
+
+// load_yaml_config 读取的全部顶层键（termclock doctor 据此提示拼写错误，新增配置项时同步添加）
+pub const CONFIG_KEYS: &[&str] = &[
+    "api_base_url", "device_code", "temp_refresh_interval", "todo_ip_filter", "todos_file",
+    "todo_task_max_chars", "todo_limit", "main_window_percent", "time_scale_x", "time_scale_y",
+    "date_scale_x", "time_color", "date_color", "todos_color", "chime_enabled", "chimes",
+    "chime_patterns", "chime_pattern", "alarm_pattern", "chime_visual", "chime_audible",
+    "mute_minutes", "ipc_socket", "alarms", "snooze_minutes", "gcal_client_id",
+    "gcal_client_secret", "gcal_calendar_id", "gcal_token_file", "gcal_remind_minutes",
+    "gcal_pattern", "work_hours", "work_days", "work_timezone", "history_enabled", "history_file",
+    "history_interval", "web_listen", "web_push_secret", "screenshot_dir", "todo_db", "todo_priority", "todo_group",
+    "todo_summary", "todo_rotate_secs", "todo_sort", "offline_after_mins", "offline_retry_secs", "stale_primary_secs", "temperature_providers", "low_power",
+    "low_bandwidth", "ascii", "todo_priority_colors", "custom_sources", "todo_sources", "profiles",
+    "themes", "theme", "state_file", "status_bar", "time_colors", "clocks", "auto_rotate", "idle_dim", "big_temperature", "secondary_calendar", "widgets", "layout", "time_effect", "time_effect_color", "time_font",
+    "show_subseconds", "clock_style", "clock_frame", "background_effect", "background_density", "background_color", "background_effect_color",
+    "countdowns", "countdown_celebrate_secs", "markers", "markers_command", "markers_chime", "latitude", "longitude",
+    "sun_bar", "upcoming", "transit", "ntp", "time_sync",
+    "compare_indoor_outdoor", "thermometer_style", "temp_min_max", "show_comfort", "comfort_dry_below", "comfort_humid_above",
+    "comfort_dew_point_max", "humidity_gauge", "rain_alert", "rain_alert_minutes", "rain_threshold_mm", "rain_alert_chime",
+    "forecast_url", "weather_accents", "radar", "ble_sensors", "serial", "dht22", "scripts", "max_concurrent_requests", "api_schema", "mqtt", "on_hour_command", "on_alarm_command", "command_panels",
+];
+
+pub fn load_yaml_config() -> Option<FileConfig> {
+    let mut value = read_config_file()?;
+    let map = value.as_mapping_mut()?;
+    // 未知配置档在 parse_cli 中已报错
+    apply_profile(map, active_profile().as_deref()).ok()?;
+    // 没有配置文件也没有环境变量覆盖时使用内置默认值
+    if !apply_env_overrides(map) && map.is_empty() {
+        return None;
+    }
+    let map = &*map;
     let get_string = |key: &str| -> Option<String> {
-        map.get(&serde_yaml::Value::String(key.to_string()))
-            .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+        map.get(serde_yaml::Value::String(key.to_string()))
+            .and_then(|v| match v {
+                // 数字写法的编号（device_code: 12345、环境变量）按字符串处理
+                serde_yaml::Value::Number(n) => Some(n.to_string()),
+                _ => v.as_str().map(|s| s.trim().to_string()),
+            })
             .filter(|s| !s.is_empty())
     };
     let get_usize = |key: &str| -> Option<usize> {
-        map.get(&serde_yaml::Value::String(key.to_string()))
+        map.get(serde_yaml::Value::String(key.to_string()))
             .and_then(|v| v.as_i64())
             .and_then(|n| if n > 0 { Some(n as usize) } else { None })
     };
     let get_u16 = |key: &str| -> Option<u16> {
-        map.get(&serde_yaml::Value::String(key.to_string()))
+        map.get(serde_yaml::Value::String(key.to_string()))
             .and_then(|v| v.as_i64())
             .and_then(|n| if n > 0 { Some(n as u16) } else { None })
     };
     let get_u64 = |key: &str| -> Option<u64> {
-        map.get(&serde_yaml::Value::String(key.to_string()))
+        map.get(serde_yaml::Value::String(key.to_string()))
             .and_then(|v| v.as_i64())
             .and_then(|n| if n > 0 { Some(n as u64) } else { None })
     };
+    let get_f64 = |key: &str| -> Option<f64> {
+        map.get(serde_yaml::Value::String(key.to_string()))
+            .and_then(|v| v.as_f64())
+    };
     let get_bool = |key: &str| -> Option<bool> {
-        map.get(&serde_yaml::Value::String(key.to_string()))
+        map.get(serde_yaml::Value::String(key.to_string()))
             .and_then(|v| v.as_bool())
     };
+    let patterns = map
+        .get(serde_yaml::Value::String("chime_patterns".to_string()))
+        .map(parse_pattern_table)
+        .unwrap_or_default();
+    let get_pattern = |key: &str| -> Option<BeepPattern> {
+        get_string(key).and_then(|spec| resolve_pattern(&spec, &patterns))
+    };
+    let api_schema = map.get(serde_yaml::Value::String("api_schema".to_string())).and_then(parse_api_schema);
+    let todo_schema = api_schema.clone().unwrap_or_default();
     Some(FileConfig {
         api_base_url: get_string("api_base_url"),
         device_code: get_string("device_code"),
@@ -58,9 +167,833 @@ pub fn load_yaml_config() -> Option<FileConfig> {
         date_color: get_string("date_color"),
         todos_color: get_string("todos_color"),
         chime_enabled: get_bool("chime_enabled"),
+        chimes: map
+            .get(serde_yaml::Value::String("chimes".to_string()))
+            .and_then(|v| parse_chimes(v, &patterns)),
+        chime_pattern: get_pattern("chime_pattern"),
+        alarm_pattern: get_pattern("alarm_pattern"),
+        chime_visual: get_bool("chime_visual"),
+        chime_audible: get_bool("chime_audible"),
+        mute_minutes: get_u64("mute_minutes"),
+        ipc_socket: get_string("ipc_socket"),
+        alarms: map.get(serde_yaml::Value::String("alarms".to_string())).and_then(parse_alarms),
+        snooze_minutes: get_u64("snooze_minutes"),
+        gcal_client_id: get_string("gcal_client_id"),
+        gcal_client_secret: get_string("gcal_client_secret"),
+        gcal_calendar_id: get_string("gcal_calendar_id"),
+        gcal_token_file: get_string("gcal_token_file"),
+        gcal_remind_minutes: get_u64("gcal_remind_minutes"),
+        gcal_pattern: get_pattern("gcal_pattern"),
+        work_hours: get_string("work_hours"),
+        work_days: get_string("work_days"),
+        work_timezone: get_string("work_timezone"),
+        history_enabled: get_bool("history_enabled"),
+        history_file: get_string("history_file"),
+        history_interval: get_u64("history_interval"),
+        web_listen: get_string("web_listen"),
+        web_push_secret: get_string("web_push_secret"),
+        screenshot_dir: get_string("screenshot_dir"),
+        todo_db: get_string("todo_db"),
+        todo_priority: get_bool("todo_priority"),
+        todo_group: get_bool("todo_group"),
+        todo_summary: get_bool("todo_summary"),
+        todo_rotate_secs: get_u64("todo_rotate_secs"),
+        todo_sort: get_string("todo_sort"),
+        offline_after_mins: get_u64("offline_after_mins"),
+        offline_retry_secs: get_u64("offline_retry_secs"),
+        stale_primary_secs: get_u64("stale_primary_secs"),
+        max_concurrent_requests: get_u64("max_concurrent_requests").map(|n| n as usize),
+        low_bandwidth: get_bool("low_bandwidth"),
+        low_power: map.get(serde_yaml::Value::String("low_power".to_string())).and_then(parse_low_power),
+        ascii: get_bool("ascii"),
+        todo_priority_colors: map
+            .get(serde_yaml::Value::String("todo_priority_colors".to_string()))
+            .and_then(|v| v.as_sequence())
+            .map(|seq| seq.iter().filter_map(|c| c.as_str().map(str::to_string)).collect()),
+        custom_sources: map.get(serde_yaml::Value::String("custom_sources".to_string())).and_then(parse_custom_sources),
+        todo_sources: map
+            .get(serde_yaml::Value::String("todo_sources".to_string()))
+            .and_then(|v| parse_todo_sources(v, get_string("api_base_url"), get_usize("todo_limit"), &todo_schema)),
+        api_schema,
+        themes: map.get(serde_yaml::Value::String("themes".to_string())).and_then(parse_themes),
+        theme: get_string("theme"),
+        state_file: get_string("state_file"),
+        status_bar: get_bool("status_bar"),
+        time_colors: map.get(serde_yaml::Value::String("time_colors".to_string())).and_then(parse_time_colors),
+        clocks: map.get(serde_yaml::Value::String("clocks".to_string())).and_then(parse_clocks),
+        auto_rotate: map.get(serde_yaml::Value::String("auto_rotate".to_string())).and_then(parse_auto_rotate),
+        idle_dim: map.get(serde_yaml::Value::String("idle_dim".to_string())).and_then(parse_idle_dim),
+        big_temperature: map.get(serde_yaml::Value::String("big_temperature".to_string())).and_then(parse_big_temperature),
+        secondary_calendar: get_string("secondary_calendar"),
+        widgets: map.get(serde_yaml::Value::String("widgets".to_string())).and_then(parse_widgets),
+        layout: map.get(serde_yaml::Value::String("layout".to_string())).and_then(parse_layout),
+        time_effect: get_string("time_effect"),
+        time_effect_color: get_string("time_effect_color"),
+        time_font: get_string("time_font"),
+        show_subseconds: get_string("show_subseconds"),
+        clock_style: get_string("clock_style"),
+        clock_frame: map.get(serde_yaml::Value::String("clock_frame".to_string())).and_then(parse_clock_frame),
+        background_effect: get_string("background_effect"),
+        background_density: get_f64("background_density"),
+        background_color: get_string("background_color"),
+        background_effect_color: get_string("background_effect_color"),
+        countdowns: map
+            .get(serde_yaml::Value::String("countdowns".to_string()))
+            .and_then(|v| v.as_sequence())
+            .map(|seq| seq.iter().filter_map(|c| c.as_str().map(str::to_string)).collect()),
+        countdown_celebrate_secs: get_u64("countdown_celebrate_secs"),
+        markers: map.get(serde_yaml::Value::String("markers".to_string())).and_then(|v| parse_markers(v, &patterns)),
+        markers_command: get_string("markers_command"),
+        markers_chime: get_bool("markers_chime"),
+        latitude: get_f64("latitude"),
+        longitude: get_f64("longitude"),
+        sun_bar: get_bool("sun_bar"),
+        upcoming: map.get(serde_yaml::Value::String("upcoming".to_string())).and_then(parse_upcoming),
+        transit: map.get(serde_yaml::Value::String("transit".to_string())).and_then(parse_transit),
+        ntp: map.get(serde_yaml::Value::String("ntp".to_string())).and_then(parse_ntp),
+        time_sync: get_bool("time_sync"),
+        compare_indoor_outdoor: get_bool("compare_indoor_outdoor"),
+        thermometer_style: get_string("thermometer_style"),
+        temp_min_max: get_bool("temp_min_max"),
+        show_comfort: get_bool("show_comfort"),
+        comfort_dry_below: get_f64("comfort_dry_below"),
+        comfort_humid_above: get_f64("comfort_humid_above"),
+        comfort_dew_point_max: get_f64("comfort_dew_point_max"),
+        humidity_gauge: map.get(serde_yaml::Value::String("humidity_gauge".to_string())).and_then(parse_humidity_gauge),
+        rain_alert: get_bool("rain_alert"),
+        rain_alert_minutes: get_u64("rain_alert_minutes"),
+        rain_threshold_mm: get_f64("rain_threshold_mm"),
+        rain_alert_chime: get_bool("rain_alert_chime"),
+        forecast_url: get_string("forecast_url"),
+        weather_accents: map.get(serde_yaml::Value::String("weather_accents".to_string())).and_then(parse_weather_accents),
+        radar: map.get(serde_yaml::Value::String("radar".to_string())).and_then(parse_radar),
+        ble_sensors: map.get(serde_yaml::Value::String("ble_sensors".to_string())).and_then(parse_ble_sensors),
+        serial: map.get(serde_yaml::Value::String("serial".to_string())).map(|v| match v.as_sequence() {
+            Some(items) => items.iter().filter_map(parse_serial).collect(),
+            None => parse_serial(v).into_iter().collect(),
+        }),
+        dht22: map.get(serde_yaml::Value::String("dht22".to_string())).and_then(parse_dht22),
+        mqtt: map.get(serde_yaml::Value::String("mqtt".to_string())).and_then(parse_mqtt),
+        on_hour_command: get_string("on_hour_command"),
+        on_alarm_command: get_string("on_alarm_command"),
+        scripts: map.get(serde_yaml::Value::String("scripts".to_string())).and_then(parse_scripts),
+        command_panels: map.get(serde_yaml::Value::String("command_panels".to_string())).and_then(parse_command_panels),
+        temperature_providers: map.get(serde_yaml::Value::String("temperature_providers".to_string())).and_then(|v| {
+            let location = get_f64("latitude").zip(get_f64("longitude"));
+            parse_temperature_providers(v, get_string("api_base_url"), get_string("device_code"), location, get_string("forecast_url"))
+        }),
+    })
+}
+
+// 环境变量覆盖配置文件：TERMCLOCK_API_BASE_URL -> api_base_url，值按 YAML 解析
+// （数字、true/false、[a, b] 列表），返回是否有覆盖
+fn apply_env_overrides(map: &mut serde_yaml::Mapping) -> bool {
+    let mut applied = false;
+    for (name, raw) in env::vars() {
+        let Some(key) = name.strip_prefix("TERMCLOCK_") else { continue };
+        if key == "CONFIG" || key == "PROFILE" || key.is_empty() {
+            continue;
+        }
+        let value = match serde_yaml::from_str::<serde_yaml::Value>(&raw) {
+            // "#ff8800" 之类会被 YAML 当作注释，保留原文
+            Ok(serde_yaml::Value::Null) | Err(_) => serde_yaml::Value::String(raw),
+            Ok(value) => value,
+        };
+        map.insert(serde_yaml::Value::String(key.to_lowercase()), value);
+        applied = true;
+    }
+    applied
+}
+
+// 侧栏列表的条数上限（“接下来”、公交发车），每条占一行
+const MAX_LIST_LIMIT: u64 = 50;
+
+// 解析“接下来”数据：[{name, ics: path | url} | {name, url, items: "$.departures[*]", time: "$.at", label: "$.line"}, limit, refresh_secs]
+fn parse_upcoming(value: &serde_yaml::Value) -> Option<Vec<UpcomingFeed>> {
+    let feeds = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let str_of = |key: &str| item.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+            let num_of = |key: &str| item.get(key).and_then(|v| v.as_u64()).filter(|&n| n > 0);
+            let kind = match (str_of("ics"), str_of("url")) {
+                (Some(location), _) => FeedKind::Ics(location),
+                (None, Some(url)) => FeedKind::Json {
+                    url,
+                    items: JsonPath::parse(&str_of("items")?)?,
+                    time: JsonPath::parse(&str_of("time")?)?,
+                    label: str_of("label").and_then(|p| JsonPath::parse(&p)),
+                },
+                (None, None) => return None,
+            };
+            Some(UpcomingFeed {
+                name: str_of("name")?,
+                kind,
+                limit: num_of("limit").unwrap_or(3).min(MAX_LIST_LIMIT) as usize,
+                refresh_secs: num_of("refresh_secs").unwrap_or(300).max(ratelimit::UPCOMING_MIN_SECS),
+            })
+        })
+        .collect();
+    Some(feeds)
+}
+
+// 解析蓝牙温湿度计：{address: "A4:C1:38:12:34:56", label: Bedroom}，缺少 label 时使用地址；地址无效的项跳过
+fn parse_ble_sensors(value: &serde_yaml::Value) -> Option<Vec<BleSensor>> {
+    let valid = |a: &str| a.split(':').count() == 6 && a.split(':').all(|b| b.len() == 2 && u8::from_str_radix(b, 16).is_ok());
+    let list = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let address = item.get("address")?.as_str()?.trim().to_ascii_uppercase();
+            let label = item.get("label").and_then(|v| v.as_str()).map(|s| s.to_string()).unwrap_or_else(|| address.clone());
+            valid(&address).then_some(BleSensor { address, label })
+        })
+        .collect();
+    Some(list)
+}
+
+// 解析串口传感器：{port, baud, pattern, label}，label 默认为设备名（如 ttyUSB0）；正则无效时跳过
+fn parse_serial(item: &serde_yaml::Value) -> Option<SerialConfig> {
+    let port = item.get("port")?.as_str()?.to_string();
+    let baud = match item.get("baud") {
+        Some(v) => u32::try_from(v.as_u64()?).ok().filter(|&b| b > 0)?,
+        None => serial::DEFAULT_BAUD,
+    };
+    let pattern = item.get("pattern").and_then(|v| v.as_str()).unwrap_or(serial::DEFAULT_PATTERN);
+    let label = item
+        .get("label")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| port.rsplit('/').next().unwrap_or(&port).to_string());
+    Some(SerialConfig { port, baud, pattern: regex::Regex::new(pattern).ok()?, label })
+}
+
+// 解析 DHT22：{pin, label, interval_secs}；pin 为 BCM 编号，读取间隔不少于 2 秒（默认 60）
+fn parse_dht22(value: &serde_yaml::Value) -> Option<Dht22Config> {
+    let pin = u32::try_from(value.get("pin")?.as_u64()?).ok().filter(|&p| p <= 27)?;
+    let label = value.get("label").and_then(|v| v.as_str()).unwrap_or("DHT22").to_string();
+    let interval_secs = match value.get("interval_secs") {
+        Some(v) => v.as_u64().filter(|&s| s >= 2)?,
+        None => 60,
+    };
+    Some(Dht22Config { pin, label, interval_secs })
+}
+
+// 解析 MQTT：{host, port (默认 1883), username, password, topic (默认 termclock), client_id,
+// home_assistant (默认开启), discovery_prefix (默认 homeassistant), name}；
+// client_id 默认为 termclock-主机名，同一 broker 上须唯一
+fn parse_mqtt(value: &serde_yaml::Value) -> Option<MqttConfig> {
+    let str_of = |key: &str| value.get(key).and_then(|v| v.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let port = match value.get("port") {
+        Some(v) => u16::try_from(v.as_u64()?).ok()?,
+        None => mqtt::DEFAULT_PORT,
+    };
+    let hostname = fs::read_to_string("/etc/hostname").map(|h| h.trim().to_string()).unwrap_or_default();
+    Some(MqttConfig {
+        host: str_of("host")?,
+        port,
+        username: str_of("username"),
+        password: str_of("password"),
+        topic: str_of("topic").map(|t| t.trim_end_matches('/').to_string()).unwrap_or_else(|| mqtt::DEFAULT_TOPIC.to_string()),
+        client_id: str_of("client_id").unwrap_or_else(|| format!("termclock-{}", hostname).trim_end_matches('-').to_string()),
+        home_assistant: match value.get("home_assistant") {
+            Some(v) => v.as_bool()?,
+            None => true,
+        },
+        discovery_prefix: str_of("discovery_prefix").unwrap_or_else(|| mqtt::DEFAULT_DISCOVERY_PREFIX.to_string()),
+        name: str_of("name").unwrap_or_else(|| "TermClock".to_string()),
+    })
+}
+
+// 解析脚本数据源：{name, command, refresh_secs (默认 60), timeout_secs (默认 10，最多 1 小时)}
+fn parse_scripts(value: &serde_yaml::Value) -> Option<Vec<ScriptSource>> {
+    let scripts = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let command = item.get("command")?.as_str()?.to_string();
+            let name = item.get("name").and_then(|v| v.as_str()).map(|s| s.to_string()).unwrap_or_else(|| command.clone());
+            let num_of = |key: &str, default: u64| item.get(key).map_or(Some(default), |v| v.as_u64().filter(|&n| n > 0));
+            Some(ScriptSource { name, command, refresh_secs: num_of("refresh_secs", 60)?, timeout_secs: num_of("timeout_secs", 10)?.min(script::MAX_TIMEOUT_SECS) })
+        })
+        .collect();
+    Some(scripts)
+}
+
+// 解析温度提供者链：[{type: api, url, device_code}, {type: wttr, location}, {type: open-meteo, latitude, longitude, url}]；
+// 未指定的字段使用顶层的 api_base_url、device_code、latitude / longitude 和 forecast_url
+fn parse_temperature_providers(
+    value: &serde_yaml::Value,
+    api_base_url: Option<String>,
+    device_code: Option<String>,
+    location: Option<(f64, f64)>,
+    forecast_url: Option<String>,
+) -> Option<Vec<TemperatureProvider>> {
+    let providers = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let str_of = |key: &str| item.get(key).and_then(|v| v.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+            let provider = match str_of("type")?.to_lowercase().as_str() {
+                "api" => TemperatureProvider::Api {
+                    base_url: str_of("url").or_else(|| api_base_url.clone())?,
+                    device_code: str_of("device_code")
+                        .or_else(|| item.get("device_code").and_then(|v| v.as_u64()).map(|n| n.to_string()))
+                        .or_else(|| device_code.clone())
+                        .unwrap_or_else(|| "SENS-FARM01".to_string()),
+                },
+                "wttr" | "wttr.in" => TemperatureProvider::Wttr { location: str_of("location") },
+                "open-meteo" | "open_meteo" => {
+                    let coords = item.get("latitude").and_then(|v| v.as_f64()).zip(item.get("longitude").and_then(|v| v.as_f64()));
+                    let (latitude, longitude) = coords.or(location)?;
+                    TemperatureProvider::OpenMeteo {
+                        base_url: str_of("url").or_else(|| forecast_url.clone()).unwrap_or_else(|| crate::weather::OPEN_METEO_URL.to_string()),
+                        latitude,
+                        longitude,
+                    }
+                }
+                _ => return None,
+            };
+            Some(provider)
+        })
+        .collect();
+    Some(providers)
+}
+
+// 解析省电模式：true / false / auto
+fn parse_low_power(value: &serde_yaml::Value) -> Option<LowPower> {
+    match value {
+        serde_yaml::Value::Bool(true) => Some(LowPower::On),
+        serde_yaml::Value::Bool(false) => Some(LowPower::Off),
+        serde_yaml::Value::String(s) if s.eq_ignore_ascii_case("auto") => Some(LowPower::Auto),
+        _ => None,
+    }
+}
+
+// 解析命令输出面板：{title, command, interval_secs (默认 10), max_lines (默认 10), timeout_secs (默认 10，最多 1 小时)}
+fn parse_command_panels(value: &serde_yaml::Value) -> Option<Vec<CommandPanel>> {
+    let panels = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let command = item.get("command")?.as_str()?.to_string();
+            let title = item.get("title").and_then(|v| v.as_str()).map(|s| s.to_string()).unwrap_or_else(|| command.clone());
+            let num_of = |key: &str, default: u64| item.get(key).map_or(Some(default), |v| v.as_u64().filter(|&n| n > 0));
+            Some(CommandPanel {
+                title,
+                command,
+                interval_secs: num_of("interval_secs", 10)?,
+                max_lines: num_of("max_lines", 10)? as usize,
+                timeout_secs: num_of("timeout_secs", 10)?.min(script::MAX_TIMEOUT_SECS),
+            })
+        })
+        .collect();
+    Some(panels)
+}
+
+// 解析雷达页面：true 使用默认设置，或 {url, zoom, refresh_secs, graphics: auto | kitty | sixel | text}
+fn parse_radar(value: &serde_yaml::Value) -> Option<RadarConfig> {
+    let mut radar = RadarConfig { url: None, zoom: 6, refresh_secs: 600, graphics: Graphics::Auto };
+    match value {
+        serde_yaml::Value::Bool(true) => {}
+        serde_yaml::Value::Mapping(_) => {
+            radar.url = value.get("url").and_then(|v| v.as_str()).map(|s| s.to_string());
+            if let Some(zoom) = value.get("zoom").and_then(|v| v.as_u64()) {
+                radar.zoom = u32::try_from(zoom).ok().filter(|&z| z <= 12)?;
+            }
+            if let Some(secs) = value.get("refresh_secs").and_then(|v| v.as_u64()).filter(|&s| s > 0) {
+                radar.refresh_secs = secs.max(ratelimit::RADAR_MIN_SECS);
+            }
+            if let Some(graphics) = value.get("graphics").and_then(|v| v.as_str()) {
+                radar.graphics = Graphics::parse(graphics)?;
+            }
+        }
+        _ => return None,
+    }
+    radar.graphics = radar.graphics.resolve();
+    Some(radar)
+}
+
+// 解析公交站点：{stop, stop_id, url, format: gtfs-rt | json, departures, route, destination, time | minutes, routes, limit, ...}
+fn parse_transit(item: &serde_yaml::Value) -> Option<TransitConfig> {
+    let str_of = |key: &str| item.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let num_of = |key: &str| item.get(key).and_then(|v| v.as_u64()).filter(|&n| n > 0);
+    let path_of = |key: &str| str_of(key).and_then(|p| JsonPath::parse(&p));
+    let pairs_of = |key: &str| -> Vec<(String, String)> {
+        item.get(key)
+            .and_then(|v| v.as_mapping())
+            .map(|m| {
+                m.iter()
+                    .filter_map(|(k, v)| Some((yaml_scalar(k)?, yaml_scalar(v)?)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let feed = match str_of("format").as_deref().unwrap_or("json") {
+        "gtfs-rt" | "gtfs-realtime" => TransitFeed::GtfsRealtime,
+        "json" => {
+            let (time, minutes) = (path_of("time"), path_of("minutes"));
+            if time.is_none() && minutes.is_none() {
+                return None;
+            }
+            TransitFeed::Json {
+                departures: path_of("departures")?,
+                route: path_of("route")?,
+                destination: path_of("destination"),
+                time,
+                minutes,
+            }
+        }
+        _ => return None,
+    };
+    let stop_id = item.get("stop_id").and_then(yaml_scalar);
+    Some(TransitConfig {
+        stop: str_of("stop").or_else(|| stop_id.clone()).unwrap_or_else(|| "Departures".to_string()),
+        stop_id,
+        url: str_of("url")?,
+        headers: pairs_of("headers"),
+        feed,
+        route_names: pairs_of("routes").into_iter().collect(),
+        limit: num_of("limit").unwrap_or(4).min(MAX_LIST_LIMIT) as usize,
+        refresh_secs: num_of("refresh_secs").unwrap_or(30).max(ratelimit::TRANSIT_MIN_SECS),
+        stale_after_secs: num_of("stale_after_secs").unwrap_or(120),
+    })
+}
+
+// 解析 NTP 偏差检查：ntp: pool.ntp.org 或 {server: pool.ntp.org, refresh_secs: 3600, warn_ms: 500}
+fn parse_ntp(value: &serde_yaml::Value) -> Option<NtpConfig> {
+    let num_of = |key: &str| value.get(key).and_then(|v| v.as_u64()).filter(|&n| n > 0);
+    let server = match value {
+        serde_yaml::Value::String(s) => s.trim().to_string(),
+        _ => value.get("server")?.as_str()?.trim().to_string(),
+    };
+    Some(NtpConfig {
+        server: Some(server).filter(|s| !s.is_empty())?,
+        refresh_secs: num_of("refresh_secs").unwrap_or(3600).max(ratelimit::NTP_MIN_SECS),
+        warn_ms: num_of("warn_ms").unwrap_or(500),
+    })
+}
+
+// clock_frame: double 或 {style: rounded, corners: "◆◆◆◆", color: darkgray}；none 为不绘制
+pub fn parse_clock_frame(value: &serde_yaml::Value) -> Option<ClockFrame> {
+    if let serde_yaml::Value::String(s) = value {
+        return FrameStyle::parse(s).map(|style| ClockFrame { style, corners: None, color: None });
+    }
+    let text = |key: &str| value.get(key).and_then(|v| v.as_str());
+    let corners = match text("corners") {
+        Some(s) => Some(parse_corners(s)?),
+        None => None,
+    };
+    Some(ClockFrame {
+        style: text("style").map_or(Some(FrameStyle::Box), FrameStyle::parse)?,
+        corners,
+        color: text("color").and_then(parse_color),
+    })
+}
+
+// auto_rotate: {pages: [clock, weather, events], interval: 15, grace: 60}；未知的页面名称忽略
+fn parse_auto_rotate(value: &serde_yaml::Value) -> Option<AutoRotate> {
+    let num_of = |key: &str| value.get(key).and_then(|v| v.as_u64()).filter(|&n| n > 0);
+    let pages: Vec<View> = value.get("pages")?.as_sequence()?.iter().filter_map(|p| p.as_str()).filter_map(View::parse).collect();
+    Some(AutoRotate {
+        pages: Some(pages).filter(|p| !p.is_empty())?,
+        interval_secs: num_of("interval").unwrap_or(15).max(2),
+        grace_secs: value.get("grace").and_then(|v| v.as_u64()).unwrap_or(60),
+    })
+}
+
+// idle_dim: 10（分钟，变暗）或 {after: 10, mode: clock}
+pub fn parse_idle_dim(value: &serde_yaml::Value) -> Option<IdleDim> {
+    let (minutes, mode) = match value {
+        serde_yaml::Value::Number(n) => (n.as_u64()?, IdleMode::Dim),
+        _ => {
+            let mode = match value.get("mode").and_then(|v| v.as_str()) {
+                Some(mode) => IdleMode::parse(mode)?,
+                None => IdleMode::Dim,
+            };
+            (value.get("after")?.as_u64()?, mode)
+        }
+    };
+    Some(IdleDim { after_secs: minutes.max(1) * 60, mode })
+}
+
+// big_temperature: true 或 {scale_x: 2, scale_y: 2, color: orange}；缩放至少为 1
+pub fn parse_big_temperature(value: &serde_yaml::Value) -> Option<BigTemperature> {
+    match value {
+        serde_yaml::Value::Bool(enabled) => return enabled.then(BigTemperature::default),
+        serde_yaml::Value::Mapping(_) => {}
+        _ => return None,
+    }
+    let scale = |key: &str| match value.get(key) {
+        None => Some(None),
+        Some(v) => v.as_u64().and_then(|n| u16::try_from(n).ok()).filter(|&n| n >= 1).map(Some),
+    };
+    let color = match value.get("color") {
+        None => None,
+        Some(v) => Some(parse_color(v.as_str()?)?),
+    };
+    Some(BigTemperature { scale_x: scale("scale_x")?, scale_y: scale("scale_y")?, color })
+}
+
+// humidity_gauge: true 或 {bar_color: cyan, zone_color: green, scale_color: gray}
+pub fn parse_humidity_gauge(value: &serde_yaml::Value) -> Option<HumidityGauge> {
+    match value {
+        serde_yaml::Value::Bool(enabled) => return enabled.then(HumidityGauge::default),
+        serde_yaml::Value::Mapping(_) => {}
+        _ => return None,
+    }
+    let base = HumidityGauge::default();
+    let color = |key: &str| match value.get(key) {
+        None => Some(None),
+        Some(v) => v.as_str().and_then(parse_color).map(Some),
+    };
+    Some(HumidityGauge {
+        bar: color("bar_color")?.unwrap_or(base.bar),
+        zone: color("zone_color")?.unwrap_or(base.zone),
+        scale: color("scale_color")?.unwrap_or(base.scale),
     })
 }
 
+// weather_accents: true 或 {rain_color: blue, snow_color: white, hot_color: orange, hot_above: 30}
+pub fn parse_weather_accents(value: &serde_yaml::Value) -> Option<WeatherAccents> {
+    match value {
+        serde_yaml::Value::Bool(enabled) => return enabled.then(WeatherAccents::default),
+        serde_yaml::Value::Mapping(_) => {}
+        _ => return None,
+    }
+    let base = WeatherAccents::default();
+    let color = |key: &str| value.get(key).and_then(|v| v.as_str()).and_then(parse_color);
+    Some(WeatherAccents {
+        rain: color("rain_color").unwrap_or(base.rain),
+        snow: color("snow_color").unwrap_or(base.snow),
+        hot: color("hot_color").unwrap_or(base.hot),
+        hot_above: value.get("hot_above").and_then(|v| v.as_f64()).unwrap_or(base.hot_above),
+    })
+}
+
+// 字符串或数字（stop_id、route_id 常写成数字）
+fn yaml_scalar(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+// 解析每日时刻标记：[{name: Fajr, time: "05:12"}, {name: Sunset, time: sunset-10, chime: "short short"}]
+fn parse_markers(value: &serde_yaml::Value, patterns: &HashMap<String, BeepPattern>) -> Option<Vec<MarkerSpec>> {
+    let markers = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let str_of = |key: &str| item.get(key).and_then(|v| v.as_str());
+            Some(MarkerSpec {
+                name: str_of("name")?.trim().to_string(),
+                time: MarkerTime::parse(str_of("time")?)?,
+                pattern: str_of("chime").and_then(|spec| resolve_pattern(spec, patterns)),
+            })
+        })
+        .collect();
+    Some(markers)
+}
+
+// 解析时钟各部分颜色：{hours: cyan, minutes: white, seconds: darkgray, separators: gray}
+fn parse_time_colors(value: &serde_yaml::Value) -> Option<TimeColors> {
+    let color = |key: &str| value.get(key).and_then(|v| v.as_str()).and_then(parse_color);
+    value.as_mapping()?;
+    Some(TimeColors {
+        hours: color("hours"),
+        minutes: color("minutes"),
+        seconds: color("seconds"),
+        separators: color("separators"),
+    })
+}
+
+// 解析多时钟：[{label: 北京}, {label: UTC, timezone: UTC, time_scale_x: 1, time_color: cyan, date_color: gray, time_font: dots}]；
+// timezone 为 IANA 时区名，缺省或 local 为本地时间；时区无法识别的时钟会被忽略
+fn parse_clocks(value: &serde_yaml::Value) -> Option<Vec<ClockSpec>> {
+    let clocks = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let str_of = |key: &str| item.get(key).and_then(|v| v.as_str()).map(str::trim);
+            let scale = |key: &str| item.get(key).and_then(|v| v.as_u64()).and_then(|n| u16::try_from(n).ok()).filter(|n| *n > 0);
+            let timezone = match str_of("timezone") {
+                None | Some("local") => None,
+                Some(name) => Some(crate::palette::parse_tz(name)?),
+            };
+            Some(ClockSpec {
+                label: str_of("label").filter(|s| !s.is_empty()).map(str::to_string),
+                timezone,
+                time_scale_x: scale("time_scale_x"),
+                time_scale_y: scale("time_scale_y"),
+                time_color: str_of("time_color").and_then(parse_color),
+                date_color: str_of("date_color").and_then(parse_color),
+                time_font: str_of("time_font").and_then(TimeFont::parse),
+            })
+        })
+        .collect();
+    Some(clocks)
+}
+
+// 解析组件样式：{todos: {title: TODOS, border: true}, greenhouse: {title: GREENHOUSE}}，键为组件名或数据源名称
+fn parse_widgets(value: &serde_yaml::Value) -> Option<HashMap<String, WidgetStyle>> {
+    let widgets = value
+        .as_mapping()?
+        .iter()
+        .filter_map(|(name, item)| {
+            let title = item.get("title").and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty());
+            Some((
+                name.as_str()?.trim().to_string(),
+                WidgetStyle {
+                    title: title.map(str::to_string),
+                    border: item.get("border").and_then(|v| v.as_bool()).unwrap_or(false),
+                    align: item.get("align").and_then(|v| v.as_str()).and_then(parse_alignment),
+                    background: item.get("background").and_then(|v| v.as_str()).and_then(parse_color),
+                },
+            ))
+        })
+        .collect();
+    Some(widgets)
+}
+
+// layout: {padding: 1, margin: 1}；padding 为一个数（四周相同）或 [上下, 左右]
+pub fn parse_layout(value: &serde_yaml::Value) -> Option<LayoutSpacing> {
+    let number = |v: &serde_yaml::Value| v.as_u64().and_then(|n| u16::try_from(n).ok());
+    let padding = match value.get("padding") {
+        None => (0, 0),
+        Some(serde_yaml::Value::Sequence(pair)) if pair.len() == 2 => (number(&pair[0])?, number(&pair[1])?),
+        Some(v) => {
+            let n = number(v)?;
+            (n, n)
+        }
+    };
+    let margin = match value.get("margin") {
+        None => 0,
+        Some(v) => number(v)?,
+    };
+    value.as_mapping().map(|_| LayoutSpacing { padding, margin })
+}
+
+// 解析主题列表：[{name: night, time_color: darkgray, date_color: "#554400"}]
+fn parse_themes(value: &serde_yaml::Value) -> Option<Vec<Theme>> {
+    let themes = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let color = |key: &str| item.get(key).and_then(|v| v.as_str()).and_then(parse_color);
+            // 设置了任一状态颜色时，其余状态颜色使用默认值
+            let keys = ["alert_color", "warn_color", "ok_color", "stale_color", "info_color"];
+            let palette = keys.iter().any(|k| color(k).is_some()).then(|| {
+                let base = Palette::default();
+                Palette {
+                    alert: color("alert_color").unwrap_or(base.alert),
+                    warn: color("warn_color").unwrap_or(base.warn),
+                    ok: color("ok_color").unwrap_or(base.ok),
+                    stale: color("stale_color").unwrap_or(base.stale),
+                    info: color("info_color").unwrap_or(base.info),
+                }
+            });
+            Some(Theme {
+                name: item.get("name")?.as_str()?.trim().to_string(),
+                time_color: color("time_color"),
+                date_color: color("date_color"),
+                todos_color: color("todos_color"),
+                palette,
+            })
+        })
+        .collect();
+    Some(themes)
+}
+
+// 解析闹钟列表：[{time: "07:30", label: 起床}] 或 [{cron: "30 7 * * 1-5", label: ...}]
+fn parse_alarms(value: &serde_yaml::Value) -> Option<Vec<Alarm>> {
+    let alarms = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let cron = match (item.get("cron").and_then(|v| v.as_str()), item.get("time").and_then(|v| v.as_str())) {
+                (Some(expr), _) => CronSchedule::parse(expr)?,
+                (None, Some(time)) => parse_alarm_time(time)?,
+                (None, None) => return None,
+            };
+            let label = item.get("label").and_then(|v| v.as_str()).unwrap_or("Alarm").trim().to_string();
+            Some(Alarm { cron, label })
+        })
+        .collect();
+    Some(alarms)
+}
+
+// 解析自定义数据源：[{name, url | ws_url, method, headers, body, value: "$.x" | list: "$.items[*].title", format}]；
+// GraphQL 写作 {graphql: endpoint, query, variables}，转为 POST {"query", "variables"} 请求；
+// Redis 写作 {redis: "redis://host/0", key, subscribe: channel}，value / list 可省略
+fn parse_custom_sources(value: &serde_yaml::Value) -> Option<Vec<CustomSource>> {
+    let sources = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let str_of = |key: &str| item.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+            let redis = str_of("redis");
+            let extract = match (str_of("value"), str_of("list")) {
+                (Some(path), _) => Extract::Value(JsonPath::parse(&path)?),
+                (None, Some(path)) => Extract::List(JsonPath::parse(&path)?),
+                (None, None) if redis.is_some() => Extract::Auto,
+                (None, None) => return None,
+            };
+            let headers = item
+                .get("headers")
+                .and_then(|v| v.as_mapping())
+                .map(|m| {
+                    m.iter()
+                        .filter_map(|(k, v)| Some((k.as_str()?.to_string(), v.as_str()?.to_string())))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let graphql = str_of("graphql");
+            let (url, transport) = match (graphql.clone().or_else(|| str_of("url")), str_of("ws_url")) {
+                _ if redis.is_some() => {
+                    (redis.clone()?, Transport::Redis { key: str_of("key")?, channel: str_of("subscribe") })
+                }
+                (Some(url), _) => (url, Transport::Poll),
+                (None, Some(url)) => (url, Transport::WebSocket),
+                (None, None) => return None,
+            };
+            let is_graphql = graphql.is_some();
+            let (method, body) = match graphql {
+                Some(_) => {
+                    let variables = item.get("variables").and_then(|v| serde_json::to_value(v).ok()).unwrap_or_else(|| serde_json::json!({}));
+                    let body = serde_json::json!({"query": str_of("query")?, "variables": variables});
+                    ("POST".to_string(), Some(body.to_string()))
+                }
+                None => (str_of("method").unwrap_or_else(|| "GET".to_string()), str_of("body")),
+            };
+            Some(CustomSource {
+                name: str_of("name").unwrap_or_else(|| url.clone()),
+                graphql: is_graphql,
+                url,
+                transport,
+                method,
+                headers,
+                body,
+                extract,
+                format: str_of("format"),
+                refresh_secs: item.get("refresh").and_then(|v| v.as_u64()).filter(|&n| n > 0).unwrap_or(60).max(ratelimit::CUSTOM_SOURCE_MIN_SECS),
+                limit: item.get("limit").and_then(|v| v.as_u64()).map(|n| n as usize).unwrap_or(5),
+            })
+        })
+        .collect();
+    Some(sources)
+}
+
+// 解析接口格式：{temperature: {path, body, rows, temp, humidity, code, msg},
+// todos: {path, body, rows, total, task, deadline, created, completed, id, code, msg},
+// todo_add / todo_done / todo_remove: {path, body, code, msg}}；
+// 未写的部分沿用默认值，任一 JSONPath 无效时整个配置无效
+pub fn parse_api_schema(value: &serde_yaml::Value) -> Option<ApiSchema> {
+    let mut schema = ApiSchema::default();
+    for (key, endpoint) in [
+        ("temperature", &mut schema.temperature),
+        ("todos", &mut schema.todos),
+        ("todo_add", &mut schema.todo_add),
+        ("todo_done", &mut schema.todo_done),
+        ("todo_remove", &mut schema.todo_remove),
+    ] {
+        let Some(item) = value.as_mapping()?.get(key) else { continue };
+        item.as_mapping()?;
+        let str_of = |key: &str| item.get(key).and_then(|v| v.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        if let Some(path) = str_of("path") {
+            endpoint.path = path;
+        }
+        if let Some(body) = str_of("body") {
+            endpoint.body = body;
+        }
+        for (name, path) in endpoint.fields.iter_mut() {
+            if let Some(spec) = str_of(name) {
+                *path = JsonPath::parse(&spec)?;
+            }
+        }
+    }
+    Some(schema)
+}
+
+// 解析待办来源列表：[{type: api | file | db | taskwarrior | redis, url, path, filter, key, limit, tag, color}]；
+// api 未指定 url 时使用 api_base_url
+fn parse_todo_sources(
+    value: &serde_yaml::Value,
+    api_base_url: Option<String>,
+    todo_limit: Option<usize>,
+    schema: &ApiSchema,
+) -> Option<Vec<TodoSource>> {
+    let sources = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let str_of = |key: &str| item.get(key).and_then(|v| v.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+            let backend = match str_of("type")?.to_lowercase().as_str() {
+                "api" => TodoBackend::Api {
+                    base_url: str_of("url").or_else(|| api_base_url.clone())?,
+                    limit: item.get("limit").and_then(|v| v.as_u64()).map(|n| n as usize).or(todo_limit).unwrap_or(4),
+                    schema: Box::new(schema.clone()),
+                },
+                "redis" => TodoBackend::Redis { url: str_of("url")?, key: str_of("key")? },
+                "file" => TodoBackend::File(str_of("path")?.into()),
+                "db" | "sqlite" => TodoBackend::Db(str_of("path")?.into()),
+                "taskwarrior" | "task" => {
+                    TodoBackend::Taskwarrior(str_of("filter").map(|f| f.split_whitespace().map(str::to_string).collect()).unwrap_or_default())
+                }
+                _ => return None,
+            };
+            Some(TodoSource { backend, tag: str_of("tag"), color: str_of("color").and_then(|c| parse_color(&c)) })
+        })
+        .collect();
+    Some(sources)
+}
+
+// 解析具名提示音表：{triple: "long short short", ...}
+pub fn parse_pattern_table(value: &serde_yaml::Value) -> HashMap<String, BeepPattern> {
+    let Some(table) = value.as_mapping() else { return HashMap::new() };
+    table
+        .iter()
+        .filter_map(|(k, v)| Some((k.as_str()?.to_string(), BeepPattern::parse(v.as_str()?)?)))
+        .collect()
+}
+
+// 先按名称查找具名样式，否则按内联样式解析
+pub fn resolve_pattern(spec: &str, patterns: &HashMap<String, BeepPattern>) -> Option<BeepPattern> {
+    patterns.get(spec.trim()).cloned().or_else(|| BeepPattern::parse(spec))
+}
+
+// 解析报时规则列表：[{cron: "0,30 * * * *", pattern: short, message, command, sound, visual}]；
+// cron 无效的规则被跳过（termclock doctor 会逐条报告）
+fn parse_chimes(value: &serde_yaml::Value, patterns: &HashMap<String, BeepPattern>) -> Option<Vec<ChimeRule>> {
+    let rules = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|item| {
+            let cron = item.get("cron")?.as_str()?;
+            let str_of = |key: &str| item.get(key).and_then(|v| v.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+            Some(ChimeRule {
+                cron: CronSchedule::parse(cron)?,
+                pattern: str_of("pattern").and_then(|spec| resolve_pattern(&spec, patterns)),
+                message: str_of("message"),
+                command: str_of("command"),
+                sound: item.get("sound").and_then(|v| v.as_bool()).unwrap_or(true),
+                visual: item.get("visual").and_then(|v| v.as_bool()),
+            })
+        })
+        .collect();
+    Some(rules)
+}
+
+// 贪睡时长的范围（分钟）
+const MAX_SNOOZE_MINUTES: u64 = 24 * 60;
+// 判定离线前等待的分钟数上限
+const MAX_OFFLINE_AFTER_MINS: u64 = 24 * 60;
+// 降水提醒向前查看的分钟数上限
+const MAX_RAIN_ALERT_MINUTES: u64 = 24 * 60;
+// 会议提前提醒的分钟数上限（7 天）
+const MAX_REMIND_MINUTES: u64 = 7 * 24 * 60;
+
 pub fn parse_args() -> Config {
     // 默认值
     let mut time_scale_x: u16 = 2;
@@ -72,17 +1005,109 @@ pub fn parse_args() -> Config {
     let mut date_color = Color::Yellow;
     let mut todos_color = Color::White;
     let mut chime_enabled = true;
+    let mut chimes: Option<Vec<ChimeRule>> = None;
+    let mut chime_pattern: Option<BeepPattern> = None;
+    let mut alarm_pattern = BeepPattern::alarm_default();
+    let mut chime_visual = false;
+    let mut chime_audible = true;
+    let mut mute_minutes: Option<u64> = None;
+    let mut ipc_socket = crate::ipc::default_socket_path();
+    let mut alarms: Vec<Alarm> = Vec::new();
+    let mut snooze_minutes: u64 = 9;
+    let mut gcal: Option<GcalConfig> = None;
+    let mut work_hours: Option<WorkHours> = None;
+    let mut history_enabled = false;
+    let mut history_file = crate::history::default_history_path();
+    let mut history_interval: u64 = 60;
+    let mut web_listen: Option<String> = None;
+    let mut web_push_secret: Option<String> = None;
+    let mut screenshot_dir = std::path::PathBuf::from(".");
+    let mut custom_sources: Vec<CustomSource> = Vec::new();
+    let mut todo_db: Option<std::path::PathBuf> = None;
+    let mut todos_file: Option<std::path::PathBuf> = None;
+    let mut todo_limit: Option<usize> = None;
+    let mut todo_priority = true;
+    let mut todo_group = true;
+    let mut todo_summary = false;
+    let mut todo_rotate_secs: Option<u64> = None;
+    let mut todo_sources: Vec<TodoSource> = Vec::new();
+    let mut offline_after_mins: u64 = 5;
+    let mut offline_retry_secs: u64 = 60;
+    let mut stale_primary_secs: u64 = 1800;
+    let mut max_concurrent_requests = ratelimit::DEFAULT_MAX_CONCURRENT;
+    let mut api_schema = ApiSchema::default();
+    let mut low_bandwidth = false;
+    let mut low_power = LowPower::Auto;
+    let mut ascii = crate::ascii::detect();
+    let mut todo_task_max_chars: Option<usize> = None;
+    let mut todo_sort: Option<TodoSort> = None;
+    let mut todo_priority_colors = [Color::LightRed, Color::Yellow, Color::LightBlue];
     let mut api_base_url: Option<String> = None;
     let mut device_code: String = "SENS-FARM01".to_string();
     let mut temp_refresh_interval: u64 = 5;
     let mut todo_ip_filter: Option<String> = None;
+    let mut themes: Vec<Theme> = Vec::new();
+    let mut theme: Option<String> = None;
+    let mut state_file = crate::state::default_state_path();
+    let mut status_bar = false;
+    let mut time_colors = TimeColors::default();
+    let mut clocks: Vec<ClockSpec> = Vec::new();
+    let mut auto_rotate = None;
+    let mut idle_dim = None;
+    let mut big_temperature = None;
+    let mut secondary_calendar: Option<SecondaryCalendar> = None;
+    let mut widgets: HashMap<String, WidgetStyle> = HashMap::new();
+    let mut layout = LayoutSpacing::default();
+    let mut time_effect = TimeEffect::None;
+    let mut time_effect_color = Color::DarkGray;
+    let mut time_font = TimeFont::Default;
+    let mut show_subseconds = Subseconds::Off;
+    let mut clock_style = ClockStyle::Plain;
+    let mut clock_frame = None;
+    let mut background_effect = BackgroundEffect::None;
+    let mut background_density = 0.3;
+    let mut background_effect_color = None;
+    let mut background_color = None;
+    let mut countdowns = vec![Countdown::new_year()];
+    let mut countdown_celebrate_secs = 10;
+    let mut markers = Vec::new();
+    let mut markers_command = None;
+    let mut markers_chime = false;
+    let mut location = None;
+    let mut sun_bar = false;
+    let mut upcoming = Vec::new();
+    let mut transit = None;
+    let mut ntp = None;
+    let mut time_sync = true;
+    let mut compare_indoor_outdoor = false;
+    let mut thermometer_style = ThermometerStyle::Bar;
+    let mut temp_min_max = true;
+    let mut show_comfort = false;
+    let mut comfort = crate::comfort::ComfortThresholds::default();
+    let mut humidity_gauge = None;
+    let mut rain_alert = false;
+    let mut rain_alert_minutes: u64 = 60;
+    let mut rain_threshold_mm = 0.1;
+    let mut rain_alert_chime = true;
+    let mut forecast_url = crate::weather::OPEN_METEO_URL.to_string();
+    let mut weather_accents = None;
+    let mut radar = None;
+    let mut ble_sensors = Vec::new();
+    let mut serial = Vec::new();
+    let mut dht22 = None;
+    let mut mqtt = None;
+    let mut on_hour_command: Option<String> = None;
+    let mut on_alarm_command: Option<String> = None;
+    let mut scripts = Vec::new();
+    let mut command_panels = Vec::new();
+    let mut temperature_providers = None;
 
     // 从配置文件加载所有设置
     if let Some(file_cfg) = load_yaml_config() {
         // API配置
         if file_cfg.api_base_url.is_some() { api_base_url = file_cfg.api_base_url.clone(); }
         if let Some(device) = file_cfg.device_code { device_code = device; }
-        if let Some(interval) = file_cfg.temp_refresh_interval { temp_refresh_interval = interval; }
+        if let Some(interval) = file_cfg.temp_refresh_interval { temp_refresh_interval = interval.max(ratelimit::SENSOR_API_MIN_SECS); }
         if file_cfg.todo_ip_filter.is_some() { todo_ip_filter = file_cfg.todo_ip_filter.clone(); }
         main_window_percent = file_cfg.main_window_percent;
         
@@ -91,20 +1116,157 @@ pub fn parse_args() -> Config {
         if let Some(scale) = file_cfg.time_scale_y { time_scale_y = scale; }
         if let Some(scale) = file_cfg.date_scale_x { date_scale_x = scale; }
         if let Some(chime) = file_cfg.chime_enabled { chime_enabled = chime; }
-        
-        // 颜色配置
-        if let Some(color_str) = file_cfg.time_color {
-            if let Some(color) = parse_color(&color_str) { time_color = color; }
+        if file_cfg.chimes.is_some() { chimes = file_cfg.chimes.clone(); }
+        if file_cfg.chime_pattern.is_some() { chime_pattern = file_cfg.chime_pattern.clone(); }
+        if let Some(pattern) = file_cfg.alarm_pattern { alarm_pattern = pattern; }
+        if let Some(visual) = file_cfg.chime_visual { chime_visual = visual; }
+        if let Some(audible) = file_cfg.chime_audible { chime_audible = audible; }
+        if file_cfg.mute_minutes.is_some() { mute_minutes = file_cfg.mute_minutes; }
+        if let Some(path) = file_cfg.ipc_socket { ipc_socket = path.into(); }
+        if let Some(list) = file_cfg.alarms { alarms = list; }
+        if let Some(minutes) = file_cfg.snooze_minutes { snooze_minutes = minutes.clamp(1, MAX_SNOOZE_MINUTES); }
+
+        // 历史记录
+        if let Some(enabled) = file_cfg.history_enabled { history_enabled = enabled; }
+        if let Some(path) = file_cfg.history_file.clone() { history_file = path.into(); }
+        if let Some(interval) = file_cfg.history_interval { history_interval = interval; }
+        if file_cfg.web_listen.is_some() { web_listen = file_cfg.web_listen.clone(); }
+        if file_cfg.web_push_secret.is_some() { web_push_secret = file_cfg.web_push_secret.clone(); }
+        if let Some(dir) = file_cfg.screenshot_dir.clone() { screenshot_dir = dir.into(); }
+        if let Some(list) = file_cfg.custom_sources.clone() { custom_sources = list; }
+        if let Some(path) = file_cfg.todo_db.clone() { todo_db = Some(path.into()); }
+        if let Some(path) = file_cfg.todos_file.clone() { todos_file = Some(path.into()); }
+        if file_cfg.todo_limit.is_some() { todo_limit = file_cfg.todo_limit; }
+        if let Some(enabled) = file_cfg.todo_priority { todo_priority = enabled; }
+        if let Some(enabled) = file_cfg.todo_group { todo_group = enabled; }
+        if let Some(enabled) = file_cfg.todo_summary { todo_summary = enabled; }
+        if let Some(secs) = file_cfg.todo_rotate_secs { todo_rotate_secs = Some(secs).filter(|&s| s > 0); }
+        if let Some(list) = file_cfg.todo_sources.clone() { todo_sources = list; }
+        if let Some(mins) = file_cfg.offline_after_mins { offline_after_mins = mins.min(MAX_OFFLINE_AFTER_MINS); }
+        if let Some(secs) = file_cfg.offline_retry_secs { offline_retry_secs = secs; }
+        if let Some(secs) = file_cfg.stale_primary_secs { stale_primary_secs = secs; }
+        if let Some(n) = file_cfg.max_concurrent_requests { max_concurrent_requests = n.max(1); }
+        if let Some(schema) = file_cfg.api_schema { api_schema = schema; }
+        if let Some(enabled) = file_cfg.low_bandwidth { low_bandwidth = enabled; }
+        if let Some(mode) = file_cfg.low_power { low_power = mode; }
+        if let Some(enabled) = file_cfg.ascii { ascii = enabled; }
+        if file_cfg.todo_task_max_chars.is_some() { todo_task_max_chars = file_cfg.todo_task_max_chars; }
+        if let Some(name) = &file_cfg.todo_sort { todo_sort = TodoSort::parse(name); }
+        if let Some(list) = file_cfg.themes.clone() { themes = list; }
+        if file_cfg.theme.is_some() { theme = file_cfg.theme.clone(); }
+        if let Some(path) = file_cfg.state_file.clone() { state_file = path.into(); }
+        if let Some(enabled) = file_cfg.status_bar { status_bar = enabled; }
+        if let Some(colors) = file_cfg.time_colors { time_colors = colors; }
+        if let Some(list) = file_cfg.clocks.clone() { clocks = list; }
+        if let Some(rotate) = file_cfg.auto_rotate.clone() { auto_rotate = Some(rotate); }
+        if let Some(idle) = file_cfg.idle_dim { idle_dim = Some(idle); }
+        if let Some(big) = file_cfg.big_temperature { big_temperature = Some(big); }
+        if let Some(calendar) = file_cfg.secondary_calendar.as_deref().and_then(SecondaryCalendar::parse) { secondary_calendar = Some(calendar); }
+        if let Some(styles) = file_cfg.widgets.clone() { widgets = styles; }
+        if let Some(spacing) = file_cfg.layout { layout = spacing; }
+        if let Some(effect) = file_cfg.time_effect.as_deref().and_then(TimeEffect::parse) { time_effect = effect; }
+        if let Some(color) = file_cfg.time_effect_color.as_deref().and_then(parse_color) { time_effect_color = color; }
+        if let Some(font) = file_cfg.time_font.as_deref().and_then(TimeFont::parse) { time_font = font; }
+        if let Some(mode) = file_cfg.show_subseconds.as_deref().and_then(Subseconds::parse) { show_subseconds = mode; }
+        if let Some(style) = file_cfg.clock_style.as_deref().and_then(ClockStyle::parse) { clock_style = style; }
+        if let Some(frame) = file_cfg.clock_frame { clock_frame = Some(frame); }
+        if let Some(effect) = file_cfg.background_effect.as_deref().and_then(BackgroundEffect::parse) { background_effect = effect; }
+        if let Some(density) = file_cfg.background_density { background_density = density.clamp(0.0, 1.0); }
+        if let Some(color) = file_cfg.background_effect_color.as_deref().and_then(parse_color) { background_effect_color = Some(color); }
+        if let Some(color) = file_cfg.background_color.as_deref().and_then(parse_color) {
+            // 旧配置中 background_color 是背景动画的颜色：开启了动画且没有 background_effect_color 时沿用这一含义
+            if background_effect != BackgroundEffect::None && file_cfg.background_effect_color.is_none() {
+                background_effect_color = Some(color);
+            } else {
+                background_color = Some(color);
+            }
         }
-        if let Some(color_str) = file_cfg.date_color {
-            if let Some(color) = parse_color(&color_str) { date_color = color; }
+        // countdowns: [] 关闭（包括默认的跨年倒计时）
+        if let Some(list) = &file_cfg.countdowns { countdowns = list.iter().filter_map(|s| Countdown::parse(s)).collect(); }
+        if let Some(secs) = file_cfg.countdown_celebrate_secs { countdown_celebrate_secs = secs; }
+        if let Some(list) = file_cfg.markers.clone() { markers = list; }
+        if let Some(command) = file_cfg.markers_command.clone() { markers_command = Some(command); }
+        if let Some(enabled) = file_cfg.markers_chime { markers_chime = enabled; }
+        if let (Some(lat), Some(lon)) = (file_cfg.latitude, file_cfg.longitude) { location = Some((lat, lon)); }
+        if let Some(enabled) = file_cfg.sun_bar { sun_bar = enabled; }
+        if let Some(list) = file_cfg.upcoming.clone() { upcoming = list; }
+        if let Some(stop) = file_cfg.transit.clone() { transit = Some(stop); }
+        if let Some(server) = file_cfg.ntp.clone() { ntp = Some(server); }
+        if let Some(enabled) = file_cfg.time_sync { time_sync = enabled; }
+        if let Some(enabled) = file_cfg.compare_indoor_outdoor { compare_indoor_outdoor = enabled; }
+        if let Some(style) = file_cfg.thermometer_style.as_deref().and_then(ThermometerStyle::parse) { thermometer_style = style; }
+        if let Some(enabled) = file_cfg.temp_min_max { temp_min_max = enabled; }
+        if let Some(enabled) = file_cfg.show_comfort { show_comfort = enabled; }
+        if let Some(value) = file_cfg.comfort_dry_below { comfort.dry_below = value; }
+        if let Some(value) = file_cfg.comfort_humid_above { comfort.humid_above = value; }
+        if let Some(value) = file_cfg.comfort_dew_point_max { comfort.dew_point_max = value; }
+        if let Some(gauge) = file_cfg.humidity_gauge { humidity_gauge = Some(gauge); }
+        if let Some(enabled) = file_cfg.rain_alert { rain_alert = enabled; }
+        if let Some(minutes) = file_cfg.rain_alert_minutes { rain_alert_minutes = minutes.min(MAX_RAIN_ALERT_MINUTES); }
+        if let Some(mm) = file_cfg.rain_threshold_mm { rain_threshold_mm = mm; }
+        if let Some(enabled) = file_cfg.rain_alert_chime { rain_alert_chime = enabled; }
+        if let Some(url) = file_cfg.forecast_url.clone() { forecast_url = url; }
+        if let Some(accents) = file_cfg.weather_accents { weather_accents = Some(accents); }
+        if let Some(page) = file_cfg.radar.clone() { radar = Some(page); }
+        if let Some(list) = file_cfg.ble_sensors.clone() { ble_sensors = list; }
+        if let Some(list) = file_cfg.serial.clone() { serial = list; }
+        if let Some(sensor) = file_cfg.dht22.clone() { dht22 = Some(sensor); }
+        if let Some(broker) = file_cfg.mqtt.clone() { mqtt = Some(broker); }
+        if file_cfg.on_hour_command.is_some() { on_hour_command = file_cfg.on_hour_command.clone(); }
+        if file_cfg.on_alarm_command.is_some() { on_alarm_command = file_cfg.on_alarm_command.clone(); }
+        if let Some(list) = file_cfg.scripts.clone() { scripts = list; }
+        if let Some(list) = file_cfg.command_panels.clone() { command_panels = list; }
+        if let Some(list) = file_cfg.temperature_providers.clone() { temperature_providers = Some(list); }
+        if let Some(colors) = &file_cfg.todo_priority_colors {
+            for (slot, name) in todo_priority_colors.iter_mut().zip(colors) {
+                if let Some(color) = parse_color(name) { *slot = color; }
+            }
         }
-        if let Some(color_str) = file_cfg.todos_color {
-            if let Some(color) = parse_color(&color_str) { todos_color = color; }
+
+        // 工作时间：默认周一至周五
+        if let Some(range) = &file_cfg.work_hours {
+            let days = file_cfg.work_days.as_deref().unwrap_or("1-5");
+            work_hours = WorkHours::parse(range, days, file_cfg.work_timezone.as_deref());
+        }
+
+        // Google 日历
+        if let (Some(client_id), Some(client_secret)) = (file_cfg.gcal_client_id, file_cfg.gcal_client_secret) {
+            gcal = Some(GcalConfig {
+                client: crate::auth::OAuthClient { client_id, client_secret },
+                calendar_id: file_cfg.gcal_calendar_id.unwrap_or_else(|| "primary".to_string()),
+                token_path: file_cfg.gcal_token_file.map(Into::into).unwrap_or_else(crate::auth::default_token_path),
+                refresh_secs: 60,
+                remind_minutes: file_cfg.gcal_remind_minutes.unwrap_or(5).min(MAX_REMIND_MINUTES),
+                pattern: file_cfg.gcal_pattern.unwrap_or_else(|| BeepPattern::parse("short short").expect("valid pattern")),
+            });
         }
+        
+        // 颜色配置
+        if let Some(color_str) = file_cfg.time_color
+            && let Some(color) = parse_color(&color_str) { time_color = color; }
+        if let Some(color_str) = file_cfg.date_color
+            && let Some(color) = parse_color(&color_str) { date_color = color; }
+        if let Some(color_str) = file_cfg.todos_color
+            && let Some(color) = parse_color(&color_str) { todos_color = color; }
     }
 
-    // 所有参数都从配置文件读取，不再支持命令行参数
+    // 未配置报时规则时默认整点报时
+    let chimes = chimes.unwrap_or_else(|| {
+        vec![ChimeRule {
+            cron: CronSchedule::parse("0 * * * *").expect("valid cron"),
+            pattern: None,
+            message: None,
+            command: None,
+            sound: true,
+            visual: None,
+        }]
+    });
+
+    // 未配置温度提供者链时：传感器 API（如有）→ wttr.in
+    let temperature_providers =
+        temperature_providers.unwrap_or_else(|| crate::provider::default_chain(api_base_url.as_deref(), &device_code));
+
+    // 所有参数都从配置文件读取，命令行仅用于选择运行模式（见 parse_cli）
 
     Config { 
         time_scale_x, 
@@ -113,18 +1275,307 @@ pub fn parse_args() -> Config {
         time_color, 
         date_color, 
         todos_color, 
+        time_colors,
+        time_effect,
+        time_effect_color,
+        time_font,
+        show_subseconds,
+        clock_style,
+        clock_frame,
+        clocks,
+        auto_rotate,
+        idle_dim,
+        big_temperature,
+        secondary_calendar,
+        widgets,
+        layout,
+        background_effect,
+        background_density,
+        background_effect_color,
+        background_color,
+        countdowns,
+        countdown_celebrate_secs,
+        markers,
+        markers_command,
+        markers_chime,
+        location,
+        sun_bar,
+        upcoming,
+        transit,
+        ntp,
+        time_sync,
+        compare_indoor_outdoor,
+        thermometer_style,
+        temp_min_max,
+        show_comfort,
+        comfort,
+        humidity_gauge,
+        rain_alert,
+        rain_alert_minutes,
+        rain_threshold_mm,
+        rain_alert_chime,
+        forecast_url,
+        weather_accents,
+        radar,
+        ble_sensors,
+        serial,
+        dht22,
+        mqtt,
+        on_hour_command,
+        on_alarm_command,
+        scripts,
+        command_panels,
+        temperature_providers,
         chime_enabled, 
+        chimes,
+        chime_pattern,
+        alarm_pattern,
+        chime_visual,
+        chime_audible,
+        mute_minutes,
+        ipc_socket,
+        alarms,
+        snooze_minutes,
+        gcal,
+        work_hours,
+        history_enabled,
+        history_file,
+        history_interval,
+        web_listen,
+        web_push_secret,
+        screenshot_dir,
+        custom_sources,
+        todo_db,
+        todos_file,
+        todo_priority,
+        todo_priority_colors,
+        todo_group,
+        todo_summary,
+        todo_rotate_secs,
+        todo_sources,
+        offline_after_mins,
+        offline_retry_secs,
+        stale_primary_secs,
+        max_concurrent_requests,
+        api_schema,
+        low_bandwidth,
+        low_power,
+        ascii,
+        todo_task_max_chars,
+        themes: if themes.is_empty() { crate::theme::builtin_themes() } else { themes },
+        theme,
+        palette: Palette::default(),
+        state_file,
+        status_bar,
+        // 未配置时沿用 todo_priority 的排序行为
+        todo_sort: todo_sort.unwrap_or(if todo_priority { TodoSort::Priority } else { TodoSort::None }),
         api_base_url, 
         device_code,
         temp_refresh_interval,
         todo_ip_filter, 
-        todo_limit: None, 
+        todo_limit, 
         main_window_percent 
     }
 }
 
-#[allow(dead_code)]
-fn parse_color(name: &str) -> Option<Color> {
+// 运行模式
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    // 默认：带界面运行
+    Run,
+    // 无界面守护进程：获取数据、记录历史、报时
+    Daemon,
+    // 连接到运行中的守护进程并显示界面
+    Attach,
+    // 管理当前待办后端：termclock todo add|list|done|rm|export|import
+    Todo(TodoCommand),
+    // 检查配置、网络和终端
+    Doctor,
+    // 导出历史记录：termclock export --from 2024-05-01 --to 2024-05-31 --format csv
+    Export(ExportCommand),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExportCommand {
+    // 包含 from，不包含 to；只写日期时 to 包含当天
+    pub from: Option<chrono::DateTime<chrono::Local>>,
+    pub to: Option<chrono::DateTime<chrono::Local>>,
+    pub format: crate::history::ExportFormat,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TodoCommand {
+    Add(String),
+    List,
+    // 序号从 1 开始（与 list 输出一致）
+    Done(usize),
+    Rm(usize),
+    // 输出到标准输出
+    Export(crate::todo_transfer::TodoFormat),
+    // 文件或 -（标准输入）；未指定 --format 时按扩展名推断
+    Import { path: std::path::PathBuf, format: crate::todo_transfer::TodoFormat },
+}
+
+// 命令行：运行模式 + 选项
+#[derive(Debug, Clone)]
+pub struct Cli {
+    pub command: Command,
+    // 替换已在运行的实例
+    pub takeover: bool,
+    // 录制 asciicast 文件
+    pub record: Option<std::path::PathBuf>,
+    // 低带宽模式（覆盖配置文件）
+    pub low_bandwidth: bool,
+    // 在父进程中运行，崩溃后自动重启
+    pub supervise: bool,
+    // 强制开启省电模式（覆盖配置文件）
+    pub low_power: bool,
+    // 强制使用 ASCII 字符（覆盖配置文件）
+    pub ascii: bool,
+    // 按行输出纯文本（屏幕阅读器）
+    pub accessible: bool,
+}
+
+const USAGE: &str = "usage: termclock [daemon|attach] [--profile name] [--takeover] [--record file.cast] [--low-bandwidth] [--low-power] [--ascii] [--accessible] [--supervise]\n       termclock todo add <task> | list | done <n> | rm <n>\n       termclock todo export [--format todotxt|markdown|json] | import <file|-> [--format todotxt|markdown|json]\n       termclock doctor\n       termclock export [--from date] [--to date] [--format csv|json]";
+
+pub fn parse_cli() -> Cli {
+    let mut cli = Cli { command: Command::Run, takeover: false, record: None, low_bandwidth: false, supervise: false, low_power: false, ascii: false, accessible: false };
+    let mut command: Option<Command> = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--takeover" => cli.takeover = true,
+            "--low-bandwidth" => cli.low_bandwidth = true,
+            "--supervise" => cli.supervise = true,
+            "--low-power" => cli.low_power = true,
+            "--ascii" => cli.ascii = true,
+            "--accessible" => cli.accessible = true,
+            "--profile" => match args.next() {
+                Some(name) => { let _ = PROFILE.set(name); }
+                None => usage_error("--profile requires a profile name"),
+            },
+            "--record" => match args.next() {
+                Some(path) => cli.record = Some(path.into()),
+                None => usage_error("--record requires a file path"),
+            },
+            "daemon" if command.is_none() => command = Some(Command::Daemon),
+            "attach" if command.is_none() => command = Some(Command::Attach),
+            "todo" if command.is_none() => command = Some(Command::Todo(parse_todo_command(&mut args))),
+            "doctor" if command.is_none() => command = Some(Command::Doctor),
+            "export" if command.is_none() => command = Some(Command::Export(ExportCommand::default())),
+            "--from" | "--to" | "--format" => match &mut command {
+                Some(Command::Export(export)) => parse_export_option(export, &arg, args.next()),
+                _ => usage_error(&format!("{} is only valid with export", arg)),
+            },
+            other => usage_error(&format!("unknown argument: {}", other)),
+        }
+    }
+    if let Some(command) = command { cli.command = command; }
+    if let Some(mut value) = read_config_file()
+        && let Some(map) = value.as_mapping_mut()
+        && let Err(e) = apply_profile(map, active_profile().as_deref())
+    {
+        usage_error(&e);
+    }
+    cli
+}
+
+fn parse_todo_command(args: &mut impl Iterator<Item = String>) -> TodoCommand {
+    let sub = args.next().unwrap_or_default();
+    let index = |arg: Option<String>| -> usize {
+        match arg.and_then(|n| n.parse::<usize>().ok()).filter(|&n| n > 0) {
+            Some(n) => n,
+            None => usage_error(&format!("todo {} requires an item number (see `termclock todo list`)", sub)),
+        }
+    };
+    match sub.as_str() {
+        // 剩余参数拼接为任务内容，无需加引号
+        "add" => {
+            let task = args.collect::<Vec<_>>().join(" ");
+            if task.trim().is_empty() {
+                usage_error("todo add requires a task");
+            }
+            TodoCommand::Add(task.trim().to_string())
+        }
+        "list" | "ls" => TodoCommand::List,
+        "done" => TodoCommand::Done(index(args.next())),
+        "rm" => TodoCommand::Rm(index(args.next())),
+        "export" | "import" => {
+            let mut path: Option<std::path::PathBuf> = None;
+            let mut format = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--format" => match args.next().as_deref().map(|v| (v, crate::todo_transfer::TodoFormat::parse(v))) {
+                        Some((_, Some(f))) => format = Some(f),
+                        Some((v, None)) => usage_error(&format!("unknown todo format: {} (expected todotxt, markdown or json)", v)),
+                        None => usage_error("--format requires a value"),
+                    },
+                    _ if sub == "import" && path.is_none() => path = Some(arg.into()),
+                    other => usage_error(&format!("unknown argument: {}", other)),
+                }
+            }
+            match path {
+                Some(path) => {
+                    let format = format.unwrap_or_else(|| crate::todo_transfer::TodoFormat::from_path(&path));
+                    TodoCommand::Import { path, format }
+                }
+                None if sub == "import" => usage_error("todo import requires a file (or - for stdin)"),
+                None => TodoCommand::Export(format.unwrap_or_default()),
+            }
+        }
+        "" => usage_error("missing todo command"),
+        other => usage_error(&format!("unknown todo command: {}", other)),
+    }
+}
+
+fn parse_export_option(export: &mut ExportCommand, option: &str, value: Option<String>) {
+    let Some(value) = value else { usage_error(&format!("{} requires a value", option)) };
+    match option {
+        "--format" => match crate::history::ExportFormat::parse(&value) {
+            Some(format) => export.format = format,
+            None => usage_error(&format!("unknown export format: {} (expected csv or json)", value)),
+        },
+        _ => match parse_export_time(&value, option == "--to") {
+            Some(at) if option == "--to" => export.to = Some(at),
+            Some(at) => export.from = Some(at),
+            None => usage_error(&format!("{}: expected YYYY-MM-DD, YYYY-MM-DD HH:MM or an RFC 3339 time, got {}", option, value)),
+        },
+    }
+}
+
+// 2024-05-01、2024-05-01 08:00、2024-05-01T08:00:00 或带时区的 RFC 3339 时间；
+// 只写日期的 --to 取次日零点，即包含当天
+fn parse_export_time(value: &str, end_of_day: bool) -> Option<chrono::DateTime<chrono::Local>> {
+    use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+    if let Ok(at) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Local));
+    }
+    let naive = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => (if end_of_day { date.succ_opt()? } else { date }).and_hms_opt(0, 0, 0)?,
+        Err(_) => ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())?,
+    };
+    Local.from_local_datetime(&naive).earliest()
+}
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
+
+// left / center / right
+pub fn parse_alignment(s: &str) -> Option<ratatui::layout::Alignment> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "left" => Some(ratatui::layout::Alignment::Left),
+        "center" | "centre" => Some(ratatui::layout::Alignment::Center),
+        "right" => Some(ratatui::layout::Alignment::Right),
+        _ => None,
+    }
+}
+
+pub fn parse_color(name: &str) -> Option<Color> {
     match name.to_ascii_lowercase().as_str() {
         "black" => Some(Color::Black),
         "red" => Some(Color::Red),
@@ -143,6 +1594,11 @@ fn parse_color(name: &str) -> Option<Color> {
         "lightmagenta" => Some(Color::LightMagenta),
         "lightcyan" => Some(Color::LightCyan),
         "orange" => Some(Color::Rgb(255, 165, 0)),
+        // "#rrggbb"
+        hex if hex.len() == 7 && hex.starts_with('#') => {
+            let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+            Some(Color::Rgb(channel(1)?, channel(3)?, channel(5)?))
+        }
         _ => None,
     }
 }
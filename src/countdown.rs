@@ -0,0 +1,84 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone};
+
+// 特殊时刻倒计时：最后 60 秒大字显示剩余秒数，归零后播放庆祝动画
+
+// 倒计时开始于目标时刻前多少秒
+pub const COUNTDOWN_SECS: i64 = 60;
+
+// "01-01 00:00 Happy New Year!"（每年重复）或 "2026-12-24 18:00 Dinner"
+#[derive(Debug, Clone, PartialEq)]
+pub struct Countdown {
+    pub year: Option<i32>,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CountdownPhase {
+    // 剩余秒数（1..=60）
+    Counting { remaining: i64, label: String },
+    // 目标时刻之后经过的毫秒数
+    Celebrating { elapsed_ms: i64, label: String },
+}
+
+impl Countdown {
+    // 默认：跨年
+    pub fn new_year() -> Self {
+        Self { year: None, month: 1, day: 1, hour: 0, minute: 0, label: "Happy New Year!".to_string() }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.trim().splitn(3, ' ');
+        let date = parts.next()?;
+        let time = parts.next().unwrap_or("00:00");
+        let label = parts.next().map(str::trim).filter(|l| !l.is_empty()).unwrap_or("Countdown").to_string();
+        let fields: Vec<&str> = date.split('-').collect();
+        let (year, month, day) = match fields.as_slice() {
+            [m, d] => (None, m.parse().ok()?, d.parse().ok()?),
+            [y, m, d] => (Some(y.parse().ok()?), m.parse().ok()?, d.parse().ok()?),
+            _ => return None,
+        };
+        let (hour, minute) = time.split_once(':')?;
+        let countdown = Self { year, month, day, hour: hour.parse().ok()?, minute: minute.parse().ok()?, label };
+        // 校验日期和时间（2 月 29 日按闰年检查）
+        countdown.at(year.unwrap_or(2024))?;
+        Some(countdown)
+    }
+
+    fn at(&self, year: i32) -> Option<DateTime<Local>> {
+        let naive = NaiveDate::from_ymd_opt(year, self.month, self.day)?.and_hms_opt(self.hour, self.minute, 0)?;
+        Local.from_local_datetime(&naive).earliest()
+    }
+
+    // 距离 now 最近的目标时刻（每年重复的取去年、今年、明年中最近的一个）
+    fn nearest(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let years = match self.year {
+            Some(year) => vec![year],
+            None => vec![now.year() - 1, now.year(), now.year() + 1],
+        };
+        years
+            .into_iter()
+            .filter_map(|y| self.at(y))
+            .min_by_key(|t| (*t - now).num_milliseconds().abs())
+    }
+}
+
+// 当前是否处于某个倒计时或庆祝阶段
+pub fn phase(countdowns: &[Countdown], now: DateTime<Local>, celebrate_secs: u64) -> Option<CountdownPhase> {
+    countdowns.iter().find_map(|countdown| {
+        let target = countdown.nearest(now)?;
+        let ms = (target - now).num_milliseconds();
+        let label = countdown.label.clone();
+        if ms > 0 && ms <= COUNTDOWN_SECS * 1000 {
+            // 向上取整：剩 0.3 秒时仍显示 1
+            Some(CountdownPhase::Counting { remaining: (ms + 999) / 1000, label })
+        } else if ms <= 0 && -ms < celebrate_secs as i64 * 1000 {
+            Some(CountdownPhase::Celebrating { elapsed_ms: -ms, label })
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,203 @@
+use std::io::{self, IsTerminal};
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+
+use crate::ipc::{self, Snapshot};
+use crate::model::{App, Config};
+use crate::systemd::Systemd;
+
+const TICK: Duration = Duration::from_millis(200);
+
+// 无界面守护进程：持续获取数据、记录历史、报时，并通过 IPC 提供状态
+pub fn run_daemon(config: Config) -> io::Result<()> {
+    let socket = config.ipc_socket.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    ipc::spawn_server(&socket, tx.clone()).map_err(|e| {
+        io::Error::new(e.kind(), format!("cannot listen on {}: {}", socket.display(), e))
+    })?;
+    if let Some(addr) = &config.web_listen {
+        crate::web::spawn_server(addr, tx, config.web_push_secret.clone()).map_err(|e| {
+            io::Error::new(e.kind(), format!("cannot listen on {}: {}", addr, e))
+        })?;
+    }
+    let mut app = App::new(config);
+    app.config.history_enabled = true;
+    // 仅在连接终端时本地发声，否则交给 attach 前端
+    app.play_sounds = io::stdout().is_terminal();
+    app.start_workers();
+    eprintln!("termclock daemon listening on {}", socket.display());
+    let mut systemd = Systemd::init();
+    systemd.notify_ready();
+
+    while !app.quit_requested {
+        while let Ok(request) = rx.try_recv() {
+            app.handle_ipc(request);
+        }
+        if systemd.tick() {
+            app.reload_config(crate::config::parse_args());
+            systemd.notify_reloaded();
+        }
+        app.tick();
+        std::thread::sleep(TICK);
+    }
+    systemd.notify_stopping();
+    let _ = std::fs::remove_file(&socket);
+    Ok(())
+}
+
+// 连接运行中的守护进程，渲染其状态；q / d 断开（守护进程继续运行）
+pub fn run_attach(config: Config, record_path: Option<&std::path::Path>) -> io::Result<()> {
+    let socket = config.ipc_socket.clone();
+    let mut client = ipc::Client::connect(&socket).map_err(|e| {
+        io::Error::new(e.kind(), format!("no daemon running at {}: {}", socket.display(), e))
+    })?;
+
+    let mut capture = crate::Capture::new(record_path)?;
+    let mut terminal = crate::setup_terminal(!config.low_bandwidth)?;
+    let mut app = App::new(config);
+    app.forward_commands = Some(Vec::new());
+    let mut seen_sound: Option<u64> = None;
+    let mut limiter = crate::FrameLimiter::new();
+
+    let result = loop {
+        if app.quit_requested {
+            break Ok(());
+        }
+        let outbox = app.forward_commands.as_mut().map(std::mem::take).unwrap_or_default();
+        if let Some(e) = outbox.iter().find_map(|line| client.request(line).err()) {
+            break Err(e);
+        }
+        let reply = match client.request("snapshot") {
+            Ok(json) => json,
+            Err(e) => break Err(e),
+        };
+        match serde_json::from_str::<Snapshot>(&reply) {
+            Ok(snap) => {
+                // 守护进程发声后在本地终端重放（首次连接时不重放旧的提示音）
+                if seen_sound.is_some_and(|seq| seq < snap.sound_seq)
+                    && let Some(pattern) = &snap.last_sound
+                {
+                    pattern.play();
+                }
+                seen_sound = Some(snap.sound_seq);
+                // 暂停时保留当前画面的数据，新闹钟响起时恢复
+                if app.paused_at.is_some() && snap.alarm.is_some() && app.alarm.is_none() {
+                    app.paused_at = None;
+                }
+                if app.paused_at.is_none() {
+                    app.apply_snapshot(snap);
+                }
+            }
+            // 错误应答或版本不兼容的快照：保留上次的数据，持续提示原因
+            Err(e) => app.notify(match reply.strip_prefix("error:") {
+                Some(error) => format!("daemon: {}", error.trim()),
+                None => format!("cannot read daemon snapshot (different termclock version?): {}", e),
+            }),
+        }
+
+        app.update_power();
+        app.run_auto_rotate();
+        let completed = limiter.draw(|| terminal.draw(|f| crate::draw_frame(f, &mut app)))?;
+        capture.after_draw(completed.buffer, &app.config.screenshot_dir);
+
+        // 绘制很慢时由帧率限制保证等待时间不为 0
+        let deadline = limiter.deadline(app.frame_delay());
+        if !event::poll(deadline.saturating_duration_since(std::time::Instant::now()))? {
+            continue;
+        }
+        let event = event::read()?;
+        // 空闲变暗时第一次按键只用于唤醒
+        let waking = app.idle_mode().is_some();
+        if matches!(event, Event::Key(_) | Event::Mouse(_)) {
+            app.note_input();
+        }
+        if let Event::Key(key) = event
+            && !waking
+            && key.kind == KeyEventKind::Press
+            && !app.handle_input_key(key)
+        {
+            let command = match key.code {
+                KeyCode::Esc if app.todo_filter.is_some() => {
+                    app.set_todo_filter(None);
+                    None
+                }
+                KeyCode::Char('q') | KeyCode::Char('d') | KeyCode::Esc => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break Ok(()),
+                KeyCode::Char('s') if app.alarm.is_some() || app.rain_alert().is_some() => Some("snooze"),
+                KeyCode::Enter if app.alarm.is_some() => Some("dismiss"),
+                KeyCode::Enter if app.offline_banner().is_some() => {
+                    app.dismiss_offline_banner();
+                    None
+                }
+                KeyCode::Char('r') => Some("refresh"),
+                KeyCode::Char('v') => {
+                    app.toggle_radar_view();
+                    None
+                }
+                KeyCode::Char('e') => {
+                    app.toggle_events_view();
+                    None
+                }
+                KeyCode::Char('h') => {
+                    app.toggle_history_view();
+                    None
+                }
+                KeyCode::Char('m') => Some("toggle-mute"),
+                KeyCode::Char('t') => {
+                    app.cycle_theme();
+                    None
+                }
+                KeyCode::Char(' ') => {
+                    app.toggle_pause();
+                    None
+                }
+                KeyCode::Char(':') => {
+                    app.start_command_input();
+                    None
+                }
+                KeyCode::Char('p') => {
+                    capture.screenshot_requested = true;
+                    None
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    app.select_next_todo();
+                    None
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    app.select_prev_todo();
+                    None
+                }
+                KeyCode::Char('y') => {
+                    app.copy_todos();
+                    None
+                }
+                KeyCode::Char('a') => {
+                    app.start_todo_input();
+                    None
+                }
+                KeyCode::Char('/') => {
+                    app.start_filter_input();
+                    None
+                }
+                KeyCode::Char('x') => {
+                    app.complete_selected_todo();
+                    Some("refresh")
+                }
+                KeyCode::Delete => {
+                    app.remove_selected_todo();
+                    Some("refresh")
+                }
+                _ => None,
+            };
+            if let Some(command) = command
+                && let Err(e) = client.request(command)
+            {
+                break Err(e);
+            }
+        }
+    };
+
+    crate::restore_terminal(&mut terminal)?;
+    result
+}
@@ -0,0 +1,102 @@
+// DHT22 / AM2302 温湿度传感器（树莓派 GPIO）。单总线协议要求微秒级时序，用户态
+// 无法可靠实现，因此通过内核 dht11 驱动读取（/boot/config.txt 中加入
+// dtoverlay=dht11,gpiopin=4，该驱动同样支持 DHT22），读数出现在 IIO 设备中。
+// 需启用 `rpi` feature；未启用时显示错误提示。
+
+#[derive(Debug, Clone)]
+pub struct Dht22Config {
+    pub pin: u32,
+    pub label: String,
+    pub interval_secs: u64,
+}
+
+#[cfg(feature = "rpi")]
+mod imp {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use super::Dht22Config;
+    use crate::sensors::{self, SharedReadings};
+
+    const IIO_DEVICES: &str = "/sys/bus/iio/devices";
+    // 协议经常校验失败或超时，每次读取最多尝试的次数
+    const ATTEMPTS: usize = 5;
+    // DHT22 最短采样间隔
+    const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+    pub fn spawn_worker(config: Dht22Config, shared: SharedReadings) {
+        sensors::register(&shared, &config.label);
+        std::thread::spawn(move || loop {
+            match read_with_retry(config.pin) {
+                Ok((temp, humidity)) => sensors::record(&shared, &config.label, Some(temp), Some(humidity)),
+                Err(e) => sensors::record_error(&shared, &config.label, format!("dht22: {}", e)),
+            }
+            std::thread::sleep(Duration::from_secs(config.interval_secs));
+        });
+    }
+
+    fn read_with_retry(pin: u32) -> Result<(f64, f64), String> {
+        let device = find_device(pin)?;
+        let mut last_error = String::new();
+        for attempt in 0..ATTEMPTS {
+            if attempt > 0 {
+                std::thread::sleep(RETRY_DELAY);
+            }
+            match read_once(&device) {
+                Ok(reading) => return Ok(reading),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(format!("{} (after {} attempts)", last_error, ATTEMPTS))
+    }
+
+    // 温度单位为千分之一摄氏度，湿度为千分之一百分比；超出传感器量程的读数视为失败
+    fn read_once(device: &Path) -> Result<(f64, f64), String> {
+        let read = |file: &str| -> Result<f64, String> {
+            let text = fs::read_to_string(device.join(file)).map_err(|e| e.to_string())?;
+            text.trim().parse::<f64>().map(|v| v / 1000.0).map_err(|e| e.to_string())
+        };
+        let temp = read("in_temp_input")?;
+        let humidity = read("in_humidityrelative_input")?;
+        if !(-40.0..=80.0).contains(&temp) || !(0.0..=100.0).contains(&humidity) {
+            return Err(format!("implausible reading {:.1}℃ {:.1}%", temp, humidity));
+        }
+        Ok((temp, humidity))
+    }
+
+    // 按设备树节点的 gpios 属性（phandle、引脚、标志，均为大端 u32）找到该引脚的设备；
+    // 无法读取 gpios 时，只有一个 dht11 设备就使用它
+    pub fn find_device(pin: u32) -> Result<PathBuf, String> {
+        let devices: Vec<PathBuf> = fs::read_dir(IIO_DEVICES)
+            .map_err(|e| format!("{}: {}", IIO_DEVICES, e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| fs::read_to_string(path.join("name")).is_ok_and(|name| name.trim() == "dht11"))
+            .collect();
+        let gpio_of = |path: &PathBuf| {
+            let bytes = fs::read(path.join("of_node/gpios")).ok()?;
+            Some(u32::from_be_bytes(bytes.get(4..8)?.try_into().ok()?))
+        };
+        if let Some(device) = devices.iter().find(|path| gpio_of(path) == Some(pin)) {
+            return Ok(device.clone());
+        }
+        match devices.as_slice() {
+            [only] if gpio_of(only).is_none() => Ok(only.clone()),
+            _ => Err(format!("no dht11 device on GPIO {} (add dtoverlay=dht11,gpiopin={} to /boot/config.txt)", pin, pin)),
+        }
+    }
+}
+
+#[cfg(not(feature = "rpi"))]
+mod imp {
+    use super::Dht22Config;
+    use crate::sensors::{self, SharedReadings};
+
+    pub fn spawn_worker(config: Dht22Config, shared: SharedReadings) {
+        sensors::record_error(&shared, &config.label, "built without the rpi feature".to_string());
+    }
+}
+
+pub use imp::spawn_worker;
+#[cfg(feature = "rpi")]
+pub use imp::find_device;
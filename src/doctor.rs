@@ -0,0 +1,613 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::{cursor, execute, terminal};
+use unicode_width::UnicodeWidthStr;
+
+use crate::background::BackgroundEffect;
+use crate::config::{self, CONFIG_KEYS};
+use crate::markers::MarkerTime;
+use crate::model::{ClockStyle, Config, Subseconds, ThermometerStyle, TimeEffect, TimeFont};
+use crate::todo::TodoSort;
+
+// termclock doctor：检查配置、网络和终端能力，输出逐项结果，有失败项时退出码为 1
+
+#[derive(Clone, Copy, PartialEq)]
+enum Level {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Default)]
+struct Report {
+    passed: usize,
+    warnings: usize,
+    failed: usize,
+}
+
+impl Report {
+    fn section(&self, name: &str) {
+        println!("{}", name);
+    }
+
+    fn item(&mut self, level: Level, msg: impl AsRef<str>) {
+        let tag = match level {
+            Level::Ok => { self.passed += 1; "ok  " }
+            Level::Warn => { self.warnings += 1; "warn" }
+            Level::Fail => { self.failed += 1; "FAIL" }
+        };
+        println!("  {}  {}", tag, msg.as_ref());
+    }
+}
+
+pub fn run_doctor(config: &Config) -> io::Result<()> {
+    let mut report = Report::default();
+    check_config(&mut report, config);
+    check_network(&mut report, config);
+    check_providers(&mut report, config);
+    check_terminal(&mut report, config)?;
+    println!();
+    println!("{} passed, {} warnings, {} failed", report.passed, report.warnings, report.failed);
+    if report.failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn check_config(report: &mut Report, config: &Config) {
+    report.section("config");
+    let path = config::config_path();
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            report.item(Level::Warn, format!("{}: not found, using defaults and TERMCLOCK_* overrides", path));
+            return;
+        }
+        Err(e) => return report.item(Level::Fail, format!("{}: {}", path, e)),
+    };
+    let mut value: serde_yaml::Value = match serde_yaml::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => return report.item(Level::Fail, format!("{}: {}", path, e)),
+    };
+    let Some(map) = value.as_mapping_mut() else {
+        return report.item(Level::Fail, format!("{}: top level must be a mapping of key: value", path));
+    };
+    report.item(Level::Ok, format!("{} parsed ({} keys)", path, map.len()));
+
+    let mut unknown = unknown_keys(map, "");
+    if let Some(profiles) = map.get("profiles").and_then(|p| p.as_mapping()) {
+        for (name, profile) in profiles {
+            if let (Some(name), Some(profile)) = (name.as_str(), profile.as_mapping()) {
+                unknown.extend(unknown_keys(profile, &format!("profiles.{}.", name)));
+            }
+        }
+    }
+    for msg in unknown {
+        report.item(Level::Warn, msg);
+    }
+    match config::apply_profile(map, config::active_profile().as_deref()) {
+        Ok(()) => {
+            if let Some(profile) = config::active_profile() {
+                report.item(Level::Ok, format!("profile {}", profile));
+            }
+        }
+        Err(e) => report.item(Level::Fail, e),
+    }
+
+    // 无法识别的值会被静默忽略，这里逐项指出
+    let str_of = |key: &str| map.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    for key in ["time_color", "date_color", "todos_color", "time_effect_color", "background_color", "background_effect_color"] {
+        if let Some(name) = str_of(key)
+            && config::parse_color(&name).is_none()
+        {
+            report.item(Level::Fail, format!("{}: unknown colour `{}`", key, name));
+        }
+    }
+    for (part, name) in map.get("time_colors").and_then(|v| v.as_mapping()).into_iter().flatten() {
+        if let (Some(part), Some(name)) = (part.as_str(), name.as_str())
+            && config::parse_color(name).is_none()
+        {
+            report.item(Level::Fail, format!("time_colors.{}: unknown colour `{}`", part, name));
+        }
+    }
+    if let Some(colors) = map.get("todo_priority_colors").and_then(|v| v.as_sequence()) {
+        for name in colors.iter().filter_map(|c| c.as_str()) {
+            if config::parse_color(name).is_none() {
+                report.item(Level::Fail, format!("todo_priority_colors: unknown colour `{}`", name));
+            }
+        }
+    }
+    for theme in map.get("themes").and_then(|v| v.as_sequence()).into_iter().flatten() {
+        let Some(name) = theme.get("name").and_then(|v| v.as_str()) else {
+            report.item(Level::Fail, "themes: entry without a name is ignored");
+            continue;
+        };
+        for key in ["time_color", "date_color", "todos_color", "alert_color", "warn_color", "ok_color", "stale_color", "info_color"] {
+            if let Some(color) = theme.get(key).and_then(|v| v.as_str())
+                && config::parse_color(color).is_none()
+            {
+                report.item(Level::Fail, format!("themes.{}.{}: unknown colour `{}`", name, key, color));
+            }
+        }
+    }
+    if let Some(name) = &config.theme
+        && crate::theme::find(&config.themes, name).is_none()
+    {
+        let builtin: Vec<String> = crate::theme::builtin_themes().into_iter().map(|t| t.name).collect();
+        report.item(Level::Fail, format!("theme: unknown theme `{}` (built-in: {})", name, builtin.join(", ")));
+    }
+    if let Some(sort) = str_of("todo_sort")
+        && TodoSort::parse(&sort).is_none()
+    {
+        report.item(Level::Fail, format!("todo_sort: expected deadline, created, priority or none, got `{}`", sort));
+    }
+    if let Some(effect) = str_of("time_effect")
+        && TimeEffect::parse(&effect).is_none()
+    {
+        report.item(Level::Fail, format!("time_effect: expected shadow, outline or none, got `{}`", effect));
+    }
+    if let Some(font) = str_of("time_font")
+        && TimeFont::parse(&font).is_none()
+    {
+        report.item(Level::Fail, format!("time_font: expected default, dots, blocks, smooth or braille, got `{}`", font));
+    }
+    if let Some(style) = str_of("thermometer_style")
+        && ThermometerStyle::parse(&style).is_none()
+    {
+        report.item(Level::Fail, format!("thermometer_style: expected bar, gauge, dial or number-only, got `{}`", style));
+    }
+    if let Some(mode) = str_of("show_subseconds")
+        && Subseconds::parse(&mode).is_none()
+    {
+        report.item(Level::Fail, format!("show_subseconds: expected tenths, hundredths or off, got `{}`", mode));
+    }
+    if let Some(style) = str_of("clock_style")
+        && ClockStyle::parse(&style).is_none()
+    {
+        report.item(Level::Fail, format!("clock_style: expected plain or flip, got `{}`", style));
+    }
+    if let Some(calendar) = str_of("secondary_calendar")
+        && crate::calendar::SecondaryCalendar::parse(&calendar).is_none()
+    {
+        report.item(Level::Fail, format!("secondary_calendar: expected hijri, hebrew or japanese, got `{}`", calendar));
+    }
+    if let Some(effect) = str_of("background_effect")
+        && BackgroundEffect::parse(&effect).is_none()
+    {
+        report.item(Level::Fail, format!("background_effect: expected rain, stars or none, got `{}`", effect));
+    }
+    if config.markers.iter().any(|m| !matches!(m.time, MarkerTime::Fixed(_))) && config.location.is_none() {
+        report.item(Level::Fail, "markers: sunrise/sunset/noon need latitude and longitude");
+    }
+    // widgets 的键：内置组件名或数据源名称；date 为时钟下方的日期，只支持 align
+    let builtin = ["meeting", "markers", "sun", "transit", "rain", "comfort", "sensors", "temperature", "todos", "date", "accent", "humidity"];
+    for name in config.widgets.keys() {
+        let known = builtin.contains(&name.as_str())
+            || config.custom_sources.iter().any(|s| &s.name == name)
+            || config.scripts.iter().any(|s| &s.name == name)
+            || config.upcoming.iter().any(|u| &u.name == name);
+        if !known {
+            report.item(Level::Warn, format!("widgets: unknown widget `{}` (expected {} or a custom_sources/scripts/upcoming name)", name, builtin.join(", ")));
+        }
+    }
+    for (name, item) in map.get("widgets").and_then(|v| v.as_mapping()).into_iter().flatten() {
+        if let (Some(name), Some(align)) = (name.as_str(), item.get("align").and_then(|v| v.as_str()))
+            && config::parse_alignment(align).is_none()
+        {
+            report.item(Level::Fail, format!("widgets.{}.align: expected left, center or right, got `{}`", name, align));
+        }
+        if let (Some(name), Some(color)) = (name.as_str(), item.get("background").and_then(|v| v.as_str()))
+            && config::parse_color(color).is_none()
+        {
+            report.item(Level::Fail, format!("widgets.{}.background: unknown colour `{}`", name, color));
+        }
+    }
+    // background_color 原来是背景动画的颜色，现在是全屏背景色；旧写法仍按动画颜色处理
+    if config.background_effect != crate::background::BackgroundEffect::None
+        && map.contains_key("background_color")
+        && !map.contains_key("background_effect_color")
+    {
+        report.item(Level::Warn, "background_color: used as the background_effect colour for compatibility; rename it to background_effect_color to fill the screen instead");
+    }
+    if map.get("clock_frame").is_some_and(|v| v.as_str() != Some("none") && config::parse_clock_frame(v).is_none()) {
+        report.item(Level::Fail, "clock_frame: expected box, double, rounded, heavy or none, or {style, corners: 1 or 4 characters, color}");
+    }
+    // auto_rotate 的页面名称；雷达页面需要 radar 配置
+    if let Some(rotate) = map.get("auto_rotate") {
+        let names = rotate.get("pages").and_then(|v| v.as_sequence()).into_iter().flatten().filter_map(|p| p.as_str());
+        for name in names.filter(|n| crate::model::View::parse(n).is_none()) {
+            report.item(Level::Warn, format!("auto_rotate: unknown page `{}` (expected clock, weather/radar, events or history)", name));
+        }
+        match &config.auto_rotate {
+            None => report.item(Level::Fail, "auto_rotate: expected {pages: [clock, weather, ...], interval: seconds, grace: seconds}"),
+            Some(rotate) if rotate.pages.contains(&crate::model::View::Radar) && config.radar.is_none() => {
+                report.item(Level::Warn, "auto_rotate: weather/radar page is skipped because radar is not configured");
+            }
+            Some(rotate) if rotate.pages.contains(&crate::model::View::History) && !config.history_enabled => {
+                report.item(Level::Warn, "auto_rotate: history page only has data when history_enabled is true or the daemon is running");
+            }
+            _ => {}
+        }
+    }
+    if map.get("idle_dim").is_some_and(|v| config::parse_idle_dim(v).is_none()) {
+        report.item(Level::Fail, "idle_dim: expected minutes or {after: minutes, mode: dim or clock}");
+    }
+    if map.get("big_temperature").is_some_and(|v| !matches!(v, serde_yaml::Value::Bool(false)) && config::parse_big_temperature(v).is_none()) {
+        report.item(Level::Fail, "big_temperature: expected true or {scale_x, scale_y (>= 1), color}");
+    }
+    if config.big_temperature.is_some() && !config.clocks.is_empty() {
+        report.item(Level::Warn, "big_temperature is only shown with a single clock (clocks is set)");
+    }
+    if map.get("layout").is_some_and(|v| config::parse_layout(v).is_none()) {
+        report.item(Level::Fail, "layout: expected {padding: N or [rows, columns], margin: N} with non-negative integers");
+    }
+    if map.contains_key("ntp") && config.ntp.is_none() {
+        report.item(Level::Fail, "ntp: expected a server name or {server, refresh_secs, warn_ms}");
+    }
+    if map.contains_key("transit") && config.transit.is_none() {
+        report.item(Level::Fail, "transit: needs url, and departures/route plus time or minutes for the json format");
+    }
+    if let Some(transit) = &config.transit
+        && matches!(transit.feed, crate::transit::TransitFeed::GtfsRealtime)
+        && transit.stop_id.is_none()
+    {
+        report.item(Level::Fail, "transit: gtfs-rt format needs stop_id");
+    }
+    if config.compare_indoor_outdoor && config.thermometer_style != ThermometerStyle::Bar {
+        report.item(Level::Warn, "thermometer_style: compare_indoor_outdoor always uses the bar style");
+    }
+    if config.compare_indoor_outdoor && config.api_base_url.is_none() {
+        report.item(Level::Warn, "compare_indoor_outdoor: indoor reading needs api_base_url (sensor API)");
+    }
+    if config.show_comfort {
+        let c = &config.comfort;
+        if !(0.0..=100.0).contains(&c.dry_below) || !(0.0..=100.0).contains(&c.humid_above) || c.dry_below >= c.humid_above {
+            report.item(Level::Fail, format!("comfort thresholds: need 0 <= comfort_dry_below ({}) < comfort_humid_above ({}) <= 100", c.dry_below, c.humid_above));
+        }
+        if config.api_base_url.is_none() {
+            report.item(Level::Warn, "show_comfort: humidity comes from the sensor API (api_base_url)");
+        }
+    }
+    if map.get("humidity_gauge").is_some_and(|v| !matches!(v, serde_yaml::Value::Bool(false)) && config::parse_humidity_gauge(v).is_none()) {
+        report.item(Level::Fail, "humidity_gauge: expected true or {bar_color, zone_color, scale_color}");
+    }
+    if map.contains_key("radar") && config.radar.is_none() {
+        report.item(Level::Fail, "radar: expected true or {url, zoom (0-12), refresh_secs, graphics: auto | kitty | sixel | text}");
+    }
+    if let Some(radar) = &config.radar {
+        match config.location {
+            None => report.item(Level::Fail, "radar needs latitude and longitude"),
+            Some(_) => report.item(Level::Ok, format!("radar graphics: {:?}", radar.graphics).to_lowercase()),
+        }
+    }
+    if !config.ble_sensors.is_empty() && !cfg!(feature = "ble") {
+        report.item(Level::Fail, "ble_sensors: this build has no Bluetooth support (rebuild with --features ble)");
+    }
+    for port in &config.serial {
+        if !std::path::Path::new(&port.port).exists() {
+            report.item(Level::Warn, format!("serial: {} not found (will retry until it is plugged in)", port.port));
+        }
+    }
+    if map.contains_key("mqtt") && config.mqtt.is_none() {
+        report.item(Level::Fail, "mqtt: expected {host, port (1-65535), username, password, topic, client_id, home_assistant (true/false)}");
+    }
+    if map.contains_key("dht22") && config.dht22.is_none() {
+        report.item(Level::Fail, "dht22: expected {pin (BCM 0-27), label, interval_secs (>= 2)}");
+    }
+    if let Some(sensor) = &config.dht22 {
+        #[cfg(feature = "rpi")]
+        match crate::dht::find_device(sensor.pin) {
+            Ok(device) => report.item(Level::Ok, format!("dht22 on GPIO {}: {}", sensor.pin, device.display())),
+            Err(e) => report.item(Level::Fail, format!("dht22: {}", e)),
+        }
+        #[cfg(not(feature = "rpi"))]
+        report.item(Level::Fail, format!("dht22 on GPIO {}: this build has no Raspberry Pi support (rebuild with --features rpi)", sensor.pin));
+    }
+    if config.rain_alert && config.location.is_none() {
+        report.item(Level::Fail, "rain_alert needs latitude and longitude");
+    }
+    if config.rain_alert && config.rain_threshold_mm <= 0.0 {
+        report.item(Level::Fail, format!("rain_threshold_mm must be positive, got {}", config.rain_threshold_mm));
+    }
+    if map.get("weather_accents").is_some_and(|v| !matches!(v, serde_yaml::Value::Bool(false)) && config::parse_weather_accents(v).is_none()) {
+        report.item(Level::Fail, "weather_accents: expected true or {rain_color, snow_color, hot_color, hot_above}");
+    }
+    if config.weather_accents.is_some() && config.location.is_none() {
+        report.item(Level::Fail, "weather_accents needs latitude and longitude");
+    }
+    if config.sun_bar && config.location.is_none() {
+        report.item(Level::Fail, "sun_bar needs latitude and longitude");
+    }
+    if map.contains_key("work_hours") && config.work_hours.is_none() {
+        report.item(Level::Fail, "work_hours/work_days/work_timezone could not be parsed");
+    }
+    // 低于最短间隔的刷新设置按最短间隔执行
+    let top = serde_yaml::Value::Mapping(map.clone());
+    let mut intervals: Vec<(String, Option<u64>, u64)> = vec![
+        ("temp_refresh_interval".to_string(), top.get("temp_refresh_interval").and_then(|v| v.as_u64()), crate::ratelimit::SENSOR_API_MIN_SECS),
+        ("radar.refresh_secs".to_string(), top.get("radar").and_then(|v| v.get("refresh_secs")).and_then(|v| v.as_u64()), crate::ratelimit::RADAR_MIN_SECS),
+        ("transit.refresh_secs".to_string(), top.get("transit").and_then(|v| v.get("refresh_secs")).and_then(|v| v.as_u64()), crate::ratelimit::TRANSIT_MIN_SECS),
+        ("ntp.refresh_secs".to_string(), top.get("ntp").and_then(|v| v.get("refresh_secs")).and_then(|v| v.as_u64()), crate::ratelimit::NTP_MIN_SECS),
+    ];
+    for (key, field, min) in [("upcoming", "refresh_secs", crate::ratelimit::UPCOMING_MIN_SECS), ("custom_sources", "refresh", crate::ratelimit::CUSTOM_SOURCE_MIN_SECS)] {
+        for item in top.get(key).and_then(|v| v.as_sequence()).into_iter().flatten() {
+            let name = item.get("name").and_then(|v| v.as_str()).unwrap_or("?");
+            intervals.push((format!("{} `{}` {}", key, name, field), item.get(field).and_then(|v| v.as_u64()), min));
+        }
+    }
+    for (what, secs, min) in intervals {
+        if let Some(secs) = secs.filter(|&s| s > 0 && s < min) {
+            report.item(Level::Warn, format!("{}: {}s is below the {}s minimum, using {}s", what, secs, min, min));
+        }
+    }
+    if map.get("max_concurrent_requests").and_then(|v| v.as_u64()) == Some(0) {
+        report.item(Level::Warn, "max_concurrent_requests: 0 is treated as 1");
+    }
+    let dropped = |key: &str, parsed: usize| {
+        // serial 也可以是单个映射
+        let total = map.get(key).map_or(0, |v| v.as_sequence().map_or(1, |s| s.len()));
+        total.saturating_sub(parsed)
+    };
+    for (key, parsed) in [
+        ("alarms", config.alarms.len()),
+        ("countdowns", config.countdowns.len()),
+        ("clocks", config.clocks.len()),
+        ("markers", config.markers.len()),
+        ("upcoming", config.upcoming.len()),
+        ("custom_sources", config.custom_sources.len()),
+        ("todo_sources", config.todo_sources.len()),
+        ("ble_sensors", config.ble_sensors.len()),
+        ("scripts", config.scripts.len()),
+        ("command_panels", config.command_panels.len()),
+        ("temperature_providers", config.temperature_providers.len()),
+        ("serial", config.serial.len()),
+    ] {
+        let n = dropped(key, parsed);
+        if n > 0 {
+            report.item(Level::Fail, format!("{}: {} entries ignored (missing or invalid fields)", key, n));
+        }
+    }
+    if config.web_push_secret.is_some() && config.web_listen.is_none() {
+        report.item(Level::Warn, "web_push_secret has no effect without web_listen");
+    }
+    // 报时规则逐条检查：cron 无效的规则从不触发，样式无效时使用默认报时音
+    if let Some(rules) = map.get("chimes").and_then(|v| v.as_sequence()) {
+        let patterns = map.get("chime_patterns").map(config::parse_pattern_table).unwrap_or_default();
+        for (i, rule) in rules.iter().enumerate() {
+            match rule.get("cron").and_then(|v| v.as_str()) {
+                None => report.item(Level::Fail, format!("chimes[{}]: missing cron, rule ignored", i)),
+                Some(cron) if crate::schedule::CronSchedule::parse(cron).is_none() => {
+                    report.item(Level::Fail, format!("chimes[{}]: invalid cron \"{}\" (minute hour day month weekday), rule ignored", i, cron))
+                }
+                Some(_) => {}
+            }
+            if let Some(spec) = rule.get("pattern").and_then(|v| v.as_str())
+                && config::resolve_pattern(spec, &patterns).is_none()
+            {
+                report.item(Level::Warn, format!("chimes[{}]: unknown pattern \"{}\", using the default chime", i, spec));
+            }
+        }
+    }
+    if let Some(value) = map.get("api_schema") {
+        if config::parse_api_schema(value).is_none() {
+            report.item(Level::Fail, "api_schema: invalid (expected mappings with valid JSONPaths), using the defaults");
+        }
+        // 用示例值渲染请求体，检查是否为合法 JSON
+        let schema = &config.api_schema;
+        let samples = [
+            ("temperature", schema.temperature.render_body(&[("device_code", config.device_code.clone())])),
+            ("todos", schema.todos.render_body(&[("status", "0".to_string()), ("size", "1".to_string())])),
+            ("todo_add", schema.todo_add.render_body(&[("task", "task".to_string()), ("deadline", String::new())])),
+            ("todo_done", schema.todo_done.render_body(&[("id", "1".to_string())])),
+            ("todo_remove", schema.todo_remove.render_body(&[("id", "1".to_string())])),
+        ];
+        for (name, body) in samples {
+            if let Err(e) = serde_json::from_str::<serde_json::Value>(&body) {
+                report.item(Level::Warn, format!("api_schema.{}.body: not valid JSON ({})", name, e));
+            }
+        }
+    }
+}
+
+fn unknown_keys(map: &serde_yaml::Mapping, prefix: &str) -> Vec<String> {
+    map.keys()
+        .filter_map(|k| k.as_str())
+        .filter(|k| !CONFIG_KEYS.contains(k) || (!prefix.is_empty() && *k == "profiles"))
+        .map(|k| match CONFIG_KEYS.iter().find(|known| edit_distance(k, known) <= 2) {
+            Some(known) => format!("unknown key `{}{}` (did you mean `{}`?)", prefix, k, known),
+            None => format!("unknown key `{}{}`", prefix, k),
+        })
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = (prev + usize::from(ca != *cb)).min(row[j] + 1).min(cur + 1);
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+fn check_network(report: &mut Report, config: &Config) {
+    report.section("network");
+    let mut probe = |name: String, result: Result<String, String>, started: Instant, level: Level| {
+        let ms = started.elapsed().as_millis();
+        match result {
+            Ok(value) => report.item(Level::Ok, format!("{}: {} ({} ms)", name, value, ms)),
+            // 错误信息已带来源前缀
+            Err(e) => report.item(level, format!("{} ({} ms)", e, ms)),
+        }
+    };
+    if let Some(base_url) = &config.api_base_url {
+        let started = Instant::now();
+        let result = crate::api::fetch_todos_api(base_url, 1, false, &config.api_schema.todos).map(|todos| match todos.len() {
+            0 => "reachable, no pending todos".to_string(),
+            _ => "reachable".to_string(),
+        });
+        probe("todo API".to_string(), result, started, Level::Fail);
+    }
+    // Redis 键：custom_sources 和 todo_sources 中的
+    let mut redis_keys: Vec<(String, &str, &str)> = Vec::new();
+    for source in &config.custom_sources {
+        if let crate::source::Transport::Redis { key, .. } = &source.transport {
+            redis_keys.push((format!("redis `{}`", source.name), &source.url, key));
+        }
+    }
+    for source in &config.todo_sources {
+        if let crate::todo_store::TodoBackend::Redis { url, key } = &source.backend {
+            redis_keys.push(("redis todos".to_string(), url, key));
+        }
+    }
+    for (name, url, key) in redis_keys {
+        let started = Instant::now();
+        let result = crate::redis_source::read(url, key)
+            .map(|value| match value.as_array() {
+                Some(items) => format!("{} ({} items)", key, items.len()),
+                None => key.to_string(),
+            })
+            .map_err(|e| format!("{}: {}", name, e));
+        probe(name, result, started, Level::Fail);
+    }
+    if let Some(mqtt) = &config.mqtt {
+        let started = Instant::now();
+        let addr = format!("{}:{}", mqtt.host, mqtt.port);
+        let result = std::net::ToSocketAddrs::to_socket_addrs(&addr)
+            .map_err(|e| e.to_string())
+            .and_then(|mut addrs| addrs.next().ok_or_else(|| "no address".to_string()))
+            .and_then(|sock| std::net::TcpStream::connect_timeout(&sock, Duration::from_secs(5)).map_err(|e| e.to_string()))
+            .map(|_| format!("{} (events on {}/events)", addr, mqtt.topic))
+            .map_err(|e| format!("mqtt {}: {}", addr, e));
+        probe("mqtt".to_string(), result, started, Level::Fail);
+    }
+    if config.time_sync {
+        let started = Instant::now();
+        let result = match crate::timesync::check() {
+            Some((true, source)) => Ok(format!("synchronized ({})", source)),
+            Some((false, source)) => Err(format!("time sync: system clock is not synchronized ({})", source)),
+            None => Err("time sync: status unavailable (no answer from timedatectl or chronyc)".to_string()),
+        };
+        probe("time sync".to_string(), result, started, Level::Warn);
+    }
+    if let Some(ntp) = &config.ntp {
+        let started = Instant::now();
+        // 偏差超过 warn_ms 也按警告报告
+        let result = crate::ntp::query(&ntp.server).and_then(|ms| match ms.unsigned_abs() > ntp.warn_ms {
+            true => Err(format!("ntp {}: system clock is off by {} (warn_ms {})", ntp.server, crate::ntp::format_offset(ms), ntp.warn_ms)),
+            false => Ok(format!("system clock offset {}", crate::ntp::format_offset(ms))),
+        });
+        probe(format!("ntp {}", ntp.server), result, started, Level::Warn);
+    }
+    // 对比模式的室外温度
+    if config.compare_indoor_outdoor {
+        let started = Instant::now();
+        probe("wttr.in (outdoor)".to_string(), crate::api::fetch_wttr_now(None), started, Level::Warn);
+    }
+}
+
+// 温度提供者链：按顺序逐个请求（不使用缓存），并显示状态文件中缓存结果的时间；
+// 有提供者成功时其他提供者的失败只是警告
+fn check_providers(report: &mut Report, config: &Config) {
+    report.section("temperature providers");
+    if config.temperature_providers.is_empty() {
+        report.item(Level::Fail, "temperature_providers is empty, temperature will show --");
+        return;
+    }
+    let state = crate::state::load(&config.state_file);
+    let results: Vec<_> = config
+        .temperature_providers
+        .iter()
+        .map(|provider| {
+            let started = Instant::now();
+            let result = provider.fetch_now(config);
+            (provider.name(), result, started.elapsed().as_millis())
+        })
+        .collect();
+    let any_ok = results.iter().any(|(_, result, _)| result.is_ok());
+    for (i, (name, result, ms)) in results.into_iter().enumerate() {
+        let cached = state.providers.get(&name).map(|c| format!(", cached {}", c.at.format("%Y-%m-%d %H:%M"))).unwrap_or_default();
+        match result {
+            Ok((temp, humidity)) => {
+                let humidity = humidity.map(|h| format!(" {:.0}%", h)).unwrap_or_default();
+                report.item(Level::Ok, format!("{}. {}: {}{} ({} ms{})", i + 1, name, temp, humidity, ms, cached));
+            }
+            Err(e) => {
+                let level = if any_ok { Level::Warn } else { Level::Fail };
+                report.item(level, format!("{}. {}: {} ({} ms{})", i + 1, name, e, ms, cached));
+            }
+        }
+    }
+}
+
+fn check_terminal(report: &mut Report, config: &Config) -> io::Result<()> {
+    report.section("terminal");
+    let mut stdout = io::stdout();
+    if !stdout.is_terminal() {
+        report.item(Level::Warn, "stdout is not a terminal, skipping terminal checks");
+        return Ok(());
+    }
+    let term = std::env::var("TERM").unwrap_or_default();
+    match term.as_str() {
+        "" | "dumb" => report.item(Level::Fail, format!("TERM is `{}`, no cursor movement or colours", term)),
+        _ => report.item(Level::Ok, format!("TERM={}", term)),
+    }
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        report.item(Level::Ok, "24-bit colour (COLORTERM)");
+    } else if term.contains("256color") {
+        report.item(Level::Ok, "256 colours");
+    } else {
+        report.item(Level::Warn, "16 colours only, RGB colours such as orange are approximated");
+    }
+    let locale = crate::ascii::locale();
+    if locale.to_lowercase().replace('-', "").contains("utf8") {
+        report.item(Level::Ok, format!("locale {}", locale));
+    } else if locale.is_empty() {
+        report.item(Level::Warn, "locale not set (LANG), block digits may not render");
+    } else {
+        report.item(Level::Warn, format!("locale `{}` is not UTF-8, block digits may not render", locale));
+    }
+    if config.ascii {
+        report.item(Level::Ok, "ASCII mode: block digits and borders drawn with # - | +");
+    } else if crate::ascii::detect() {
+        report.item(Level::Warn, "ascii: false overrides the detected ASCII-only terminal");
+    }
+    match terminal::size() {
+        Ok((w, h)) => report.item(Level::Ok, format!("size {}x{}", w, h)),
+        Err(e) => report.item(Level::Fail, format!("cannot read terminal size: {}", e)),
+    }
+
+    // 打印后读取光标列，与 unicode-width 的宽度比较（不一致时布局会错位）
+    for sample in ["█", "℃", "°", "待"] {
+        execute!(stdout, cursor::MoveToColumn(0))?;
+        write!(stdout, "{}", sample)?;
+        stdout.flush()?;
+        let column = cursor::position().map(|(x, _)| x as usize);
+        execute!(stdout, cursor::MoveToColumn(0), terminal::Clear(terminal::ClearType::CurrentLine))?;
+        let expected = sample.width();
+        match column {
+            Ok(width) if width == expected => report.item(Level::Ok, format!("{} is {} column(s) wide", sample, width)),
+            Ok(width) => report.item(
+                Level::Warn,
+                format!("{} is {} column(s) wide, expected {}; layout may be misaligned", sample, width, expected),
+            ),
+            Err(e) => {
+                report.item(Level::Warn, format!("cannot query cursor position: {}", e));
+                break;
+            }
+        }
+    }
+
+    write!(stdout, "\x07")?;
+    stdout.flush()?;
+    std::thread::sleep(Duration::from_millis(300));
+    report.item(Level::Ok, "BEL sent; if you heard no beep, chimes will only flash");
+    Ok(())
+}
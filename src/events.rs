@@ -0,0 +1,69 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+// 事件记录：最近的报时、闹钟、提醒和数据源错误，e 键打开的事件页面按时间倒序列出，
+// 离开一段时间后回来可以看到错过了什么。只保存在内存中
+const MAX_EVENTS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EventKind {
+    Chime,
+    Alarm,
+    Alert,
+    Error,
+}
+
+impl EventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventKind::Chime => "chime",
+            EventKind::Alarm => "alarm",
+            EventKind::Alert => "alert",
+            EventKind::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub at: DateTime<Local>,
+    pub kind: EventKind,
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: VecDeque<Event>,
+    // 上一轮仍存在的错误，避免每次刷新失败都重复记录
+    active_errors: Vec<String>,
+}
+
+impl EventLog {
+    pub fn push(&mut self, kind: EventKind, text: String) {
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(Event { at: Local::now(), kind, text });
+    }
+
+    // 当前的错误列表：只记录新出现的错误，恢复后再次出错会重新记录
+    pub fn track_errors(&mut self, errors: Vec<String>) {
+        let new: Vec<String> = errors.iter().filter(|e| !self.active_errors.contains(e)).cloned().collect();
+        for error in new {
+            self.push(EventKind::Error, error);
+        }
+        self.active_errors = errors;
+    }
+
+    // 最新的在前
+    pub fn recent(&self) -> Vec<Event> {
+        self.events.iter().rev().cloned().collect()
+    }
+
+    // attach 前端：使用守护进程的记录（最新的在前）
+    pub fn replace(&mut self, recent: Vec<Event>) {
+        self.events = recent.into_iter().rev().collect();
+    }
+}
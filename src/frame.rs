@@ -0,0 +1,79 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+
+// 时钟装饰边框：按缩放后大字的实际尺寸围绕数字绘制，上下各占一行、左右与笔画之间留一列。
+// clock_frame: box / double / rounded / heavy，或 {style: double, corners: "◆◆◆◆", color: darkgray}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameStyle {
+    Box,
+    Double,
+    Rounded,
+    Heavy,
+}
+
+impl FrameStyle {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "box" | "single" => Some(FrameStyle::Box),
+            "double" => Some(FrameStyle::Double),
+            "rounded" => Some(FrameStyle::Rounded),
+            "heavy" | "thick" => Some(FrameStyle::Heavy),
+            _ => None,
+        }
+    }
+
+    // 横线、竖线和四角（左上、右上、左下、右下）
+    fn glyphs(&self) -> (char, char, [char; 4]) {
+        match self {
+            FrameStyle::Box => ('─', '│', ['┌', '┐', '└', '┘']),
+            FrameStyle::Double => ('═', '║', ['╔', '╗', '╚', '╝']),
+            FrameStyle::Rounded => ('─', '│', ['╭', '╮', '╰', '╯']),
+            FrameStyle::Heavy => ('━', '┃', ['┏', '┓', '┗', '┛']),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockFrame {
+    pub style: FrameStyle,
+    // 自定义四角字符（单列宽），替换线型自带的转角
+    pub corners: Option<[char; 4]>,
+    // 默认使用 time_color
+    pub color: Option<Color>,
+}
+
+// 四个字符依次为左上、右上、左下、右下；一个字符时四角相同
+pub fn parse_corners(s: &str) -> Option<[char; 4]> {
+    let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+    match chars.as_slice() {
+        [c] => Some([*c; 4]),
+        [a, b, c, d] => Some([*a, *b, *c, *d]),
+        _ => None,
+    }
+}
+
+// 在 rect 的四边绘制边框，内部不变；不足 2x2 时不绘制
+pub fn render(buffer: &mut Buffer, rect: Rect, frame: &ClockFrame, style: Style) {
+    if rect.width < 2 || rect.height < 2 {
+        return;
+    }
+    let (horizontal, vertical, corners) = frame.style.glyphs();
+    let [top_left, top_right, bottom_left, bottom_right] = frame.corners.unwrap_or(corners);
+    let (left, top, right, bottom) = (rect.left(), rect.top(), rect.right() - 1, rect.bottom() - 1);
+    let mut put = |x: u16, y: u16, c: char| {
+        buffer.get_mut(x, y).set_char(c).set_style(style);
+    };
+    for x in left + 1..right {
+        put(x, top, horizontal);
+        put(x, bottom, horizontal);
+    }
+    for y in top + 1..bottom {
+        put(left, y, vertical);
+        put(right, y, vertical);
+    }
+    put(left, top, top_left);
+    put(right, top, top_right);
+    put(left, bottom, bottom_left);
+    put(right, bottom, bottom_right);
+}
@@ -0,0 +1,135 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{self, PollResult, StoredToken, CALENDAR_SCOPE};
+use crate::model::{GcalConfig, Meeting};
+
+// 后台线程与界面共享的日历状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GcalStatus {
+    Connecting,
+    AuthPending { url: String, code: String },
+    Ready(Option<Meeting>),
+    Error(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct EventList {
+    #[serde(default)]
+    items: Vec<Event>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Event {
+    id: String,
+    summary: Option<String>,
+    start: EventTime,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventTime {
+    // 全天事件只有 date 字段，此处为 None
+    date_time: Option<String>,
+}
+
+// 启动日历后台线程：授权、刷新令牌并定期获取下一场会议
+pub fn spawn_worker(cfg: GcalConfig) -> Arc<Mutex<GcalStatus>> {
+    let status = Arc::new(Mutex::new(GcalStatus::Connecting));
+    let shared = status.clone();
+    std::thread::spawn(move || {
+        let set = |s: GcalStatus| {
+            if let Ok(mut guard) = shared.lock() { *guard = s; }
+        };
+        let mut token = auth::load_token(&cfg.token_path);
+        loop {
+            let current = match token.take() {
+                Some(t) if !t.is_expired() => Some(t),
+                Some(t) => auth::refresh_token(&cfg.client, &t).inspect(|fresh| {
+                    let _ = auth::save_token(&cfg.token_path, fresh);
+                }),
+                None => authorize(&cfg, &set),
+            };
+            let Some(current) = current else {
+                std::thread::sleep(Duration::from_secs(30));
+                continue;
+            };
+            match fetch_next_meeting(&cfg.calendar_id, &current) {
+                Ok(meeting) => set(GcalStatus::Ready(meeting)),
+                Err(e) => set(GcalStatus::Error(e)),
+            }
+            token = Some(current);
+            std::thread::sleep(crate::power::stretch(Duration::from_secs(cfg.refresh_secs)));
+        }
+    });
+    status
+}
+
+// 设备授权流程：界面显示验证码，轮询直到用户完成授权
+fn authorize(cfg: &GcalConfig, set: &impl Fn(GcalStatus)) -> Option<StoredToken> {
+    let Some(device) = auth::request_device_code(&cfg.client, CALENDAR_SCOPE) else {
+        set(GcalStatus::Error("device code request failed".into()));
+        return None;
+    };
+    set(GcalStatus::AuthPending { url: device.verification_url.clone(), code: device.user_code.clone() });
+    let mut interval = device.interval;
+    let mut waited = 0;
+    while waited < device.expires_in {
+        std::thread::sleep(Duration::from_secs(interval));
+        waited += interval;
+        match auth::poll_device_token(&cfg.client, &device) {
+            PollResult::Pending => {}
+            PollResult::SlowDown => interval += 5,
+            PollResult::Granted(token) => {
+                let _ = auth::save_token(&cfg.token_path, &token);
+                set(GcalStatus::Connecting);
+                return Some(token);
+            }
+            PollResult::Failed(e) => {
+                set(GcalStatus::Error(e));
+                return None;
+            }
+        }
+    }
+    set(GcalStatus::Error("authorization expired".into()));
+    None
+}
+
+fn fetch_next_meeting(calendar_id: &str, token: &StoredToken) -> Result<Option<Meeting>, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = format!(
+        "https://www.googleapis.com/calendar/v3/calendars/{}/events",
+        calendar_id.replace('@', "%40").replace('#', "%23")
+    );
+    let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let _permit = crate::ratelimit::permit();
+    let list: EventList = client
+        .get(&url)
+        .bearer_auth(&token.access_token)
+        .query(&[
+            ("timeMin", now.as_str()),
+            ("maxResults", "5"),
+            ("singleEvents", "true"),
+            ("orderBy", "startTime"),
+        ])
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .map_err(|e| e.to_string())?;
+    // 跳过全天事件，只提醒有具体开始时间的会议
+    Ok(list.items.into_iter().find_map(|ev| {
+        let start = ev.start.date_time.as_deref().and_then(|s| DateTime::parse_from_rfc3339(s).ok())?;
+        Some(Meeting {
+            id: ev.id,
+            title: ev.summary.unwrap_or_else(|| "(no title)".into()),
+            start: start.with_timezone(&Local),
+        })
+    }))
+}
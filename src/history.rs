@@ -0,0 +1,198 @@
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local, NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+
+// 历史记录（JSON Lines，每行一条）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub ts: DateTime<Local>,
+    pub temp: Option<f64>,
+    // 旧版本的记录没有湿度和完成数
+    #[serde(default)]
+    pub humidity: Option<f64>,
+    pub todos: usize,
+    // 今天已完成的待办数（开启 todo_summary 时记录）
+    #[serde(default)]
+    pub done_today: Option<usize>,
+}
+
+// termclock export 的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+// 默认历史文件：~/.local/share/termclock/history.jsonl
+pub fn default_history_path() -> PathBuf {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| Path::new(&h).join(".local").join("share")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    base.join("termclock").join("history.jsonl")
+}
+
+pub fn append(path: &Path, record: &HistoryRecord) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let line = serde_json::to_string(record).map_err(io::Error::other)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+// 读取 [from, to) 之间的记录（未指定的一端不限），跳过无法解析的行（写入中断等）
+pub fn load_range(path: &Path, from: Option<DateTime<Local>>, to: Option<DateTime<Local>>) -> io::Result<Vec<HistoryRecord>> {
+    let file = std::fs::File::open(path)?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<HistoryRecord>(&line).ok())
+        .filter(|record| from.is_none_or(|from| record.ts >= from) && to.is_none_or(|to| record.ts < to))
+        .collect())
+}
+
+// 从文件末尾按块向前查找，直到块中第一条完整的记录早于 since（记录按时间顺序追加），
+// 只解析这之后的部分，不必读取整个历史文件
+const TAIL_CHUNK: u64 = 64 * 1024;
+
+pub fn load_since(path: &Path, since: DateTime<Local>) -> io::Result<Vec<HistoryRecord>> {
+    let mut file = std::fs::File::open(path)?;
+    let mut start = file.metadata()?.len();
+    while start > 0 {
+        start = start.saturating_sub(TAIL_CHUNK);
+        file.seek(SeekFrom::Start(start))?;
+        let mut lines = BufReader::new(&mut file).lines().skip(usize::from(start > 0));
+        if lines.next().and_then(Result::ok).and_then(|line| serde_json::from_str::<HistoryRecord>(&line).ok()).is_some_and(|r| r.ts < since) {
+            break;
+        }
+    }
+    file.seek(SeekFrom::Start(start))?;
+    Ok(BufReader::new(file)
+        .lines()
+        .skip(usize::from(start > 0))
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<HistoryRecord>(&line).ok())
+        .filter(|record| record.ts >= since)
+        .collect())
+}
+
+// 导出：CSV 每条记录一行（缺失的值留空），JSON 为记录数组
+pub fn export(records: &[HistoryRecord], format: ExportFormat, out: &mut impl Write) -> io::Result<()> {
+    match format {
+        ExportFormat::Csv => {
+            writeln!(out, "timestamp,temperature,humidity,todos,done_today")?;
+            let opt = |v: Option<String>| v.unwrap_or_default();
+            for r in records {
+                writeln!(
+                    out,
+                    "{},{},{},{},{}",
+                    r.ts.to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+                    opt(r.temp.map(|t| t.to_string())),
+                    opt(r.humidity.map(|h| h.to_string())),
+                    r.todos,
+                    opt(r.done_today.map(|d| d.to_string())),
+                )?;
+            }
+            Ok(())
+        }
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, records)?;
+            writeln!(out)
+        }
+    }
+}
+
+// 热力图覆盖的天数（含今天）
+pub const HEATMAP_DAYS: i64 = 7;
+
+// 温度热力图：最近几天每小时的平均温度，从最早的一天到今天；没有记录的小时为 None
+#[derive(Debug, Clone, Default)]
+pub struct Heatmap {
+    pub days: Vec<(NaiveDate, [Option<f64>; 24])>,
+}
+
+// 在后台线程读取最近的记录并生成热力图，完成后写入 state；读取期间 state 保留上一次的结果
+pub fn load_heatmap(path: PathBuf, state: Arc<Mutex<Option<Heatmap>>>) {
+    std::thread::spawn(move || {
+        let now = Local::now();
+        let records = load_since(&path, now - chrono::Duration::days(HEATMAP_DAYS)).unwrap_or_default();
+        let heatmap = Heatmap::build(&records, now.date_naive());
+        if let Ok(mut guard) = state.lock() {
+            *guard = Some(heatmap);
+        }
+    });
+}
+
+impl Heatmap {
+    pub fn build(records: &[HistoryRecord], today: NaiveDate) -> Self {
+        let first = today - chrono::Duration::days(HEATMAP_DAYS - 1);
+        let mut sums = vec![[(0.0, 0u32); 24]; HEATMAP_DAYS as usize];
+        for record in records {
+            let (Some(temp), date) = (record.temp, record.ts.date_naive()) else { continue };
+            if date < first || date > today {
+                continue;
+            }
+            let cell = &mut sums[(date - first).num_days() as usize][record.ts.hour() as usize];
+            cell.0 += temp;
+            cell.1 += 1;
+        }
+        let days = sums
+            .into_iter()
+            .enumerate()
+            .map(|(i, hours)| (first + chrono::Duration::days(i as i64), hours.map(|(sum, n)| (n > 0).then(|| sum / n as f64))))
+            .collect();
+        Heatmap { days }
+    }
+
+    // 有记录的小时中最低、最高的平均温度
+    pub fn range(&self) -> Option<(f64, f64)> {
+        let values = self.days.iter().flat_map(|(_, hours)| hours.iter().flatten().copied());
+        values.fold(None, |range, t| match range {
+            None => Some((t, t)),
+            Some((min, max)) => Some((f64::min(min, t), f64::max(max, t))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_since_matches_full_scan() {
+        let path = std::env::temp_dir().join(format!("termclock-history-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let start = Local::now() - chrono::Duration::days(30);
+        // 约 30 天、每 10 分钟一条，跨越多个读取块；中间夹一行损坏的记录
+        for i in 0..4320 {
+            let record = HistoryRecord { ts: start + chrono::Duration::minutes(i * 10), temp: Some(20.0), humidity: None, todos: 0, done_today: None };
+            append(&path, &record).unwrap();
+            if i == 4000 {
+                std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{broken\n").unwrap();
+            }
+        }
+        for days in [0, 1, 7, 29, 31] {
+            let since = Local::now() - chrono::Duration::days(days);
+            let expected: Vec<_> = load_range(&path, Some(since), None).unwrap().iter().map(|r| r.ts).collect();
+            let tail: Vec<_> = load_since(&path, since).unwrap().iter().map(|r| r.ts).collect();
+            assert_eq!(tail, expected, "days = {}", days);
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,16 @@
+use std::process::{Command, Stdio};
+
+// 用户命令钩子（on_hour_command / on_alarm_command）：通过 sh -c 在后台运行，
+// 不等待结束、不显示输出，用于触发智能家居场景或播放自定义声音。
+// 参数通过环境变量传入，如 $HOUR、$ALARM_LABEL
+pub fn run(command: &str, env: &[(&str, String)]) {
+    let mut child = Command::new("sh");
+    child.arg("-c").arg(command).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    for (name, value) in env {
+        child.env(name, value);
+    }
+    // 单独线程等待退出，避免留下僵尸进程
+    if let Ok(mut child) = child.spawn() {
+        std::thread::spawn(move || child.wait());
+    }
+}
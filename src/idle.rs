@@ -0,0 +1,56 @@
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+
+// 空闲变暗：一段时间没有按键或鼠标操作后降低整个画面的亮度，或只显示一个小时钟，
+// 减少烧屏和对桌边屏幕的注意力干扰；任意按键或鼠标操作恢复（该按键不执行其原本的功能）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdleMode {
+    Dim,
+    Clock,
+}
+
+impl IdleMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dim" => Some(IdleMode::Dim),
+            "clock" | "blank" => Some(IdleMode::Clock),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdleDim {
+    pub after_secs: u64,
+    pub mode: IdleMode,
+}
+
+// 降低缓冲区中所有颜色的亮度：RGB 按比例变暗，亮色换成对应的暗色，其余加上 DIM
+pub fn dim(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        cell.fg = darken(cell.fg);
+        cell.bg = match cell.bg {
+            Color::Reset => Color::Reset,
+            bg => darken(bg),
+        };
+        cell.modifier = cell.modifier.difference(Modifier::BOLD).union(Modifier::DIM);
+    }
+}
+
+fn darken(color: Color) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => {
+            let scale = |c: u8| (u16::from(c) * 2 / 5) as u8;
+            Color::Rgb(scale(r), scale(g), scale(b))
+        }
+        Color::White => Color::Gray,
+        Color::Gray => Color::DarkGray,
+        Color::LightRed => Color::Red,
+        Color::LightGreen => Color::Green,
+        Color::LightYellow => Color::Yellow,
+        Color::LightBlue => Color::Blue,
+        Color::LightMagenta => Color::Magenta,
+        Color::LightCyan => Color::Cyan,
+        other => other,
+    }
+}
@@ -0,0 +1,231 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::chime::BeepPattern;
+use crate::gcal::GcalStatus;
+use crate::source::SourceState;
+use crate::model::TodoSummary;
+
+// mute 的分钟数上限（100 天），更大的值视为无效命令
+pub const MAX_MUTE_MINUTES: u64 = 100 * 24 * 60;
+
+// IPC 命令（每个连接发送一行文本）
+#[derive(Debug, Clone, PartialEq)]
+pub enum IpcCommand {
+    // mute [分钟]：静音，可选自动解除
+    Mute(Option<u64>),
+    Unmute,
+    ToggleMute,
+    Snooze,
+    Dismiss,
+    Refresh,
+    // timer <秒> / timer cancel：倒计时结束时像闹钟一样响铃
+    Timer(Option<u64>),
+    // 返回当前状态（JSON），供 attach 前端渲染
+    Snapshot,
+    // 请求实例退出（--takeover）
+    Quit,
+    // 网页服务收到的推送数据（POST /push/...）
+    Push(Push),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Push {
+    Temperature { temp: f64, humidity: Option<f64> },
+    // None 表示恢复使用配置的待办来源
+    Todos(Option<Vec<String>>),
+    // 文本为空时清除；secs 秒后自动消失，未指定则一直显示
+    Message { text: String, secs: Option<u64> },
+}
+
+impl IpcCommand {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        let cmd = match parts.next()?.to_ascii_lowercase().as_str() {
+            "mute" => match parts.next() {
+                Some(m) => IpcCommand::Mute(Some(m.parse().ok().filter(|&m| m <= MAX_MUTE_MINUTES)?)),
+                None => IpcCommand::Mute(None),
+            },
+            "unmute" => IpcCommand::Unmute,
+            "toggle-mute" => IpcCommand::ToggleMute,
+            "snooze" => IpcCommand::Snooze,
+            "dismiss" => IpcCommand::Dismiss,
+            "refresh" => IpcCommand::Refresh,
+            "timer" => match parts.next()? {
+                "cancel" => IpcCommand::Timer(None),
+                secs => IpcCommand::Timer(Some(secs.parse().ok().filter(|&s| s > 0 && s <= crate::palette::MAX_TIMER.as_secs())?)),
+            },
+            "snapshot" => IpcCommand::Snapshot,
+            "quit" => IpcCommand::Quit,
+            _ => return None,
+        };
+        if parts.next().is_some() { None } else { Some(cmd) }
+    }
+}
+
+// 守护进程状态快照
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub now: Option<chrono::DateTime<chrono::Local>>,
+    pub temperature: Option<String>,
+    pub todos: Vec<String>,
+    pub muted: bool,
+    pub unmute_in_secs: Option<u64>,
+    pub alarm: Option<AlarmSnapshot>,
+    pub flash_ms: Option<u64>,
+    pub meeting: Option<GcalStatus>,
+    // 每次发声递增，前端据此在本地终端重放提示音
+    pub sound_seq: u64,
+    pub last_sound: Option<BeepPattern>,
+    #[serde(default)]
+    pub sources: Vec<(String, SourceState)>,
+    #[serde(default)]
+    pub scripts: Vec<(String, crate::script::ScriptState)>,
+    #[serde(default)]
+    pub panels: Vec<(String, crate::panel::PanelState)>,
+    #[serde(default)]
+    pub api_error: Option<String>,
+    #[serde(default)]
+    pub todo_summary: Option<TodoSummary>,
+    // 待办内容 -> todo_sources 序号（显示来源标签和颜色）
+    #[serde(default)]
+    pub todo_origins: std::collections::HashMap<String, usize>,
+    // 离线横幅（超过 offline_after_mins 后才有值）
+    #[serde(default)]
+    pub offline_since: Option<chrono::DateTime<chrono::Local>>,
+    // 倒计时（剩余秒数，标签）
+    #[serde(default)]
+    pub timer: Option<(u64, String)>,
+    #[serde(default)]
+    pub data_updated: Option<chrono::DateTime<chrono::Local>>,
+    #[serde(default)]
+    pub markers: Vec<crate::markers::DailyMarker>,
+    // “接下来”数据（名称，显示条数，状态）
+    #[serde(default)]
+    pub upcoming: Vec<(String, usize, crate::upcoming::FeedState)>,
+    #[serde(default)]
+    pub transit: Option<crate::transit::TransitState>,
+    #[serde(default)]
+    pub ntp: Option<crate::ntp::NtpState>,
+    // 当前天气状况（天气强调色）
+    #[serde(default)]
+    pub condition: Option<crate::weather::Condition>,
+    // 系统时间同步状态（timedatectl / chrony）
+    #[serde(default)]
+    pub time_synced: Option<bool>,
+    // 事件记录（最新的在前）
+    #[serde(default)]
+    pub events: Vec<crate::events::Event>,
+    #[serde(default)]
+    pub outdoor_temperature: Option<String>,
+    #[serde(default)]
+    pub temp_range: Option<crate::temperature::DailyRange>,
+    #[serde(default)]
+    pub humidity: Option<f64>,
+    #[serde(default)]
+    pub humidity_range: Option<crate::temperature::DailyRange>,
+    #[serde(default)]
+    pub forecast: Option<crate::weather::ForecastState>,
+    #[serde(default)]
+    pub rain_snoozed_until: Option<chrono::DateTime<chrono::Local>>,
+    // 雷达摘要（不含图像）
+    #[serde(default)]
+    pub radar: Option<crate::radar::RadarState>,
+    #[serde(default)]
+    pub local_sensors: Vec<crate::sensors::SensorReading>,
+    // 推送的消息
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmSnapshot {
+    pub label: String,
+    pub snooze_count: u32,
+    pub snoozed_secs: Option<u64>,
+}
+
+// 一次请求：主循环处理后通过 respond 回复客户端
+pub struct IpcRequest {
+    pub command: IpcCommand,
+    reply: Sender<String>,
+}
+
+impl IpcRequest {
+    pub fn respond(self, msg: impl Into<String>) {
+        let _ = self.reply.send(msg.into());
+    }
+}
+
+// 默认 socket 路径：$XDG_RUNTIME_DIR/termclock.sock，否则 /tmp/termclock.sock
+pub fn default_socket_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => Path::new(&dir).join("termclock.sock"),
+        None => PathBuf::from("/tmp/termclock.sock"),
+    }
+}
+
+// 启动 IPC 监听线程，命令通过 tx 交给主循环
+pub fn spawn_server(path: &Path, tx: Sender<IpcRequest>) -> io::Result<()> {
+    // 清理上次异常退出遗留的 socket 文件
+    if path.exists() && UnixStream::connect(path).is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+    let listener = UnixListener::bind(path)?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let tx = tx.clone();
+            std::thread::spawn(move || handle_client(stream, tx));
+        }
+    });
+    Ok(())
+}
+
+// 将命令交给主循环并等待回复（IPC 与 Web 服务共用）
+pub fn send_request(tx: &Sender<IpcRequest>, command: IpcCommand) -> Option<String> {
+    let (reply_tx, reply_rx) = mpsc::channel();
+    tx.send(IpcRequest { command, reply: reply_tx }).ok()?;
+    reply_rx.recv_timeout(Duration::from_secs(2)).ok()
+}
+
+fn handle_client(stream: UnixStream, tx: Sender<IpcRequest>) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let Ok(mut writer) = stream.try_clone() else { return };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() { continue; }
+        let reply = match IpcCommand::parse(&line) {
+            Some(command) => send_request(&tx, command).unwrap_or_else(|| "error: no response".to_string()),
+            None => format!("error: unknown command: {}", line.trim()),
+        };
+        if writeln!(writer, "{}", reply).is_err() { break; }
+    }
+}
+
+// IPC 客户端：保持一个连接，逐行请求/应答
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl Client {
+    pub fn connect(path: &Path) -> io::Result<Self> {
+        let stream = UnixStream::connect(path)?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        Ok(Self { writer: stream.try_clone()?, reader: BufReader::new(stream) })
+    }
+
+    pub fn request(&mut self, line: &str) -> io::Result<String> {
+        writeln!(self.writer, "{}", line)?;
+        let mut reply = String::new();
+        if self.reader.read_line(&mut reply)? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed"));
+        }
+        Ok(reply.trim_end().to_string())
+    }
+}
@@ -0,0 +1,124 @@
+use serde_json::Value;
+
+// JSONPath 子集：$ . [n] [-n] [*] .* ['key'] ..key
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+    Wildcard,
+    // 递归查找所有层级中的同名字段
+    Descendant(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(expr: &str) -> Option<Self> {
+        let expr = expr.trim();
+        // 允许省略开头的 $.，如 "data.rows[0]"
+        let owned;
+        let mut rest = match expr.strip_prefix('$') {
+            Some(rest) => rest,
+            None if expr.starts_with(['.', '[']) => expr,
+            None => {
+                owned = format!(".{}", expr);
+                owned.as_str()
+            }
+        };
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix("..") {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                if end == 0 {
+                    return None;
+                }
+                segments.push(Segment::Descendant(after[..end].to_string()));
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('.') {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                segments.push(match &after[..end] {
+                    "" => return None,
+                    "*" => Segment::Wildcard,
+                    key => Segment::Key(key.to_string()),
+                });
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']')?;
+                let inner = after[..end].trim();
+                segments.push(if inner == "*" {
+                    Segment::Wildcard
+                } else if let Some(key) = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+                {
+                    Segment::Key(key.to_string())
+                } else {
+                    Segment::Index(inner.parse().ok()?)
+                });
+                rest = &after[end + 1..];
+            } else {
+                return None;
+            }
+        }
+        Some(Self { segments })
+    }
+
+    // 返回所有匹配的节点（按文档顺序）
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for value in current {
+                match segment {
+                    Segment::Key(key) => next.extend(value.get(key)),
+                    Segment::Index(i) => {
+                        if let Some(items) = value.as_array() {
+                            let idx = if *i < 0 { items.len() as i64 + i } else { *i };
+                            next.extend(usize::try_from(idx).ok().and_then(|idx| items.get(idx)));
+                        }
+                    }
+                    Segment::Wildcard => match value {
+                        Value::Array(items) => next.extend(items),
+                        Value::Object(map) => next.extend(map.values()),
+                        _ => {}
+                    },
+                    Segment::Descendant(key) => collect_descendants(value, key, &mut next),
+                }
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+fn collect_descendants<'a>(value: &'a Value, key: &str, out: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(v) = map.get(key) {
+                out.push(v);
+            }
+            for v in map.values() {
+                collect_descendants(v, key, out);
+            }
+        }
+        Value::Array(items) => {
+            for v in items {
+                collect_descendants(v, key, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+// 将 JSON 节点转换为显示文本（字符串不带引号）
+pub fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "--".to_string(),
+        other => other.to_string(),
+    }
+}
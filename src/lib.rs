@@ -0,0 +1,553 @@
+mod model;
+mod api;
+mod ui;
+mod config;
+mod schedule;
+mod chime;
+mod ipc;
+mod alarm;
+mod auth;
+mod gcal;
+mod app;
+mod history;
+mod daemon;
+mod lock;
+mod systemd;
+mod web;
+mod record;
+mod jsonpath;
+mod source;
+mod lenient;
+mod todo_db;
+mod todo_store;
+mod todo;
+mod backend;
+mod taskwarrior;
+mod temperature;
+mod doctor;
+mod state;
+mod theme;
+mod palette;
+mod background;
+mod countdown;
+mod solar;
+mod markers;
+mod upcoming;
+mod transit;
+mod comfort;
+mod weather;
+mod radar;
+mod sensors;
+mod ble;
+mod serial;
+mod dht;
+mod script;
+mod panel;
+mod provider;
+mod ratelimit;
+mod conditional;
+mod api_schema;
+mod redis_source;
+mod mqtt;
+mod hooks;
+mod supervise;
+mod power;
+mod ascii;
+mod accessible;
+mod calendar;
+mod ntp;
+mod timesync;
+mod events;
+mod frame;
+mod idle;
+mod todo_transfer;
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use backend::QuietBackend;
+use background::Background;
+use countdown::CountdownPhase;
+use idle::IdleMode;
+use power::LowPower;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::{Frame, Terminal};
+
+use model::{App, ClockFace, Config, View};
+use config::{parse_args, parse_cli, Command, ExportCommand, TodoCommand};
+
+// 基准测试（benches/）使用的内部接口
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::config::parse_args;
+    pub use crate::draw_frame;
+    pub use crate::model::App;
+    pub use crate::ui::{render_big_time, thermometer_layout, BigTextStyle};
+}
+
+// 等待下一帧期间检查 IPC 请求的间隔
+const IPC_POLL: Duration = Duration::from_millis(50);
+// 省电模式下减少唤醒次数
+const LOW_POWER_IPC_POLL: Duration = Duration::from_millis(500);
+// 两帧之间的最短间隔（最高约 100 fps）
+const MIN_FRAME_GAP: Duration = Duration::from_millis(10);
+
+type Tui = Terminal<QuietBackend<io::Stdout>>;
+
+// 程序入口（src/main.rs 调用）
+pub fn run() -> io::Result<()> {
+    let cli = parse_cli();
+    let mut config = parse_args();
+    config.low_bandwidth |= cli.low_bandwidth;
+    config.ascii |= cli.ascii;
+    if cli.low_power {
+        config.low_power = LowPower::On;
+    }
+    let crash_log = supervise::crash_log_path(&config.state_file);
+    // 父进程不获取单实例锁，由子进程获取
+    if cli.supervise && matches!(cli.command, Command::Run | Command::Daemon | Command::Attach) {
+        return supervise::run(&crash_log);
+    }
+    if matches!(cli.command, Command::Run | Command::Daemon | Command::Attach) {
+        supervise::install_panic_hook(crash_log, !cli.accessible && !matches!(cli.command, Command::Daemon));
+    }
+    // Single instance per socket: avoids double chimes and duplicate API load
+    if matches!(cli.command, Command::Run | Command::Daemon)
+        && let Err(e) = lock::ensure_single_instance(&config.ipc_socket, cli.takeover)
+    {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    match cli.command {
+        Command::Run if cli.accessible => accessible::run(config),
+        Command::Run => run_tui(config, cli.record.as_deref()),
+        Command::Daemon => daemon::run_daemon(config),
+        Command::Attach => daemon::run_attach(config, cli.record.as_deref()),
+        Command::Todo(cmd) => run_todo_command(&config, cmd),
+        Command::Doctor => doctor::run_doctor(&config),
+        Command::Export(export) => run_export(&config, export),
+    }
+}
+
+// termclock export：把历史文件中的记录按时间范围输出到标准输出
+fn run_export(config: &Config, export: ExportCommand) -> io::Result<()> {
+    let records = match history::load_range(&config.history_file, export.from, export.to) {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("{}: {}", config.history_file.display(), e);
+            std::process::exit(1);
+        }
+    };
+    // 输出被 head 等提前关闭时正常退出
+    match history::export(&records, export.format, &mut io::stdout().lock()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+// termclock todo ...：操作当前配置的待办后端
+fn run_todo_command(config: &Config, cmd: TodoCommand) -> io::Result<()> {
+    let backend = todo_store::TodoBackend::from_config(config);
+    let changes = !matches!(cmd, TodoCommand::List | TodoCommand::Export(_));
+    let result = match cmd {
+        TodoCommand::List => backend.list(None).map(|todos| {
+            for (i, todo) in todos.iter().enumerate() {
+                println!("{:>3}  {}", i + 1, todo);
+            }
+        }),
+        TodoCommand::Add(task) => backend.add(&task),
+        TodoCommand::Done(n) => backend.complete(n - 1, None).and_then(|t| t.map(|t| println!("done: {}", t)).ok_or_else(|| no_item(n))),
+        TodoCommand::Rm(n) => backend.remove(n - 1, None).and_then(|t| t.map(|t| println!("removed: {}", t)).ok_or_else(|| no_item(n))),
+        TodoCommand::Export(format) => todo_transfer::export(&backend)
+            .and_then(|records| todo_transfer::write(&records, format))
+            .map(|out| {
+                // 输出被 head 等提前关闭时正常退出
+                let _ = io::Write::write_all(&mut io::stdout().lock(), out.as_bytes());
+            }),
+        TodoCommand::Import { path, format } => todo_transfer::read_input(&path)
+            .and_then(|content| todo_transfer::parse(&content, format))
+            .and_then(|records| todo_transfer::import(&backend, &records))
+            .map(|report| println!("{}", report.summary())),
+    };
+    if let Err(e) = result {
+        eprintln!("{}: {}", backend.name(), e);
+        std::process::exit(1);
+    }
+    // 通知运行中的实例立即刷新（未运行时忽略）
+    if changes
+        && let Ok(mut client) = ipc::Client::connect(&config.ipc_socket)
+    {
+        let _ = client.request("refresh");
+    }
+    Ok(())
+}
+
+fn no_item(n: usize) -> String {
+    format!("no todo #{}", n)
+}
+
+// 低带宽模式不开启鼠标捕获（鼠标移动事件会产生上行流量并触发重绘）
+fn setup_terminal(mouse: bool) -> io::Result<Tui> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    if mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+    let backend = QuietBackend::new(stdout);
+    Terminal::new(backend)
+}
+
+fn restore_terminal(terminal: &mut Tui) -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()
+}
+
+// 录制 / 截图：在每帧绘制完成后处理
+struct Capture {
+    recorder: Option<record::CastRecorder>,
+    screenshot_requested: bool,
+}
+
+impl Capture {
+    fn new(record_path: Option<&std::path::Path>) -> io::Result<Self> {
+        let recorder = match record_path {
+            Some(path) => {
+                let (w, h) = crossterm::terminal::size()?;
+                Some(record::CastRecorder::create(path, w, h)?)
+            }
+            None => None,
+        };
+        Ok(Self { recorder, screenshot_requested: false })
+    }
+
+    fn after_draw(&mut self, buffer: &ratatui::buffer::Buffer, screenshot_dir: &std::path::Path) {
+        if let Some(recorder) = &mut self.recorder
+            && recorder.frame(buffer).is_err()
+        {
+            // Stop recording on write errors instead of failing every frame
+            self.recorder = None;
+        }
+        if self.screenshot_requested {
+            self.screenshot_requested = false;
+            let _ = record::save_screenshot(buffer, screenshot_dir);
+        }
+    }
+}
+
+// 帧率限制：绘制很慢时（慢速 SSH、超大终端），两帧之间至少空闲与上次绘制相同的时间，
+// 等待时间不会变成 0，主循环不会空转占满 CPU
+struct FrameLimiter {
+    drawn_at: Instant,
+    draw_time: Duration,
+}
+
+impl FrameLimiter {
+    fn new() -> Self {
+        Self { drawn_at: Instant::now(), draw_time: Duration::ZERO }
+    }
+
+    // 记录一次绘制的开始时间和耗时
+    fn draw<T>(&mut self, draw: impl FnOnce() -> T) -> T {
+        self.drawn_at = Instant::now();
+        let result = draw();
+        self.draw_time = self.drawn_at.elapsed();
+        result
+    }
+
+    // 下一帧最早的绘制时间
+    fn earliest(&self) -> Instant {
+        self.drawn_at + self.draw_time + self.draw_time.max(MIN_FRAME_GAP)
+    }
+
+    // 按 frame_delay 计划的下一帧时间，不早于 earliest
+    fn deadline(&self, frame_delay: Duration) -> Instant {
+        (Instant::now() + frame_delay).max(self.earliest())
+    }
+}
+
+pub fn draw_frame(f: &mut Frame, app: &mut App) {
+    let size = f.size();
+    // 全屏背景色，其余内容画在其上
+    if let Some(color) = app.config.background_color {
+        ui::fill_background(f, size, color);
+    }
+    // 空闲时只显示小时钟
+    if app.idle_mode() == Some(IdleMode::Clock) {
+        return ui::draw_idle_clock(f, size, app.display_time());
+    }
+    // 状态栏占用最后一行
+    let main = match app.config.status_bar {
+        true => Rect { height: size.height.saturating_sub(1), ..size },
+        false => size,
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(app.config.main_window_percent), Constraint::Percentage(100 - app.config.main_window_percent)])
+        .split(main);
+
+    // Visual chime: toggle the inverted style every 500ms while flashing (steady in low-bandwidth and low-power modes)
+    let flash = app.flash_until.is_some_and(|until| {
+        let now = Instant::now();
+        now < until && (app.config.low_bandwidth || app.low_power || ((until - now).as_millis() / 500).is_multiple_of(2))
+    });
+    // 雷达页面替换时钟和侧栏
+    app.radar_area = None;
+    if app.view == View::Radar {
+        app.radar_area = ui::draw_radar_page(f, main, app);
+        return draw_overlays(f, size, app);
+    }
+    // 事件页面替换时钟和侧栏
+    if app.view == View::Events {
+        ui::draw_events_page(f, main, &app.events.recent(), app.now(), &app.config.palette);
+        return draw_overlays(f, size, app);
+    }
+    // 历史页面：最近 7 天的温度热力图
+    if app.view == View::History {
+        app.refresh_heatmap();
+        ui::draw_history_page(f, main, app.heatmap().as_ref(), &app.config.palette);
+        return draw_overlays(f, size, app);
+    }
+    let zone = app.display_tz.map(|tz| tz.name().to_string());
+    let countdown = app.countdown();
+    match &countdown {
+        // 倒计时最后一分钟：大字显示剩余秒数
+        Some(CountdownPhase::Counting { remaining, label }) => ui::draw_countdown(f, chunks[0], &app.config, *remaining, label),
+        _ if app.config.clocks.is_empty() => {
+            let face = ClockFace::primary(&app.config, zone);
+            // 大字温度在时钟右侧，两者整体居中；放不下时只显示时钟
+            let big = app.config.big_temperature.map(|big| ui::big_temperature_lines(app, &face, &big)).filter(|lines| {
+                let width = lines.first().map_or(0, |line| line.width() as u16);
+                ui::clock_width(app, &face, app.display_time()) + ui::BIG_TEMPERATURE_GAP + width <= chunks[0].width
+            });
+            match big {
+                Some(lines) => {
+                    let width = lines.first().map_or(0, |line| line.width() as u16) + ui::BIG_TEMPERATURE_GAP;
+                    let clock_area = Rect { width: chunks[0].width - width, ..chunks[0] };
+                    let ink = ui::draw_clock(f, clock_area, app, &face, app.display_time(), flash, app.flip_frame());
+                    ui::draw_big_temperature(f, chunks[0], ink, lines);
+                }
+                None => {
+                    ui::draw_clock(f, chunks[0], app, &face, app.display_time(), flash, app.flip_frame());
+                }
+            }
+        }
+        // 多时钟：时钟区域横向等分
+        _ => {
+            let count = app.config.clocks.len() as u32;
+            let areas = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Ratio(1, count); count as usize])
+                .split(chunks[0]);
+            for (spec, area) in app.config.clocks.iter().zip(areas.iter()) {
+                let face = ClockFace::from_spec(spec, &app.config, zone.clone());
+                ui::draw_clock(f, *area, app, &face, app.clock_time(spec.timezone), flash, app.flip_frame());
+            }
+        }
+    }
+    if !matches!(countdown, Some(CountdownPhase::Counting { .. })) {
+        ui::draw_work_tag(f, chunks[0], &app.config, app.now());
+    }
+    let ntp = app.ntp_state().zip(app.config.ntp.as_ref().map(|n| n.warn_ms));
+    ui::draw_time_sync(f, chunks[0], app.time_synced(), ntp.as_ref().map(|(state, warn_ms)| (state, *warn_ms)), &app.config.palette);
+    // 背景层：填充时钟区域中前景留下的空白
+    if app.background_active() {
+        let animate = app.paused_at.is_none();
+        let (effect, density, color) = (app.config.background_effect, app.config.background_density, app.config.background_effect_color);
+        let background = app.background.get_or_insert_with(Background::new);
+        background.render(f.buffer_mut(), chunks[0], effect, density, color, animate);
+    }
+    if let Some(CountdownPhase::Celebrating { elapsed_ms, label }) = &countdown {
+        ui::draw_celebration(f, chunks[0], *elapsed_ms, label);
+    }
+    // 时钟与侧栏之间的间距
+    let margin = app.config.layout.margin.min(chunks[1].height);
+    ui::draw_sidebar(f, Rect { y: chunks[1].y + margin, height: chunks[1].height - margin, ..chunks[1] }, app);
+    draw_overlays(f, size, app);
+}
+
+// 状态栏、横幅、提示等覆盖层（各页面共用）
+fn draw_overlays(f: &mut Frame, size: Rect, app: &mut App) {
+    if app.config.status_bar {
+        let profile = config::active_profile();
+        ui::draw_status_bar(f, size, app, profile.as_deref());
+    }
+    if let Some(alarm) = &app.alarm {
+        ui::draw_alarm_banner(f, size, alarm, &app.config.palette);
+    }
+    if app.is_muted() {
+        let remaining = app.unmute_at.map(|t| t.saturating_duration_since(Instant::now()));
+        ui::draw_mute_indicator(f, size, remaining);
+    }
+    if app.paused_at.is_some() {
+        ui::draw_paused_indicator(f, size);
+    }
+    if let Some((until, label)) = &app.timer {
+        ui::draw_timer_indicator(f, size, until.saturating_duration_since(Instant::now()), label);
+    }
+    if let Some(message) = app.message() {
+        ui::draw_message_banner(f, size, message, &app.config.palette);
+    }
+    if let Some(since) = app.offline_banner() {
+        ui::draw_offline_banner(f, size, since, app.config.offline_retry_secs, &app.config.palette);
+    }
+    if let Some(error) = app.api_error() {
+        ui::draw_status_line(f, size, &error, &app.config.palette);
+    }
+    if let Some((prompt, input)) = &app.input {
+        ui::draw_completions(f, size, &app.command_completions());
+        ui::draw_input(f, size, prompt.label(), input);
+    }
+    if let Some((message, until)) = &app.notice
+        && Instant::now() < *until
+    {
+        ui::draw_notice(f, size, message);
+    }
+    if app.idle_mode() == Some(IdleMode::Dim) {
+        idle::dim(f.buffer_mut());
+    }
+    // 最后统一替换为 ASCII 字符（各页面共用）
+    if app.config.ascii {
+        ascii::fallback(f.buffer_mut());
+    }
+}
+
+// 主界面的快捷键；返回 false 表示退出
+fn handle_key(app: &mut App, capture: &mut Capture, key: event::KeyEvent) -> bool {
+    use crossterm::event::KeyModifiers;
+    match key.code {
+        KeyCode::Char('s') if app.alarm.is_some() || app.rain_alert().is_some() => app.snooze(),
+        KeyCode::Enter if app.alarm.is_some() => app.dismiss_alarm(),
+        KeyCode::Enter if app.offline_banner().is_some() => app.dismiss_offline_banner(),
+        KeyCode::Char('q') => return false,
+        KeyCode::Esc if app.todo_filter.is_some() => app.set_todo_filter(None),
+        KeyCode::Esc => return false,
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+        KeyCode::Char('r') => {
+            // Reload todos and temp on demand
+            app.refresh_now();
+        }
+        KeyCode::Char('m') => app.toggle_mute(),
+        KeyCode::Char('t') => app.cycle_theme(),
+        KeyCode::Char('v') => app.toggle_radar_view(),
+        KeyCode::Char('e') => app.toggle_events_view(),
+        KeyCode::Char('h') => app.toggle_history_view(),
+        KeyCode::Char(' ') => app.toggle_pause(),
+        KeyCode::Char(':') => app.start_command_input(),
+        KeyCode::Char('p') => capture.screenshot_requested = true,
+        KeyCode::Char('j') | KeyCode::Down => app.select_next_todo(),
+        KeyCode::Char('k') | KeyCode::Up => app.select_prev_todo(),
+        KeyCode::Char('y') => app.copy_todos(),
+        KeyCode::Char('a') => app.start_todo_input(),
+        KeyCode::Char('/') => app.start_filter_input(),
+        KeyCode::Char('x') => app.complete_selected_todo(),
+        KeyCode::Delete => app.remove_selected_todo(),
+        _ => {}
+    }
+    true
+}
+
+fn run_tui(config: Config, record_path: Option<&std::path::Path>) -> io::Result<()> {
+    // IPC is optional: another instance may own the socket, or the path may be unwritable
+    let (ipc_tx, ipc_rx) = std::sync::mpsc::channel();
+    let ipc_ok = ipc::spawn_server(&config.ipc_socket, ipc_tx.clone()).is_ok();
+    let ipc_path = config.ipc_socket.clone();
+    if let Some(addr) = &config.web_listen
+        && let Err(e) = web::spawn_server(addr, ipc_tx, config.web_push_secret.clone())
+    {
+        eprintln!("web mirror disabled: cannot listen on {}: {}", addr, e);
+    }
+
+    let mut app = App::new(config);
+    app.start_workers();
+    app.tick();
+
+    // Setup terminal
+    let mut capture = Capture::new(record_path)?;
+    let mut terminal = setup_terminal(!app.config.low_bandwidth)?;
+    let mut systemd = systemd::Systemd::init();
+    systemd.notify_ready();
+    let mut radar_overlay = radar::Overlay::default();
+    let mut limiter = FrameLimiter::new();
+
+    'frames: loop {
+        let completed = limiter.draw(|| terminal.draw(|f| draw_frame(f, &mut app)))?;
+        capture.after_draw(completed.buffer, &app.config.screenshot_dir);
+        // 雷达图像直接写入终端（在 ratatui 绘制的页面之上）
+        if let Some(radar) = &app.config.radar
+            && radar_overlay.update(terminal.backend_mut(), radar.graphics, app.radar_area, app.radar_frame().as_ref())?
+        {
+            terminal.clear()?;
+        }
+
+        while let Ok(request) = ipc_rx.try_recv() {
+            app.handle_ipc(request);
+        }
+
+        if app.quit_requested {
+            break;
+        }
+
+        // Watchdog ping; SIGHUP reloads the config file
+        if systemd.tick() {
+            app.reload_config(parse_args());
+            systemd.notify_reloaded();
+        }
+
+        app.tick();
+
+        // 等到下一帧；期间的按键、终端尺寸变化和 IPC 请求立即处理，
+        // 处理后尽快重绘，但不早于帧率限制允许的时间（连续按键只合并重绘一次）
+        let mut deadline = limiter.deadline(app.frame_delay());
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            let poll = if app.low_power { LOW_POWER_IPC_POLL } else { IPC_POLL };
+            if event::poll(remaining.min(poll))? {
+                let event = event::read()?;
+                // 空闲变暗时第一次按键只用于唤醒
+                let waking = app.idle_mode().is_some();
+                if matches!(event, Event::Key(_) | Event::Mouse(_)) {
+                    app.note_input();
+                }
+                if let Event::Key(key) = event
+                    && !waking
+                    && key.kind == KeyEventKind::Press
+                    && !app.handle_input_key(key)
+                    && !handle_key(&mut app, &mut capture, key)
+                {
+                    break 'frames;
+                }
+                deadline = deadline.min(limiter.earliest());
+            }
+            while let Ok(request) = ipc_rx.try_recv() {
+                app.handle_ipc(request);
+                deadline = deadline.min(limiter.earliest());
+            }
+            // 低功耗时一帧可长达一分钟，等待期间也要发送看门狗心跳并响应 SIGHUP
+            if systemd.tick() {
+                app.reload_config(parse_args());
+                systemd.notify_reloaded();
+                deadline = deadline.min(limiter.earliest());
+            }
+        }
+    }
+
+    // Restore terminal
+    systemd.notify_stopping();
+    restore_terminal(&mut terminal)?;
+    if ipc_ok {
+        let _ = std::fs::remove_file(&ipc_path);
+    }
+    Ok(())
+}
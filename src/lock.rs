@@ -0,0 +1,31 @@
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::ipc;
+
+const TAKEOVER_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 单实例检查：IPC socket 可连接即说明已有实例在运行。
+// takeover 为 true 时通知旧实例退出，并等待其释放 socket。
+pub fn ensure_single_instance(socket: &Path, takeover: bool) -> Result<(), String> {
+    if UnixStream::connect(socket).is_err() {
+        return Ok(());
+    }
+    if !takeover {
+        return Err(format!(
+            "another termclock instance is running ({}); use --takeover to replace it",
+            socket.display()
+        ));
+    }
+    let mut client = ipc::Client::connect(socket).map_err(|e| e.to_string())?;
+    client.request("quit").map_err(|e| format!("takeover failed: {}", e))?;
+    let started = Instant::now();
+    while UnixStream::connect(socket).is_ok() {
+        if started.elapsed() >= TAKEOVER_TIMEOUT {
+            return Err("takeover failed: previous instance did not exit".to_string());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use chrono::{Local, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::chime::BeepPattern;
+
+// 每日时刻标记（祷告时间、服药、接孩子）：侧栏显示并高亮下一个，可在每个时刻报时。
+// 时刻来自可插拔的提供者：配置列表（固定时刻或日出日落 ± 分钟）、外部命令
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyMarker {
+    pub name: String,
+    pub time: NaiveTime,
+    // 到点时的提示音（None 时按 markers_chime 决定）
+    pub pattern: Option<BeepPattern>,
+}
+
+// 配置中的时刻："05:12"、"sunrise"、"sunset-15"、"noon+30"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MarkerTime {
+    Fixed(NaiveTime),
+    Sunrise(i64),
+    Noon(i64),
+    Sunset(i64),
+}
+
+impl MarkerTime {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Ok(time) = NaiveTime::parse_from_str(s, "%H:%M") {
+            return Some(MarkerTime::Fixed(time));
+        }
+        let split = s.find(['+', '-']).unwrap_or(s.len());
+        let (name, offset) = s.split_at(split);
+        let offset = match offset {
+            "" => 0,
+            _ => offset.parse().ok()?,
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "sunrise" => Some(MarkerTime::Sunrise(offset)),
+            "noon" => Some(MarkerTime::Noon(offset)),
+            "sunset" => Some(MarkerTime::Sunset(offset)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MarkerSpec {
+    pub name: String,
+    pub time: MarkerTime,
+    pub pattern: Option<BeepPattern>,
+}
+
+pub trait MarkerProvider {
+    fn markers(&self, date: NaiveDate) -> Result<Vec<DailyMarker>, String>;
+}
+
+// 配置文件中的列表；日出日落需要 latitude/longitude
+pub struct ListProvider<'a> {
+    pub specs: &'a [MarkerSpec],
+    pub location: Option<(f64, f64)>,
+}
+
+impl MarkerProvider for ListProvider<'_> {
+    fn markers(&self, date: NaiveDate) -> Result<Vec<DailyMarker>, String> {
+        let needs_sun = self.specs.iter().any(|s| !matches!(s.time, MarkerTime::Fixed(_)));
+        let sun = match (needs_sun, self.location) {
+            (false, _) => None,
+            (true, None) => return Err("markers: sunrise/sunset need latitude and longitude".to_string()),
+            (true, Some((lat, lon))) => crate::solar::sun_times(date, lat, lon),
+        };
+        let local = |t: chrono::DateTime<chrono::Utc>, offset: i64| (t + chrono::Duration::minutes(offset)).with_timezone(&Local).time();
+        Ok(self
+            .specs
+            .iter()
+            .filter_map(|spec| {
+                let time = match spec.time {
+                    MarkerTime::Fixed(time) => time,
+                    // 极昼、极夜当天跳过
+                    MarkerTime::Sunrise(offset) => local(sun.as_ref()?.sunrise, offset),
+                    MarkerTime::Noon(offset) => local(sun.as_ref()?.noon, offset),
+                    MarkerTime::Sunset(offset) => local(sun.as_ref()?.sunset, offset),
+                };
+                Some(DailyMarker { name: spec.name.clone(), time, pattern: spec.pattern.clone() })
+            })
+            .collect())
+    }
+}
+
+// 外部命令：{date} 替换为 YYYY-MM-DD，每行输出 "HH:MM 名称"
+pub struct CommandProvider<'a> {
+    pub command: &'a str,
+}
+
+impl MarkerProvider for CommandProvider<'_> {
+    fn markers(&self, date: NaiveDate) -> Result<Vec<DailyMarker>, String> {
+        let command = self.command.replace("{date}", &date.format("%Y-%m-%d").to_string());
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .output()
+            .map_err(|e| format!("markers_command: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("markers_command: exited with {}", output.status));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (time, name) = line.trim().split_once(char::is_whitespace)?;
+                let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+                Some(DailyMarker { name: name.trim().to_string(), time, pattern: None })
+            })
+            .collect())
+    }
+}
+
+// 合并所有提供者当天的时刻，按时间排序；出错的提供者跳过并返回错误信息
+pub fn load(providers: &[&dyn MarkerProvider], date: NaiveDate) -> (Vec<DailyMarker>, Vec<String>) {
+    let mut markers = Vec::new();
+    let mut errors = Vec::new();
+    for provider in providers {
+        match provider.markers(date) {
+            Ok(list) => markers.extend(list),
+            Err(e) => errors.push(e),
+        }
+    }
+    markers.sort_by_key(|m| m.time);
+    (markers, errors)
+}
+
+// 下一个时刻的序号（今天的都已过去时为 None）
+pub fn next_index(markers: &[DailyMarker], now: chrono::DateTime<Local>) -> Option<usize> {
+    let time = now.time();
+    markers.iter().position(|m| m.time > time)
+}
+
+// 某个时刻今天的本地时间
+pub fn today_at(marker: &DailyMarker, now: chrono::DateTime<Local>) -> Option<chrono::DateTime<Local>> {
+    Local.from_local_datetime(&now.date_naive().and_time(marker.time)).earliest()
+}
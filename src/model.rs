@@ -1,57 +1,104 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use ratatui::style::Color;
+use crate::schedule::{CronSchedule, WorkHours};
+use crate::chime::BeepPattern;
+use crate::alarm::{Alarm, ActiveAlarm};
+use crate::source::{CustomSource, SourceState};
+use crate::lenient;
 
-// API响应数据结构
-#[derive(Debug, Deserialize)]
-pub struct ApiResponse<T> {
-    pub code: i32,
+// API响应数据结构（字段与后端保持一致，部分字段暂未使用）
+// 字段缺失或类型不同时按缺省值处理，兼容不同版本的后端
+#[allow(dead_code)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ApiResponse<T: Default> {
+    #[serde(deserialize_with = "lenient::i64")]
+    pub code: i64,
+    #[serde(deserialize_with = "lenient::string")]
     pub msg: String,
+    #[serde(deserialize_with = "lenient::or_default", bound(deserialize = "T: Deserialize<'de>"))]
     pub data: T,
 }
 
-#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct TemperatureData {
-    pub page: i32,
-    pub page_size: i32,
+    #[serde(deserialize_with = "lenient::i64")]
+    pub page: i64,
+    #[serde(deserialize_with = "lenient::i64")]
+    pub page_size: i64,
     pub rows: Vec<TemperatureRow>,
-    pub total: i32,
+    #[serde(deserialize_with = "lenient::i64")]
+    pub total: i64,
 }
 
-#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct TemperatureRow {
+    #[serde(deserialize_with = "lenient::string")]
     pub created_at: String,
+    #[serde(deserialize_with = "lenient::string")]
     pub device_code: String,
-    pub id: i32,
+    #[serde(deserialize_with = "lenient::string")]
+    pub id: String,
+    #[serde(deserialize_with = "lenient::bool")]
     pub valid: bool,
     pub values: TemperatureValues,
 }
 
-#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct TemperatureValues {
-    pub hum: f64,
-    pub temp: f64,
+    #[serde(deserialize_with = "lenient::f64_opt")]
+    pub hum: Option<f64>,
+    #[serde(deserialize_with = "lenient::f64_opt")]
+    pub temp: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct TodoData {
-    pub page: i32,
-    pub page_size: i32,
+    #[serde(deserialize_with = "lenient::i64")]
+    pub page: i64,
+    #[serde(deserialize_with = "lenient::i64")]
+    pub page_size: i64,
     pub rows: Vec<TodoRow>,
-    pub total: i32,
+    #[serde(deserialize_with = "lenient::i64")]
+    pub total: i64,
 }
 
-#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct TodoRow {
+    #[serde(deserialize_with = "lenient::bool")]
     pub completed: bool,
     pub completed_time: Option<String>,
+    #[serde(deserialize_with = "lenient::string")]
     pub create_time: String,
+    #[serde(deserialize_with = "lenient::string")]
     pub deadline: String,
-    pub id: i32,
+    #[serde(deserialize_with = "lenient::string")]
+    pub id: String,
+    #[serde(deserialize_with = "lenient::string")]
     pub ipaddr: String,
+    #[serde(deserialize_with = "lenient::string")]
     pub task: String,
+    #[serde(deserialize_with = "lenient::string")]
     pub update_time: String,
 }
 
+// 待办进度：今日完成数 / 剩余数
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TodoSummary {
+    pub done_today: usize,
+    pub remaining: usize,
+}
+
 // 配置文件结构
 #[derive(Debug, Clone)]
 pub struct FileConfig {
@@ -71,6 +118,129 @@ pub struct FileConfig {
     pub date_color: Option<String>,
     pub todos_color: Option<String>,
     pub chime_enabled: Option<bool>,
+    pub chimes: Option<Vec<ChimeRule>>,
+    pub chime_pattern: Option<BeepPattern>,
+    pub alarm_pattern: Option<BeepPattern>,
+    pub chime_visual: Option<bool>,
+    pub chime_audible: Option<bool>,
+    pub mute_minutes: Option<u64>,
+    pub ipc_socket: Option<String>,
+    pub alarms: Option<Vec<Alarm>>,
+    pub snooze_minutes: Option<u64>,
+    // Google 日历
+    pub gcal_client_id: Option<String>,
+    pub gcal_client_secret: Option<String>,
+    pub gcal_calendar_id: Option<String>,
+    pub gcal_token_file: Option<String>,
+    pub gcal_remind_minutes: Option<u64>,
+    pub gcal_pattern: Option<BeepPattern>,
+    // 工作时间
+    pub work_hours: Option<String>,
+    pub work_days: Option<String>,
+    pub work_timezone: Option<String>,
+    // 历史记录
+    pub history_enabled: Option<bool>,
+    pub history_file: Option<String>,
+    pub history_interval: Option<u64>,
+    // 网页镜像
+    pub web_listen: Option<String>,
+    pub web_push_secret: Option<String>,
+    pub screenshot_dir: Option<String>,
+    // 自定义 REST 数据源
+    pub custom_sources: Option<Vec<CustomSource>>,
+    // 本地 SQLite 待办存储
+    pub todo_db: Option<String>,
+    // 待办优先级
+    pub todo_priority: Option<bool>,
+    pub todo_priority_colors: Option<Vec<String>>,
+    pub todo_group: Option<bool>,
+    pub todo_summary: Option<bool>,
+    pub todo_rotate_secs: Option<u64>,
+    pub todo_sort: Option<String>,
+    pub todo_sources: Option<Vec<crate::todo_store::TodoSource>>,
+    // 离线模式
+    pub offline_after_mins: Option<u64>,
+    pub offline_retry_secs: Option<u64>,
+    pub stale_primary_secs: Option<u64>,
+    pub temperature_providers: Option<Vec<crate::provider::TemperatureProvider>>,
+    pub max_concurrent_requests: Option<usize>,
+    pub api_schema: Option<crate::api_schema::ApiSchema>,
+    pub low_bandwidth: Option<bool>,
+    pub low_power: Option<crate::power::LowPower>,
+    pub ascii: Option<bool>,
+    // 主题与状态文件
+    pub themes: Option<Vec<crate::theme::Theme>>,
+    pub theme: Option<String>,
+    pub state_file: Option<String>,
+    pub status_bar: Option<bool>,
+    pub time_colors: Option<TimeColors>,
+    pub time_effect: Option<String>,
+    pub time_effect_color: Option<String>,
+    pub time_font: Option<String>,
+    pub show_subseconds: Option<String>,
+    pub clock_style: Option<String>,
+    pub clock_frame: Option<crate::frame::ClockFrame>,
+    pub clocks: Option<Vec<ClockSpec>>,
+    pub auto_rotate: Option<AutoRotate>,
+    pub idle_dim: Option<crate::idle::IdleDim>,
+    pub big_temperature: Option<BigTemperature>,
+    pub secondary_calendar: Option<String>,
+    pub widgets: Option<std::collections::HashMap<String, WidgetStyle>>,
+    pub layout: Option<LayoutSpacing>,
+    pub background_effect: Option<String>,
+    pub background_density: Option<f64>,
+    pub background_color: Option<String>,
+    pub background_effect_color: Option<String>,
+    pub countdowns: Option<Vec<String>>,
+    pub countdown_celebrate_secs: Option<u64>,
+    pub markers: Option<Vec<crate::markers::MarkerSpec>>,
+    pub markers_command: Option<String>,
+    pub markers_chime: Option<bool>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub sun_bar: Option<bool>,
+    pub upcoming: Option<Vec<crate::upcoming::UpcomingFeed>>,
+    pub transit: Option<crate::transit::TransitConfig>,
+    pub ntp: Option<crate::ntp::NtpConfig>,
+    pub time_sync: Option<bool>,
+    pub compare_indoor_outdoor: Option<bool>,
+    pub thermometer_style: Option<String>,
+    pub temp_min_max: Option<bool>,
+    pub show_comfort: Option<bool>,
+    pub comfort_dry_below: Option<f64>,
+    pub comfort_humid_above: Option<f64>,
+    pub comfort_dew_point_max: Option<f64>,
+    pub humidity_gauge: Option<HumidityGauge>,
+    pub rain_alert: Option<bool>,
+    pub rain_alert_minutes: Option<u64>,
+    pub rain_threshold_mm: Option<f64>,
+    pub rain_alert_chime: Option<bool>,
+    pub forecast_url: Option<String>,
+    pub weather_accents: Option<crate::theme::WeatherAccents>,
+    pub radar: Option<crate::radar::RadarConfig>,
+    pub ble_sensors: Option<Vec<BleSensor>>,
+    pub serial: Option<Vec<crate::serial::SerialConfig>>,
+    pub dht22: Option<crate::dht::Dht22Config>,
+    pub scripts: Option<Vec<crate::script::ScriptSource>>,
+    pub command_panels: Option<Vec<crate::panel::CommandPanel>>,
+    pub mqtt: Option<crate::mqtt::MqttConfig>,
+    pub on_hour_command: Option<String>,
+    pub on_alarm_command: Option<String>,
+}
+
+// 报时规则：cron 表达式 + 提示音样式（缺省为整点报时音）+ 可选的动作
+#[derive(Debug, Clone)]
+pub struct ChimeRule {
+    pub cron: CronSchedule,
+    pub pattern: Option<BeepPattern>,
+    // shown as a notice and recorded in the event log
+    pub message: Option<String>,
+    // run through sh -c, like on_hour_command
+    pub command: Option<String>,
+    // false for action-only rules (no beep or flash)
+    pub sound: bool,
+    // overrides chime_visual for this rule
+    pub visual: Option<bool>,
 }
 
 // 应用配置结构
@@ -79,30 +249,627 @@ pub struct Config {
     // scaling factors
     pub time_scale_x: u16,
     pub time_scale_y: u16,
+    #[allow(dead_code)]
     pub date_scale_x: u16,
     // colors
     pub time_color: Color,
     pub date_color: Color,
     pub todos_color: Color,
+    // per-field clock colors (fall back to time_color)
+    pub time_colors: TimeColors,
+    // big digit shadow/outline
+    pub time_effect: TimeEffect,
+    pub time_effect_color: Color,
+    pub time_font: TimeFont,
+    // fractional seconds under the big digits
+    pub show_subseconds: Subseconds,
+    // split-flap animation on digit changes
+    pub clock_style: ClockStyle,
+    // decorative border around the big digits
+    pub clock_frame: Option<crate::frame::ClockFrame>,
+    // several clocks side by side, each with its own timezone/scale/colors (empty = single clock)
+    pub clocks: Vec<ClockSpec>,
+    // cycle through view pages on unattended displays
+    pub auto_rotate: Option<AutoRotate>,
+    // dim the screen (or show only a small clock) after a period without input
+    pub idle_dim: Option<crate::idle::IdleDim>,
+    // temperature in big digits to the right of the clock
+    pub big_temperature: Option<BigTemperature>,
+    // date in another calendar under the Gregorian date
+    pub secondary_calendar: Option<crate::calendar::SecondaryCalendar>,
+    // per-widget titles and borders, keyed by widget name (or custom source/script/feed name)
+    pub widgets: std::collections::HashMap<String, WidgetStyle>,
+    // padding inside sidebar widgets and blank rows between them
+    pub layout: LayoutSpacing,
+    // animated background behind the clock (off in low-bandwidth mode and over SSH)
+    pub background_effect: crate::background::BackgroundEffect,
+    pub background_density: f64,
+    pub background_effect_color: Option<Color>,
+    // fill colour for the whole screen (None keeps the terminal's own background)
+    pub background_color: Option<Color>,
+    // special moments: big countdown for the final minute, then fireworks
+    pub countdowns: Vec<crate::countdown::Countdown>,
+    pub countdown_celebrate_secs: u64,
+    // named daily times (static list or command provider), optionally chiming
+    pub markers: Vec<crate::markers::MarkerSpec>,
+    pub markers_command: Option<String>,
+    pub markers_chime: bool,
+    // latitude/longitude for sunrise/sunset
+    pub location: Option<(f64, f64)>,
+    // daylight progress bar (needs location)
+    pub sun_bar: bool,
+    // "next occurrence" feeds (ICS or JSON)
+    pub upcoming: Vec<crate::upcoming::UpcomingFeed>,
+    // next departures for one stop
+    pub transit: Option<crate::transit::TransitConfig>,
+    // offset between system time and an NTP server
+    pub ntp: Option<crate::ntp::NtpConfig>,
+    // OS time synchronization indicator (timedatectl / chrony)
+    pub time_sync: bool,
+    // indoor (sensor API) and outdoor (wttr.in) thermometers with delta
+    pub compare_indoor_outdoor: bool,
+    // bar / gauge / dial / number-only rendering of the thermometer
+    pub thermometer_style: ThermometerStyle,
+    // today's min/max markers on the thermometer
+    pub temp_min_max: bool,
+    // humidity comfort band (sensor API humidity)
+    pub show_comfort: bool,
+    pub comfort: crate::comfort::ComfortThresholds,
+    // humidity bar with the comfort zone shaded and today's min/max
+    pub humidity_gauge: Option<HumidityGauge>,
+    // precipitation alert from the forecast provider (needs location)
+    pub rain_alert: bool,
+    pub rain_alert_minutes: u64,
+    pub rain_threshold_mm: f64,
+    pub rain_alert_chime: bool,
+    pub forecast_url: String,
+    // tint the thermometer and an accent line by the current weather (None = off)
+    pub weather_accents: Option<crate::theme::WeatherAccents>,
+    // weather radar page (needs location)
+    pub radar: Option<crate::radar::RadarConfig>,
+    // Bluetooth thermometers (needs the ble feature)
+    pub ble_sensors: Vec<BleSensor>,
+    // 串口传感器
+    pub serial: Vec<crate::serial::SerialConfig>,
+    // 树莓派 GPIO 上的 DHT22（需 rpi feature）
+    pub dht22: Option<crate::dht::Dht22Config>,
+    // 脚本数据源（输出 JSON 的可执行文件）
+    pub scripts: Vec<crate::script::ScriptSource>,
+    // 命令输出面板
+    pub command_panels: Vec<crate::panel::CommandPanel>,
+    // MQTT 事件发布
+    pub mqtt: Option<crate::mqtt::MqttConfig>,
+    // 整点和闹钟响铃时在后台运行的命令（sh -c）
+    pub on_hour_command: Option<String>,
+    pub on_alarm_command: Option<String>,
     // chime
     pub chime_enabled: bool,
+    pub chimes: Vec<ChimeRule>,
+    pub chime_pattern: Option<BeepPattern>,
+    pub alarm_pattern: BeepPattern,
+    pub chime_visual: bool,
+    pub chime_audible: bool,
+    // do-not-disturb: auto-unmute after N minutes when muted via key
+    pub mute_minutes: Option<u64>,
+    pub ipc_socket: std::path::PathBuf,
+    // alarms
+    pub alarms: Vec<Alarm>,
+    pub snooze_minutes: u64,
+    // google calendar meeting reminders
+    pub gcal: Option<GcalConfig>,
+    // work-hours indicator
+    pub work_hours: Option<WorkHours>,
+    // history log (always on in daemon mode)
+    pub history_enabled: bool,
+    pub history_file: std::path::PathBuf,
+    pub history_interval: u64,
+    // web mirror listen address, e.g. "0.0.0.0:8080"
+    pub web_listen: Option<String>,
+    // 设置后接受 POST /push/temperature、/push/todos、/push/message（请求头 X-TermClock-Secret）
+    pub web_push_secret: Option<String>,
+    // screenshots (`p` key)
+    pub screenshot_dir: std::path::PathBuf,
+    // generic REST sources rendered as value / list widgets
+    pub custom_sources: Vec<CustomSource>,
+    // local SQLite todo store (takes precedence over API and file)
+    pub todo_db: Option<std::path::PathBuf>,
+    pub todos_file: Option<std::path::PathBuf>,
+    // todo priority prefixes (!1 / (A)): sort and color by band
+    pub todo_priority: bool,
+    pub todo_priority_colors: [Color; 3],
+    // group todos under Overdue / Today / Tomorrow / Later headers
+    pub todo_group: bool,
+    // "✓ N done today / M remaining" line above the todo list
+    pub todo_summary: bool,
+    // page through todos that don't fit every N seconds (keyboard-less displays)
+    pub todo_rotate_secs: Option<u64>,
+    // todo list order: deadline / created / priority / none
+    pub todo_sort: crate::todo::TodoSort,
+    // merged todo sources (api / file / db / taskwarrior) replacing the fallback chain
+    pub todo_sources: Vec<crate::todo_store::TodoSource>,
+    // offline banner after N minutes of failed requests; retry cadence while offline
+    pub offline_after_mins: u64,
+    pub offline_retry_secs: u64,
+    // 传感器 API 失败时，不超过这么久的上次结果优先于 wttr.in（0 为不使用）
+    pub stale_primary_secs: u64,
+    // 温度提供者链，按顺序尝试
+    pub temperature_providers: Vec<crate::provider::TemperatureProvider>,
+    // 同时进行的 HTTP 请求上限（所有数据源共用）
+    pub max_concurrent_requests: usize,
+    // 传感器 / 待办 API 的接口路径、请求体模板和响应字段位置
+    pub api_schema: crate::api_schema::ApiSchema,
+    // slow links: HH:MM clock, steady chime flash, no mouse capture
+    pub low_bandwidth: bool,
+    // 省电模式：auto 时电池供电自动开启
+    pub low_power: crate::power::LowPower,
+    // 方块字符和制表符替换为 ASCII（未配置时按终端和 locale 自动检测）
+    pub ascii: bool,
+    pub todo_task_max_chars: Option<usize>,
+    // colour themes cycled with `t` (built-in palette when none configured)
+    pub themes: Vec<crate::theme::Theme>,
+    // 启动时使用的主题（优先于状态文件中 t 键上次选择的主题），可以是内置主题名
+    pub theme: Option<String>,
+    // 当前主题的状态颜色（由 App::apply_theme 设置）
+    pub palette: crate::theme::Palette,
+    // runtime state persisted across restarts (selected theme)
+    pub state_file: std::path::PathBuf,
+    // bottom hint/status bar (key hints, profile, mute, data age)
+    pub status_bar: bool,
     // api config
     pub api_base_url: Option<String>,
     pub device_code: String,
     // refresh intervals
     pub temp_refresh_interval: u64,
     // todo config
+    #[allow(dead_code)]
     pub todo_ip_filter: Option<String>,
     pub todo_limit: Option<usize>,
     pub main_window_percent: u16,
 }
 
+// 时钟各部分的颜色：time_colors: {hours: cyan, minutes: white, seconds: darkgray, separators: gray}
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimeColors {
+    pub hours: Option<Color>,
+    pub minutes: Option<Color>,
+    pub seconds: Option<Color>,
+    pub separators: Option<Color>,
+}
+
+impl TimeColors {
+    pub fn is_set(&self) -> bool {
+        *self != Self::default()
+    }
+}
+
+// 组件的标题、边框、对齐和背景色：widgets: {todos: {title: TODOS, border: true, align: left}, temperature: {title: GREENHOUSE, background: blue}}
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WidgetStyle {
+    pub title: Option<String>,
+    pub border: bool,
+    // todos / temperature / date 在所在区域中的位置，默认居中
+    pub align: Option<ratatui::layout::Alignment>,
+    pub background: Option<Color>,
+}
+
+// 时钟右侧的大字温度，远处也能看清：big_temperature: true 或 {scale_x: 2, scale_y: 2, color: orange}
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BigTemperature {
+    // 未设置时与时钟大字的缩放相同
+    pub scale_x: Option<u16>,
+    pub scale_y: Option<u16>,
+    // 默认使用 time_color
+    pub color: Option<Color>,
+}
+
+// 湿度条：humidity_gauge: true 或 {bar_color: cyan, zone_color: green, scale_color: gray}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HumidityGauge {
+    // 当前湿度的条
+    pub bar: Color,
+    // 舒适区间（comfort_dry_below 到 comfort_humid_above）的刻度和底纹
+    pub zone: Color,
+    // 刻度和标签
+    pub scale: Color,
+}
+
+impl Default for HumidityGauge {
+    fn default() -> Self {
+        Self { bar: Color::Cyan, zone: Color::Green, scale: Color::Gray }
+    }
+}
+
+// 布局间距：layout: {padding: [0, 2], margin: 1}
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LayoutSpacing {
+    // 侧栏组件的内边距（上下行数, 左右列数），在标题和边框之内
+    pub padding: (u16, u16),
+    // 侧栏组件之间、时钟与侧栏之间的空行
+    pub margin: u16,
+}
+
+// clocks 中的一个时钟：未设置的项沿用全局配置
+// clocks: [{label: 北京}, {label: UTC, timezone: UTC, time_color: cyan, time_scale_x: 1}]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClockSpec {
+    // 显示在大字上方
+    pub label: Option<String>,
+    // None 为本地时间（或 :tz 设置的时区）
+    pub timezone: Option<chrono_tz::Tz>,
+    pub time_scale_x: Option<u16>,
+    pub time_scale_y: Option<u16>,
+    pub time_color: Option<Color>,
+    pub date_color: Option<Color>,
+    pub time_font: Option<TimeFont>,
+}
+
+// 绘制一个时钟所需的设置（draw_clock 不直接读取全局配置）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClockFace {
+    pub label: Option<String>,
+    // 显示在日期后的时区名
+    pub zone: Option<String>,
+    pub time_scale_x: u16,
+    pub time_scale_y: u16,
+    pub time_color: Color,
+    pub date_color: Color,
+    pub time_colors: TimeColors,
+    pub time_effect: TimeEffect,
+    pub time_effect_color: Color,
+    pub time_font: TimeFont,
+    pub show_subseconds: Subseconds,
+    pub secondary_calendar: Option<crate::calendar::SecondaryCalendar>,
+}
+
+impl ClockFace {
+    // 全局配置对应的时钟（未配置 clocks 时只显示这一个）
+    pub fn primary(config: &Config, zone: Option<String>) -> Self {
+        Self {
+            label: None,
+            zone,
+            time_scale_x: config.time_scale_x,
+            time_scale_y: config.time_scale_y,
+            time_color: config.time_color,
+            date_color: config.date_color,
+            time_colors: config.time_colors,
+            time_effect: config.time_effect,
+            time_effect_color: config.time_effect_color,
+            time_font: config.time_font,
+            show_subseconds: config.show_subseconds,
+            secondary_calendar: config.secondary_calendar,
+        }
+    }
+
+    // 单独设置了 time_color 的时钟不再使用全局的 time_colors；zone 为未设置 timezone 时显示的时区名
+    pub fn from_spec(spec: &ClockSpec, config: &Config, zone: Option<String>) -> Self {
+        let primary = Self::primary(config, spec.timezone.map(|tz| tz.name().to_string()).or(zone));
+        Self {
+            label: spec.label.clone(),
+            time_scale_x: spec.time_scale_x.unwrap_or(primary.time_scale_x),
+            time_scale_y: spec.time_scale_y.unwrap_or(primary.time_scale_y),
+            time_color: spec.time_color.unwrap_or(primary.time_color),
+            date_color: spec.date_color.unwrap_or(primary.date_color),
+            time_colors: if spec.time_color.is_some() { TimeColors::default() } else { primary.time_colors },
+            time_font: spec.time_font.unwrap_or(primary.time_font),
+            ..primary
+        }
+    }
+}
+
+// 大字效果：shadow 向右下投一格阴影，outline 在笔画外描一圈
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimeEffect {
+    #[default]
+    None,
+    Shadow,
+    Outline,
+}
+
+impl TimeEffect {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Some(TimeEffect::None),
+            "shadow" => Some(TimeEffect::Shadow),
+            "outline" => Some(TimeEffect::Outline),
+            _ => None,
+        }
+    }
+}
+
+// 大字字体：default 实心方块，dots 点阵（·●），blocks 半格方块（▀▄，纵向密度加倍），
+// smooth 平滑放大后用四分格方块（▖▗▘▝ 等）绘制，横竖分辨率都加倍；
+// braille 用盲文点阵（每个单元格 2×4 个点）绘制，分辨率横向加倍、纵向四倍
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimeFont {
+    #[default]
+    Default,
+    Dots,
+    Blocks,
+    Smooth,
+    Braille,
+}
+
+impl TimeFont {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "default" => Some(TimeFont::Default),
+            "dots" => Some(TimeFont::Dots),
+            "blocks" => Some(TimeFont::Blocks),
+            "smooth" => Some(TimeFont::Smooth),
+            "braille" => Some(TimeFont::Braille),
+            _ => None,
+        }
+    }
+}
+
+// 温度计样式：bar 刻度加温度条（默认），gauge 使用 Gauge 进度条，
+// dial 用盲文点阵画半圆表盘和指针，number-only 只显示大字温度
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ThermometerStyle {
+    #[default]
+    Bar,
+    Gauge,
+    Dial,
+    Number,
+}
+
+impl ThermometerStyle {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "bar" => Some(ThermometerStyle::Bar),
+            "gauge" => Some(ThermometerStyle::Gauge),
+            "dial" => Some(ThermometerStyle::Dial),
+            "number-only" | "number" => Some(ThermometerStyle::Number),
+            _ => None,
+        }
+    }
+}
+
+// 大字下方的小数秒：tenths 一位、hundredths 两位；开启时帧率随之提高
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Subseconds {
+    #[default]
+    Off,
+    Tenths,
+    Hundredths,
+}
+
+impl Subseconds {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(Subseconds::Off),
+            "tenths" => Some(Subseconds::Tenths),
+            "hundredths" => Some(Subseconds::Hundredths),
+            _ => None,
+        }
+    }
+
+    // 每次读数变化的间隔
+    pub fn period(&self) -> Option<std::time::Duration> {
+        match self {
+            Subseconds::Off => None,
+            Subseconds::Tenths => Some(std::time::Duration::from_millis(100)),
+            Subseconds::Hundredths => Some(std::time::Duration::from_millis(10)),
+        }
+    }
+
+    // ".4" / ".47"
+    pub fn format(&self, nanos: u32) -> Option<String> {
+        match self {
+            Subseconds::Off => None,
+            Subseconds::Tenths => Some(format!(".{}", nanos.min(999_999_999) / 100_000_000)),
+            Subseconds::Hundredths => Some(format!(".{:02}", nanos.min(999_999_999) / 10_000_000)),
+        }
+    }
+}
+
+// 时钟样式：plain 直接跳变，flip 数字变化时播放 3 帧翻页动画
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ClockStyle {
+    #[default]
+    Plain,
+    Flip,
+}
+
+impl ClockStyle {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "plain" => Some(ClockStyle::Plain),
+            "flip" => Some(ClockStyle::Flip),
+            _ => None,
+        }
+    }
+}
+
+// 蓝牙温湿度计：MAC 地址和显示标签
+#[derive(Debug, Clone, PartialEq)]
+pub struct BleSensor {
+    pub address: String,
+    pub label: String,
+}
+
+// 主界面页面（v 键切换）
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum View {
+    #[default]
+    Clock,
+    Radar,
+    Events,
+    History,
+}
+
+impl View {
+    // auto_rotate 中的页面名称；weather 为雷达页面
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "clock" => Some(View::Clock),
+            "radar" | "weather" => Some(View::Radar),
+            "events" => Some(View::Events),
+            "history" => Some(View::History),
+            _ => None,
+        }
+    }
+}
+
+// 无人值守时自动轮换页面：auto_rotate: {pages: [clock, weather, events], interval: 15, grace: 60}
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoRotate {
+    pub pages: Vec<View>,
+    pub interval_secs: u64,
+    // 按键后暂停轮换的时间
+    pub grace_secs: u64,
+}
+
+// Google 日历配置（client_id/secret 均配置时启用）
+#[derive(Debug, Clone)]
+pub struct GcalConfig {
+    pub client: crate::auth::OAuthClient,
+    pub calendar_id: String,
+    pub token_path: std::path::PathBuf,
+    pub refresh_secs: u64,
+    pub remind_minutes: u64,
+    pub pattern: BeepPattern,
+}
+
+// 下一场会议
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Meeting {
+    pub id: String,
+    pub title: String,
+    pub start: chrono::DateTime<chrono::Local>,
+}
+
+// 底部输入框的用途
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prompt {
+    AddTodo,
+    Filter,
+    // 命令面板
+    Command,
+}
+
+impl Prompt {
+    pub fn label(self) -> &'static str {
+        match self {
+            Prompt::AddTodo => "add todo: ",
+            Prompt::Filter => "filter: ",
+            Prompt::Command => ":",
+        }
+    }
+}
+
 // 应用状态结构
 pub struct App {
     pub last_temp_fetch: Option<std::time::Instant>,
     pub cached_temp: Option<String>,
+    // 室外温度（compare_indoor_outdoor 时获取）
+    pub cached_outdoor_temp: Option<String>,
+    // 传感器湿度（%）
+    pub cached_humidity: Option<f64>,
+    // 当天最低、最高温度
+    pub temp_range: Option<crate::temperature::DailyRange>,
+    // 当天最低、最高湿度（开启 humidity_gauge 时记录）
+    pub humidity_range: Option<crate::temperature::DailyRange>,
     pub todos: Vec<String>,
     pub config: Config,
-    pub last_chime_hour: Option<u32>,
+    pub last_schedule_minute: Option<i64>,
+    pub alarm: Option<ActiveAlarm>,
+    pub gcal_status: Option<std::sync::Arc<std::sync::Mutex<crate::gcal::GcalStatus>>>,
+    pub reminded_meeting: Option<String>,
+    pub flash_until: Option<std::time::Instant>,
+    pub muted: bool,
+    pub unmute_at: Option<std::time::Instant>,
     pub last_todos_refresh: Option<std::time::Instant>,
+    pub last_history_log: Option<std::time::Instant>,
+    // 发声计数与最近一次提示音（attach 前端据此重放）
+    pub sound_seq: u64,
+    pub last_sound: Option<BeepPattern>,
+    pub play_sounds: bool,
+    // 收到 IPC quit 后退出主循环
+    pub quit_requested: bool,
+    // 当前选中的待办（j / k 移动，y 复制）
+    pub selected_todo: Option<usize>,
+    // 底部短暂提示（如"已复制"）
+    pub notice: Option<(String, std::time::Instant)>,
+    // 保持剪贴板实例存活（X11 下实例释放后复制内容会丢失）
+    pub clipboard: Option<arboard::Clipboard>,
+    // 自定义数据源（名称，后台线程共享状态）
+    pub sources: Vec<(String, std::sync::Arc<std::sync::Mutex<SourceState>>)>,
+    // 脚本数据源（名称，后台线程共享状态）
+    pub scripts: Vec<(String, std::sync::Arc<std::sync::Mutex<crate::script::ScriptState>>)>,
+    // 命令输出面板（标题，后台线程共享状态）
+    pub panels: Vec<(String, std::sync::Arc<std::sync::Mutex<crate::panel::PanelState>>)>,
+    // “接下来”组件的后台线程状态
+    // （名称，显示条数，状态）
+    pub upcoming: Vec<(String, usize, std::sync::Arc<std::sync::Mutex<crate::upcoming::FeedState>>)>,
+    // 公交发车后台线程状态
+    pub transit: Option<std::sync::Arc<std::sync::Mutex<crate::transit::TransitState>>>,
+    // NTP 偏差后台线程状态
+    pub ntp: Option<std::sync::Arc<std::sync::Mutex<crate::ntp::NtpState>>>,
+    // 最近的报时、闹钟、提醒和错误（事件页面）
+    pub events: crate::events::EventLog,
+    // 系统时间同步状态后台线程（None 表示无法获取）
+    pub time_sync: Option<std::sync::Arc<std::sync::Mutex<Option<bool>>>>,
+    // 降水预报后台线程状态
+    pub forecast: Option<std::sync::Arc<std::sync::Mutex<crate::weather::ForecastState>>>,
+    // 当前天气状况（天气强调色）
+    pub condition: Option<std::sync::Arc<std::sync::Mutex<Option<crate::weather::Condition>>>>,
+    // 上一轮是否有降水提醒（从无到有时提示一次）
+    pub rain_alert_active: bool,
+    pub rain_snoozed_until: Option<chrono::DateTime<chrono::Local>>,
+    // 雷达后台线程状态
+    pub radar: Option<std::sync::Arc<std::sync::Mutex<crate::radar::RadarState>>>,
+    // 当前页面，以及雷达图像在上一帧中的位置
+    pub view: View,
+    // 最近一次按键或鼠标操作（启动后还没有操作时为 None）、最近一次自动切换页面的时间
+    pub last_input: Option<std::time::Instant>,
+    pub started_at: std::time::Instant,
+    pub rotated_at: std::time::Instant,
+    pub radar_area: Option<ratatui::layout::Rect>,
+    // 历史页面的热力图（后台读取，None 为读取中）及上次开始读取的时间
+    pub heatmap: Option<(std::time::Instant, std::sync::Arc<std::sync::Mutex<Option<crate::history::Heatmap>>>)>,
+    // 本地传感器读数（蓝牙等）
+    pub local_sensors: crate::sensors::SharedReadings,
+    // 网页推送（POST /push/...）：温度读数、待办列表、消息（文本，消失时间）
+    pub pushed_reading: Option<crate::sensors::SensorReading>,
+    pub pushed_todos: Option<Vec<String>>,
+    pub message: Option<(String, Option<std::time::Instant>)>,
+    // MQTT 事件发布（配置了 mqtt 时）
+    pub mqtt: Option<crate::mqtt::Mqtt>,
+    // 最近一次 API 请求或解析错误（显示在状态栏）
+    pub temp_error: Option<String>,
+    pub todos_error: Option<String>,
+    // 输入框（用途，内容）
+    pub input: Option<(Prompt, String)>,
+    // 待办过滤关键字（/ 输入，Esc 清除）
+    pub todo_filter: Option<String>,
+    // 待办文件修改时间（外部修改后立即重新加载）
+    pub todos_file_mtime: Option<std::time::SystemTime>,
+    pub todo_summary: Option<TodoSummary>,
+    // 待办内容 -> todo_sources 序号
+    pub todo_origins: std::collections::HashMap<String, usize>,
+    // 网络请求连续失败的起始时间；横幅被 Enter 关闭
+    pub offline_since: Option<chrono::DateTime<chrono::Local>>,
+    pub offline_dismissed: bool,
+    // 当前主题（None 为配置文件中的颜色）及配置文件中的颜色（时间，日期，待办，优先级）
+    pub theme: Option<String>,
+    pub base_colors: (Color, Color, Color, [Color; 3]),
+    // 空格暂停：界面停在此刻的时间和数据
+    pub paused_at: Option<chrono::DateTime<chrono::Local>>,
+    // 倒计时（到期时间，标签）
+    pub timer: Option<(std::time::Instant, String)>,
+    // 时钟显示的时区（None 为本地时间）
+    pub display_tz: Option<chrono_tz::Tz>,
+    // attach 前端：命令面板中作用于守护进程的命令（IPC 命令行）暂存于此，由主循环发送
+    pub forward_commands: Option<Vec<String>>,
+    // 最近一次成功获取温度或待办的时间（状态栏显示数据新旧）
+    pub data_updated: Option<chrono::DateTime<chrono::Local>>,
+    // 今天的时刻标记及其日期（跨天或重载配置后重新计算）
+    pub markers: Vec<crate::markers::DailyMarker>,
+    pub markers_date: Option<chrono::NaiveDate>,
+    // 背景动画状态（首次绘制时创建）
+    pub background: Option<crate::background::Background>,
+    // 当前是否处于省电模式，及上次检查电源状态的时间
+    pub low_power: bool,
+    pub last_power_check: Option<std::time::Instant>,
 }
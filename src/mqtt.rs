@@ -0,0 +1,178 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS};
+use serde_json::{json, Value};
+
+// MQTT：报时、闹钟和按键操作以 JSON 发布到 <topic>/events，供家庭自动化系统响应（如整点闪灯）；
+// 开启 home_assistant 时发布 Home Assistant 自动发现配置，时钟作为一个设备出现，
+// 带温度 / 湿度传感器、静音开关和消息文本框
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    // 主题前缀
+    pub topic: String,
+    pub client_id: String,
+    pub home_assistant: bool,
+    pub discovery_prefix: String,
+    // Home Assistant 中的设备名
+    pub name: String,
+}
+
+pub const DEFAULT_PORT: u16 = 1883;
+pub const DEFAULT_TOPIC: &str = "termclock";
+pub const DEFAULT_DISCOVERY_PREFIX: &str = "homeassistant";
+// 断线后重连间隔
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+// Home Assistant 发来的控制命令，由主循环执行
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Mute(bool),
+    // 空文本清除消息
+    Message(String),
+}
+
+// 发布不阻塞界面：断线或队列已满时丢弃事件
+#[derive(Clone)]
+pub struct Mqtt {
+    client: Client,
+    topic: String,
+    // 上次发布的状态（重连后重新发布）与待执行的命令
+    state: Arc<Mutex<Option<String>>>,
+    commands: Arc<Mutex<Vec<Command>>>,
+}
+
+impl Mqtt {
+    // 后台线程维持连接并自动重连；每次连上后重新发布发现配置、在线状态并订阅命令主题
+    pub fn connect(config: &MqttConfig) -> Self {
+        let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        if let Some(username) = &config.username {
+            options.set_credentials(username, config.password.clone().unwrap_or_default());
+        }
+        if config.home_assistant {
+            options.set_last_will(LastWill::new(format!("{}/availability", config.topic), "offline", QoS::AtLeastOnce, true));
+        }
+        let (client, mut connection) = Client::new(options, 32);
+        let mqtt = Self { client, topic: config.topic.clone(), state: Default::default(), commands: Default::default() };
+        let worker = mqtt.clone();
+        let config = config.clone();
+        std::thread::spawn(move || {
+            // 出错后继续迭代即重新连接
+            for event in connection.iter() {
+                match event {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) if config.home_assistant => worker.announce(&config),
+                    Ok(Event::Incoming(Packet::Publish(publish))) => worker.receive(&publish.topic, &publish.payload),
+                    Ok(_) => {}
+                    Err(_) => std::thread::sleep(RECONNECT_DELAY),
+                }
+            }
+        });
+        mqtt
+    }
+
+    // {"event": "chime", "time": "2025-06-01T14:00:00+08:00", ...fields}
+    pub fn event(&self, name: &str, fields: Value) {
+        let mut payload = json!({"event": name, "time": chrono::Local::now().to_rfc3339()});
+        if let (Some(payload), Value::Object(fields)) = (payload.as_object_mut(), fields) {
+            payload.extend(fields);
+        }
+        let _ = self.client.try_publish(format!("{}/events", self.topic), QoS::AtMostOnce, false, payload.to_string());
+    }
+
+    // 状态有变化时发布到 <topic>/state（保留消息）
+    pub fn publish_state(&self, state: Value) {
+        let payload = state.to_string();
+        let Ok(mut last) = self.state.lock() else { return };
+        if last.as_deref() == Some(payload.as_str()) {
+            return;
+        }
+        if self.client.try_publish(format!("{}/state", self.topic), QoS::AtLeastOnce, true, payload.clone()).is_ok() {
+            *last = Some(payload);
+        }
+    }
+
+    pub fn take_commands(&self) -> Vec<Command> {
+        self.commands.lock().map(|mut c| std::mem::take(&mut *c)).unwrap_or_default()
+    }
+
+    fn announce(&self, config: &MqttConfig) {
+        let node = config.client_id.replace(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '-', "_");
+        let topic = &self.topic;
+        let device = json!({
+            "identifiers": [node],
+            "name": config.name,
+            "manufacturer": "TermClock",
+            "model": "Terminal clock",
+            "sw_version": env!("CARGO_PKG_VERSION"),
+        });
+        let entities = [
+            ("sensor", "temperature", json!({
+                "name": "Temperature",
+                "device_class": "temperature",
+                "state_class": "measurement",
+                "unit_of_measurement": "°C",
+                "state_topic": format!("{}/state", topic),
+                "value_template": "{{ value_json.temperature }}",
+            })),
+            ("sensor", "humidity", json!({
+                "name": "Humidity",
+                "device_class": "humidity",
+                "state_class": "measurement",
+                "unit_of_measurement": "%",
+                "state_topic": format!("{}/state", topic),
+                "value_template": "{{ value_json.humidity }}",
+            })),
+            ("switch", "mute", json!({
+                "name": "Mute",
+                "icon": "mdi:bell-off",
+                "state_topic": format!("{}/state", topic),
+                "value_template": "{{ value_json.mute }}",
+                "command_topic": format!("{}/mute/set", topic),
+            })),
+            ("text", "message", json!({
+                "name": "Message",
+                "icon": "mdi:message-text",
+                "max": 255,
+                "state_topic": format!("{}/state", topic),
+                "value_template": "{{ value_json.message }}",
+                "command_topic": format!("{}/message/set", topic),
+            })),
+        ];
+        for (component, object, mut entity) in entities {
+            if let Some(fields) = entity.as_object_mut() {
+                fields.insert("unique_id".to_string(), json!(format!("{}_{}", node, object)));
+                fields.insert("availability_topic".to_string(), json!(format!("{}/availability", topic)));
+                fields.insert("device".to_string(), device.clone());
+            }
+            let discovery = format!("{}/{}/{}/{}/config", config.discovery_prefix, component, node, object);
+            let _ = self.client.try_publish(discovery, QoS::AtLeastOnce, true, entity.to_string());
+        }
+        let _ = self.client.try_publish(format!("{}/availability", topic), QoS::AtLeastOnce, true, "online");
+        for command in ["mute", "message"] {
+            let _ = self.client.try_subscribe(format!("{}/{}/set", topic, command), QoS::AtLeastOnce);
+        }
+        // 重新连接后 broker 上的状态可能已过时，重新发布
+        if let Ok(state) = self.state.lock()
+            && let Some(payload) = state.clone()
+        {
+            let _ = self.client.try_publish(format!("{}/state", topic), QoS::AtLeastOnce, true, payload);
+        }
+    }
+
+    fn receive(&self, topic: &str, payload: &[u8]) {
+        let text = String::from_utf8_lossy(payload).trim().to_string();
+        let command = match topic.strip_prefix(&self.topic) {
+            Some("/mute/set") => Command::Mute(text.eq_ignore_ascii_case("on")),
+            Some("/message/set") => Command::Message(text),
+            _ => return,
+        };
+        if let Ok(mut commands) = self.commands.lock() {
+            commands.push(command);
+        }
+    }
+}
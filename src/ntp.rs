@@ -0,0 +1,105 @@
+use std::net::UdpSocket;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+// 时钟偏差：定期向 NTP 服务器发送一次 SNTP 请求，计算系统时间与服务器时间的差，
+// 用于发现 NTP 被防火墙拦截、树莓派（无 RTC）时间漂移等情况。只显示偏差，不修改系统时间
+
+#[derive(Debug, Clone)]
+pub struct NtpConfig {
+    // host 或 host:port（默认端口 123）
+    pub server: String,
+    pub refresh_secs: u64,
+    // 偏差超过该值时显示为警告色
+    pub warn_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NtpState {
+    // 系统时间比服务器慢时为正（需要加上该值才是准确时间）
+    pub offset_ms: Option<i64>,
+    // 最近一次成功查询的时间
+    pub updated: Option<DateTime<Local>>,
+    pub error: Option<String>,
+}
+
+// 1900-01-01（NTP 纪元）到 1970-01-01 的秒数
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+pub fn spawn_worker(config: NtpConfig) -> Arc<Mutex<NtpState>> {
+    let state = Arc::new(Mutex::new(NtpState::default()));
+    let shared = state.clone();
+    std::thread::spawn(move || loop {
+        let result = query(&config.server);
+        if let Ok(mut guard) = shared.lock() {
+            match result {
+                Ok(offset) => *guard = NtpState { offset_ms: Some(offset), updated: Some(Local::now()), error: None },
+                // 保留上次的偏差，界面按 error 显示为过时
+                Err(e) => guard.error = Some(e),
+            }
+        }
+        std::thread::sleep(crate::power::stretch(Duration::from_secs(config.refresh_secs)));
+    });
+    state
+}
+
+// 查询一次，返回偏差（毫秒）：((T2 - T1) + (T3 - T4)) / 2
+pub fn query(server: &str) -> Result<i64, String> {
+    let addr = if server.contains(':') { server.to_string() } else { format!("{}:123", server) };
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    socket.set_read_timeout(Some(Duration::from_secs(5))).map_err(|e| e.to_string())?;
+    socket.connect(&addr).map_err(|e| format!("ntp {}: {}", addr, e))?;
+    let mut request = [0u8; 48];
+    // LI = 0，版本 4，模式 3（客户端）
+    request[0] = 0x23;
+    let t1 = unix_nanos();
+    let t1_ntp = to_ntp(t1);
+    request[40..48].copy_from_slice(&t1_ntp.to_be_bytes());
+    socket.send(&request).map_err(|e| format!("ntp {}: {}", addr, e))?;
+    let mut response = [0u8; 48];
+    let len = socket.recv(&mut response).map_err(|e| format!("ntp {}: {}", addr, e))?;
+    let t4 = unix_nanos();
+    let timestamp = |at: usize| u64::from_be_bytes(response[at..at + 8].try_into().unwrap_or_default());
+    if len < 48 || response[0] & 0x07 != 4 {
+        return Err(format!("ntp {}: invalid response", addr));
+    }
+    // 层级 0 为 Kiss-o'-Death（服务器要求降低请求频率等）
+    if response[1] == 0 {
+        return Err(format!("ntp {}: refused ({})", addr, String::from_utf8_lossy(&response[12..16]).trim_end_matches('\0')));
+    }
+    // 原始时间戳必须与请求一致，否则不是对本次请求的应答
+    if timestamp(24) != t1_ntp {
+        return Err(format!("ntp {}: mismatched response", addr));
+    }
+    let (t2, t3) = (from_ntp(timestamp(32)), from_ntp(timestamp(40)));
+    let offset = ((t2 - t1) + (t3 - t4)) / 2;
+    Ok((offset / 1_000_000) as i64)
+}
+
+// +12ms / -1.3s
+pub fn format_offset(ms: i64) -> String {
+    match ms.unsigned_abs() {
+        0..1000 => format!("{:+}ms", ms),
+        _ => format!("{:+.1}s", ms as f64 / 1000.0),
+    }
+}
+
+fn unix_nanos() -> i128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as i128)
+}
+
+// 高 32 位为秒，低 32 位为秒的小数部分（2036 年前有效）
+fn to_ntp(nanos: i128) -> u64 {
+    let secs = (nanos / 1_000_000_000) as u64 + NTP_UNIX_OFFSET;
+    let frac = ((nanos % 1_000_000_000) << 32) / 1_000_000_000;
+    (secs << 32) | frac as u64
+}
+
+fn from_ntp(timestamp: u64) -> i128 {
+    let secs = i128::from(timestamp >> 32) - i128::from(NTP_UNIX_OFFSET);
+    let frac = (i128::from(timestamp & 0xffff_ffff) * 1_000_000_000) >> 32;
+    secs * 1_000_000_000 + frac
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+// 命令面板（: 键）：解析命令并提供模糊补全
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteCommand {
+    // theme <名称>，default 恢复配置文件颜色
+    Theme(Option<String>),
+    // timer 10m / timer cancel
+    Timer(Option<Duration>),
+    TodoAdd(String),
+    // 序号从 1 开始（与 termclock todo list 一致）
+    TodoDone(usize),
+    TodoRm(usize),
+    // tz Europe/Berlin / tz local
+    Tz(Option<chrono_tz::Tz>),
+    Mute(Option<u64>),
+    Unmute,
+    Refresh,
+    Pause,
+    Quit,
+}
+
+impl PaletteCommand {
+    // attach 前端需交给守护进程执行的命令（IPC 命令行）
+    pub fn ipc_line(&self) -> Option<String> {
+        match self {
+            PaletteCommand::Timer(Some(d)) => Some(format!("timer {}", d.as_secs())),
+            PaletteCommand::Timer(None) => Some("timer cancel".to_string()),
+            PaletteCommand::Mute(Some(m)) => Some(format!("mute {}", m)),
+            PaletteCommand::Mute(None) => Some("mute".to_string()),
+            PaletteCommand::Unmute => Some("unmute".to_string()),
+            PaletteCommand::Refresh => Some("refresh".to_string()),
+            _ => None,
+        }
+    }
+}
+
+const COMMANDS: &[&str] = &["theme", "timer", "todo", "tz", "mute", "unmute", "refresh", "pause", "quit"];
+const TIMER_PRESETS: &[&str] = &["5m", "10m", "25m", "1h", "cancel"];
+const TODO_ACTIONS: &[&str] = &["add", "done", "rm"];
+
+pub fn parse(line: &str, themes: &[String]) -> Result<PaletteCommand, String> {
+    let line = line.trim().trim_start_matches(':').trim();
+    let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    let cmd = match name.to_ascii_lowercase().as_str() {
+        "theme" => match rest {
+            "" => return Err("usage: theme <name> (default restores config colours)".to_string()),
+            "default" => PaletteCommand::Theme(None),
+            _ => match themes.iter().find(|t| t.eq_ignore_ascii_case(rest)) {
+                Some(t) => PaletteCommand::Theme(Some(t.clone())),
+                None => return Err(format!("unknown theme: {}", rest)),
+            },
+        },
+        "timer" => match rest {
+            "cancel" | "off" => PaletteCommand::Timer(None),
+            _ => PaletteCommand::Timer(Some(parse_duration(rest).ok_or("usage: timer <10m | 90s | 1h30m | cancel>")?)),
+        },
+        "todo" => {
+            let (action, arg) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let index = || arg.trim().parse::<usize>().ok().filter(|&n| n > 0).ok_or("todo done/rm needs an item number");
+            match action {
+                "add" if !arg.trim().is_empty() => PaletteCommand::TodoAdd(arg.trim().to_string()),
+                "done" => PaletteCommand::TodoDone(index()?),
+                "rm" => PaletteCommand::TodoRm(index()?),
+                _ => return Err("usage: todo add <task> | done <n> | rm <n>".to_string()),
+            }
+        }
+        "tz" => match rest {
+            "" | "local" => PaletteCommand::Tz(None),
+            _ => PaletteCommand::Tz(Some(parse_tz(rest).ok_or_else(|| format!("unknown time zone: {}", rest))?)),
+        },
+        "mute" => match rest {
+            "" => PaletteCommand::Mute(None),
+            _ => PaletteCommand::Mute(Some(
+                rest.parse().ok().filter(|&m| m <= crate::ipc::MAX_MUTE_MINUTES).ok_or("usage: mute [minutes] (at most 100 days)")?,
+            )),
+        },
+        "unmute" => PaletteCommand::Unmute,
+        "refresh" => PaletteCommand::Refresh,
+        "pause" => PaletteCommand::Pause,
+        "quit" | "q" => PaletteCommand::Quit,
+        "" => return Err(String::new()),
+        other => return Err(format!("unknown command: {}", other)),
+    };
+    Ok(cmd)
+}
+
+// 倒计时上限（100 天），更长的时间按无效输入处理，也避免计算到期时刻时溢出
+pub const MAX_TIMER: Duration = Duration::from_secs(100 * 24 * 3600);
+
+// "10m"、"90s"、"1h30m"，纯数字按分钟
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    if let Ok(mins) = s.parse::<u64>() {
+        return mins.checked_mul(60).map(Duration::from_secs).filter(|d| !d.is_zero() && *d <= MAX_TIMER);
+    }
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in s.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            'h' | 'm' | 's' => {
+                let n: u64 = digits.parse().ok()?;
+                let unit = match c { 'h' => 3600, 'm' => 60, _ => 1 };
+                total = n.checked_mul(unit).and_then(|secs| total.checked_add(secs))?;
+                digits.clear();
+            }
+            _ => return None,
+        }
+    }
+    Some(Duration::from_secs(total)).filter(|d| digits.is_empty() && !d.is_zero() && *d <= MAX_TIMER)
+}
+
+pub fn parse_tz(name: &str) -> Option<chrono_tz::Tz> {
+    name.parse().ok().or_else(|| {
+        chrono_tz::TZ_VARIANTS.iter().copied().find(|tz| tz.name().eq_ignore_ascii_case(name))
+    })
+}
+
+// Tab：取第一个候选，需要参数的命令后补一个空格
+pub fn complete(input: &str, themes: &[String]) -> Option<String> {
+    let first = completions(input, themes).into_iter().next()?;
+    let takes_arg = ["theme", "timer", "todo", "tz", "mute"].contains(&first.as_str()) || first == "todo add";
+    Some(if takes_arg { format!("{} ", first) } else { first })
+}
+
+// 补全候选（完整命令行），按匹配程度排序
+pub fn completions(input: &str, themes: &[String]) -> Vec<String> {
+    let input = input.trim_start();
+    let Some((name, arg)) = input.split_once(char::is_whitespace) else {
+        return ranked(input, COMMANDS.iter().map(|c| c.to_string()));
+    };
+    let args: Vec<String> = match name {
+        "theme" => std::iter::once("default".to_string()).chain(themes.iter().cloned()).collect(),
+        "timer" => TIMER_PRESETS.iter().map(|s| s.to_string()).collect(),
+        // todo add 之后是任务内容，不补全
+        "todo" if !arg.contains(char::is_whitespace) => TODO_ACTIONS.iter().map(|s| s.to_string()).collect(),
+        "tz" => std::iter::once("local".to_string())
+            .chain(chrono_tz::TZ_VARIANTS.iter().map(|tz| tz.name().to_string()))
+            .collect(),
+        _ => Vec::new(),
+    };
+    ranked(arg.trim_start(), args.into_iter()).into_iter().map(|a| format!("{} {}", name, a)).collect()
+}
+
+fn ranked(pattern: &str, candidates: impl Iterator<Item = String>) -> Vec<String> {
+    let mut scored: Vec<(usize, String)> = candidates.filter_map(|c| Some((fuzzy_score(pattern, &c)?, c))).collect();
+    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.len().cmp(&b.1.len())));
+    scored.into_iter().map(|(_, c)| c).collect()
+}
+
+// 模糊匹配：pattern 的字符按顺序出现在候选中（忽略大小写），分数越小越好；
+// 前缀匹配最优，其次连续子串，再按字符间的间隔计分
+fn fuzzy_score(pattern: &str, candidate: &str) -> Option<usize> {
+    let pattern = pattern.to_lowercase();
+    let lower = candidate.to_lowercase();
+    if lower.starts_with(&pattern) {
+        return Some(0);
+    }
+    if lower.contains(&pattern) {
+        return Some(1);
+    }
+    let mut gaps = 0;
+    let mut chars = lower.chars();
+    for p in pattern.chars() {
+        let mut skipped = 0;
+        loop {
+            match chars.next() {
+                Some(c) if c == p => break,
+                Some(_) => skipped += 1,
+                None => return None,
+            }
+        }
+        gaps += skipped;
+    }
+    Some(2 + gaps)
+}
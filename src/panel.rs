@@ -0,0 +1,164 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use serde::{Deserialize, Serialize};
+
+// 命令输出面板：定期运行命令，按原样显示标准输出（支持 ANSI 颜色），
+// 相当于时钟布局里的 watch
+#[derive(Debug, Clone)]
+pub struct CommandPanel {
+    pub title: String,
+    // 通过 sh -c 运行，可带参数
+    pub command: String,
+    pub interval_secs: u64,
+    // 最多显示的行数，超出部分截断
+    pub max_lines: usize,
+    pub timeout_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PanelState {
+    Loading,
+    // 保留 ANSI 转义序列的输出行，绘制时再解析
+    Ready(Vec<String>),
+    Error(String),
+}
+
+pub fn spawn_worker(panel: CommandPanel) -> Arc<Mutex<PanelState>> {
+    let state = Arc::new(Mutex::new(PanelState::Loading));
+    let shared = state.clone();
+    std::thread::spawn(move || loop {
+        let next = match crate::script::run_command(&panel.command, &panel.title, panel.timeout_secs) {
+            Ok(text) => PanelState::Ready(output_lines(&text, panel.max_lines)),
+            Err(e) => PanelState::Error(e),
+        };
+        if let Ok(mut guard) = shared.lock() {
+            *guard = next;
+        }
+        std::thread::sleep(crate::power::stretch(Duration::from_secs(panel.interval_secs)));
+    });
+    state
+}
+
+// 去掉末尾空行，制表符展开为空格，只保留前 max_lines 行
+fn output_lines(text: &str, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = text.lines().take(max_lines).map(|l| l.trim_end_matches('\r').replace('\t', "    ")).collect();
+    while lines.last().is_some_and(|l| strip_ansi(l).trim().is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+fn strip_ansi(line: &str) -> String {
+    parse_ansi(line).spans.into_iter().map(|s| s.content.into_owned()).collect()
+}
+
+// 解析一行中的 SGR 颜色序列（ESC[...m）：粗体、暗淡、斜体、下划线、反色，
+// 16 色、256 色和 24 位颜色；其他转义序列和控制字符直接丢弃
+pub fn parse_ansi(line: &str) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut style = Style::default();
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            if !c.is_control() {
+                text.push(c);
+            }
+            continue;
+        }
+        if chars.peek() != Some(&'[') {
+            // ESC 加单个字符（如 ESC ( B）
+            chars.next();
+            continue;
+        }
+        chars.next();
+        let mut params = String::new();
+        let mut last = None;
+        for c in chars.by_ref() {
+            if ('\x40'..='\x7e').contains(&c) {
+                last = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        if last != Some('m') {
+            continue;
+        }
+        if !text.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut text), style));
+        }
+        style = apply_sgr(style, &params);
+    }
+    if !text.is_empty() {
+        spans.push(Span::styled(text, style));
+    }
+    Line::from(spans)
+}
+
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let codes: Vec<u16> = params.split([';', ':']).map(|p| p.parse().unwrap_or(0)).collect();
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => style = Style::default(),
+            1 => style = style.add_modifier(Modifier::BOLD),
+            2 => style = style.add_modifier(Modifier::DIM),
+            3 => style = style.add_modifier(Modifier::ITALIC),
+            4 => style = style.add_modifier(Modifier::UNDERLINED),
+            7 => style = style.add_modifier(Modifier::REVERSED),
+            22 => style = style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style = style.remove_modifier(Modifier::ITALIC),
+            24 => style = style.remove_modifier(Modifier::UNDERLINED),
+            27 => style = style.remove_modifier(Modifier::REVERSED),
+            n @ 30..=37 => style.fg = Some(basic_color(n - 30, false)),
+            n @ 90..=97 => style.fg = Some(basic_color(n - 90, true)),
+            n @ 40..=47 => style.bg = Some(basic_color(n - 40, false)),
+            n @ 100..=107 => style.bg = Some(basic_color(n - 100, true)),
+            39 => style.fg = None,
+            49 => style.bg = None,
+            n @ (38 | 48) => {
+                // 38;5;n 为 256 色，38;2;r;g;b 为 24 位颜色
+                let color = match codes.get(i + 1) {
+                    Some(5) => codes.get(i + 2).map(|&c| (Color::Indexed(c as u8), 3)),
+                    Some(2) if codes.len() > i + 4 => Some((Color::Rgb(codes[i + 2] as u8, codes[i + 3] as u8, codes[i + 4] as u8), 5)),
+                    _ => None,
+                };
+                let Some((color, used)) = color else { break };
+                if n == 38 {
+                    style.fg = Some(color);
+                } else {
+                    style.bg = Some(color);
+                }
+                i += used;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    style
+}
+
+fn basic_color(index: u16, bright: bool) -> Color {
+    match (index, bright) {
+        (0, false) => Color::Black,
+        (1, false) => Color::Red,
+        (2, false) => Color::Green,
+        (3, false) => Color::Yellow,
+        (4, false) => Color::Blue,
+        (5, false) => Color::Magenta,
+        (6, false) => Color::Cyan,
+        (7, false) => Color::Gray,
+        (0, true) => Color::DarkGray,
+        (1, true) => Color::LightRed,
+        (2, true) => Color::LightGreen,
+        (3, true) => Color::LightYellow,
+        (4, true) => Color::LightBlue,
+        (5, true) => Color::LightMagenta,
+        (6, true) => Color::LightCyan,
+        _ => Color::White,
+    }
+}
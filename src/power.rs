@@ -0,0 +1,43 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+// 省电模式：每分钟重绘一次（不显示秒），关闭动画和背景效果，网络请求间隔拉长，
+// 适合在笔记本电池供电时运行。auto 时按电源状态自动切换
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LowPower {
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+// 省电时后台数据线程和温度 / 待办请求的间隔倍数
+const STRETCH_FACTOR: u32 = 4;
+// 后台线程读取的当前状态（由主循环更新）
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_active(active: bool) {
+    ACTIVE.store(active, Ordering::Relaxed);
+}
+
+// 省电时拉长刷新间隔
+pub fn stretch(interval: Duration) -> Duration {
+    match ACTIVE.load(Ordering::Relaxed) {
+        true => interval.checked_mul(STRETCH_FACTOR).unwrap_or(interval),
+        false => interval,
+    }
+}
+
+// 是否使用电池供电：有系统电池正在放电（/sys/class/power_supply；蓝牙鼠标等设备电池不算）
+pub fn on_battery() -> bool {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else { return false };
+    entries.flatten().any(|entry| {
+        let read = |name: &str| read_trimmed(&entry.path().join(name));
+        read("type") == "Battery" && read("scope") != "Device" && read("status") == "Discharging"
+    })
+}
+
+fn read_trimmed(path: &Path) -> String {
+    std::fs::read_to_string(path).map(|s| s.trim().to_string()).unwrap_or_default()
+}
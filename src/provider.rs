@@ -0,0 +1,48 @@
+use crate::model::Config;
+
+// 温度提供者链：按 temperature_providers 的顺序尝试，第一个成功的结果用于显示。
+// 未配置时为 传感器 API（如有）→ wttr.in
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemperatureProvider {
+    // 传感器 API（api_schema 中的 temperature 接口）
+    Api { base_url: String, device_code: String },
+    // wttr.in；location 为空时按 IP 定位
+    Wttr { location: Option<String> },
+    // Open-Meteo 当前气温和湿度
+    OpenMeteo { base_url: String, latitude: f64, longitude: f64 },
+}
+
+impl TemperatureProvider {
+    // 状态文件缓存和诊断中使用的名称
+    pub fn name(&self) -> String {
+        match self {
+            Self::Api { device_code, .. } => format!("api:{}", device_code),
+            Self::Wttr { location: None } => "wttr.in".to_string(),
+            Self::Wttr { location: Some(location) } => format!("wttr.in:{}", location),
+            Self::OpenMeteo { latitude, longitude, .. } => format!("open-meteo@{:.3},{:.3}", latitude, longitude),
+        }
+    }
+
+    // 温度文本（如 "21.5℃"）和湿度；wttr.in 在最短间隔内复用上次结果
+    pub fn fetch(&self, config: &Config) -> Result<(String, Option<f64>), String> {
+        match self {
+            Self::Wttr { location } => crate::api::fetch_wttr(&config.state_file, location.as_deref()).map(|temp| (temp, None)),
+            _ => self.fetch_now(config),
+        }
+    }
+
+    // 不使用缓存，直接请求（doctor 使用）
+    pub fn fetch_now(&self, config: &Config) -> Result<(String, Option<f64>), String> {
+        match self {
+            Self::Api { base_url, device_code } => crate::api::fetch_sensor_reading(base_url, device_code, &config.api_schema.temperature),
+            Self::Wttr { location } => crate::api::fetch_wttr_now(location.as_deref()).map(|temp| (temp, None)),
+            Self::OpenMeteo { base_url, latitude, longitude } => crate::weather::current(base_url, *latitude, *longitude),
+        }
+    }
+}
+
+// 未配置 temperature_providers 时的默认链
+pub fn default_chain(api_base_url: Option<&str>, device_code: &str) -> Vec<TemperatureProvider> {
+    let api = api_base_url.map(|base_url| TemperatureProvider::Api { base_url: base_url.to_string(), device_code: device_code.to_string() });
+    api.into_iter().chain([TemperatureProvider::Wttr { location: None }]).collect()
+}
@@ -0,0 +1,415 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
+use chrono::{DateTime, Local, TimeZone};
+use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
+
+// 天气雷达：定期获取所在位置的雷达瓦片（默认 RainViewer），在雷达页面中用 kitty / sixel
+// 图形协议显示；不支持图形的终端（以及 attach 前端）显示文字摘要
+
+#[derive(Debug, Clone)]
+pub struct RadarConfig {
+    // 瓦片地址模板（{z} {x} {y}）；未设置时使用 RainViewer 最新一帧
+    pub url: Option<String>,
+    pub zoom: u32,
+    pub refresh_secs: u64,
+    pub graphics: Graphics,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Graphics {
+    Auto,
+    Kitty,
+    Sixel,
+    Text,
+}
+
+impl Graphics {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Graphics::Auto),
+            "kitty" => Some(Graphics::Kitty),
+            "sixel" => Some(Graphics::Sixel),
+            "text" | "none" => Some(Graphics::Text),
+            _ => None,
+        }
+    }
+
+    // auto：按终端环境变量判断（无法可靠探测时按文字显示）
+    pub fn resolve(self) -> Self {
+        if self != Graphics::Auto {
+            return self;
+        }
+        let var = |name: &str| std::env::var(name).unwrap_or_default().to_ascii_lowercase();
+        let (term, program) = (var("TERM"), var("TERM_PROGRAM"));
+        if std::env::var_os("KITTY_WINDOW_ID").is_some() || term.contains("kitty") || ["wezterm", "ghostty"].contains(&program.as_str()) {
+            Graphics::Kitty
+        } else if term.contains("sixel") || term.starts_with("foot") || term.starts_with("mlterm") || term == "yaft-256color" {
+            Graphics::Sixel
+        } else {
+            Graphics::Text
+        }
+    }
+}
+
+// 解码后的 RGBA 图像（已标出所在位置）
+#[derive(Debug)]
+pub struct RadarImage {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RadarFrame {
+    // 雷达数据时间
+    pub time: DateTime<Local>,
+    // 瓦片中有降水的比例（0..=1）
+    pub coverage: f64,
+    pub at_location: bool,
+    // 图像不通过 IPC 传输
+    #[serde(skip)]
+    pub image: Option<Arc<RadarImage>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RadarState {
+    Loading,
+    Ready(RadarFrame),
+    Error(String),
+}
+
+const RAINVIEWER_MAPS: &str = "https://api.rainviewer.com/public/weather-maps.json";
+// 无降水处的底色，以及位置标记颜色
+const BACKGROUND: [u8; 4] = [24, 24, 32, 255];
+const MARKER: [u8; 4] = [255, 64, 64, 255];
+
+pub fn spawn_worker(config: RadarConfig, location: (f64, f64)) -> Arc<Mutex<RadarState>> {
+    let state = Arc::new(Mutex::new(RadarState::Loading));
+    let shared = state.clone();
+    std::thread::spawn(move || loop {
+        let next = match fetch(&config, location) {
+            Ok(frame) => RadarState::Ready(frame),
+            Err(e) => RadarState::Error(e),
+        };
+        if let Ok(mut guard) = shared.lock() {
+            *guard = next;
+        }
+        std::thread::sleep(crate::power::stretch(Duration::from_secs(config.refresh_secs)));
+    });
+    state
+}
+
+fn fetch(config: &RadarConfig, (lat, lon): (f64, f64)) -> Result<RadarFrame, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let get = |url: &str| {
+        let _permit = crate::ratelimit::permit();
+        client
+            .get(url)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.bytes())
+            .map_err(|e| format!("radar: {}", e))
+    };
+    let (x, y, px, py) = tile_position(lat, lon, config.zoom);
+    let (template, time) = match &config.url {
+        Some(url) => (url.clone(), Local::now()),
+        None => {
+            let maps: serde_json::Value = serde_json::from_slice(&get(RAINVIEWER_MAPS)?).map_err(|e| format!("radar: {}", e))?;
+            let latest = maps["radar"]["past"].as_array().and_then(|frames| frames.last()).ok_or("radar: no frames available")?;
+            let host = maps["host"].as_str().ok_or("radar: missing host")?;
+            let path = latest["path"].as_str().ok_or("radar: missing path")?;
+            let time = latest["time"].as_i64().and_then(|t| Local.timestamp_opt(t, 0).single()).unwrap_or_else(Local::now);
+            (format!("{}{}/256/{{z}}/{{x}}/{{y}}/2/1_1.png", host, path), time)
+        }
+    };
+    let url = template.replace("{z}", &config.zoom.to_string()).replace("{x}", &x.to_string()).replace("{y}", &y.to_string());
+    let mut image = decode_png(&get(&url)?).ok_or("radar: unsupported or invalid PNG")?;
+    let pixel = |x: usize, y: usize| (y * image.width + x) * 4;
+    let (mx, my) = ((px * image.width as f64) as usize, (py * image.height as f64) as usize);
+    let at_location = image.rgba.get(pixel(mx, my) + 3).is_some_and(|&a| a > 0);
+    let wet = image.rgba.chunks(4).filter(|p| p[3] > 0).count();
+    let coverage = wet as f64 / (image.width * image.height).max(1) as f64;
+    // 铺底色，并在所在位置画一个十字
+    for p in image.rgba.chunks_mut(4) {
+        let a = p[3] as u32;
+        for c in 0..3 {
+            p[c] = ((p[c] as u32 * a + BACKGROUND[c] as u32 * (255 - a)) / 255) as u8;
+        }
+        p[3] = 255;
+    }
+    for d in -4i64..=4 {
+        for (cx, cy) in [(mx as i64 + d, my as i64), (mx as i64, my as i64 + d)] {
+            if (0..image.width as i64).contains(&cx) && (0..image.height as i64).contains(&cy) {
+                let i = pixel(cx as usize, cy as usize);
+                image.rgba[i..i + 4].copy_from_slice(&MARKER);
+            }
+        }
+    }
+    Ok(RadarFrame { time, coverage, at_location, image: Some(Arc::new(image)) })
+}
+
+// 经纬度所在的瓦片坐标（Web Mercator）和瓦片内的相对位置（0..1）
+fn tile_position(lat: f64, lon: f64, zoom: u32) -> (u64, u64, f64, f64) {
+    let n = f64::from(1u32 << zoom.min(20));
+    let fx = (lon + 180.0) / 360.0 * n;
+    let lat = lat.clamp(-85.05, 85.05).to_radians();
+    let fy = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / std::f64::consts::PI) / 2.0 * n;
+    (fx.floor() as u64, fy.floor() as u64, fx.fract(), fy.fract())
+}
+
+// 文字摘要（不支持图形时显示）
+pub fn summary(frame: &RadarFrame) -> String {
+    let here = if frame.at_location { "precipitation at your location" } else { "dry at your location" };
+    format!("radar {} · {} · precipitation over {:.0}% of the area", frame.time.format("%H:%M"), here, frame.coverage * 100.0)
+}
+
+// 最小 PNG 解码：非隔行，灰度 / RGB / 调色板 / 带透明度，位深 1-16（16 位取高字节）
+fn decode_png(data: &[u8]) -> Option<RadarImage> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    let mut pos = SIGNATURE.len();
+    if data.get(..pos)? != SIGNATURE {
+        return None;
+    }
+    let (mut width, mut height, mut depth, mut color) = (0, 0, 0, 0);
+    let (mut palette, mut alpha, mut idat) = (Vec::new(), Vec::new(), Vec::new());
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().ok()?) as usize;
+        let kind = &data[pos + 4..pos + 8];
+        let body = data.get(pos + 8..pos + 8 + len)?;
+        match kind {
+            b"IHDR" => {
+                width = u32::from_be_bytes(body.get(0..4)?.try_into().ok()?) as usize;
+                height = u32::from_be_bytes(body.get(4..8)?.try_into().ok()?) as usize;
+                depth = *body.get(8)? as usize;
+                color = *body.get(9)?;
+                if *body.get(12)? != 0 {
+                    return None;
+                }
+            }
+            b"PLTE" => palette = body.to_vec(),
+            b"tRNS" => alpha = body.to_vec(),
+            b"IDAT" => idat.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+    let channels = match color {
+        0 | 3 => 1,
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => return None,
+    };
+    if width == 0 || height == 0 || ![1, 2, 4, 8, 16].contains(&depth) {
+        return None;
+    }
+    let raw = miniz_oxide::inflate::decompress_to_vec_zlib(&idat).ok()?;
+    let stride = (width * channels * depth).div_ceil(8);
+    let bpp = (channels * depth).div_ceil(8);
+    if raw.len() < (stride + 1) * height {
+        return None;
+    }
+    // 反滤波
+    let mut rows = vec![0u8; stride * height];
+    for y in 0..height {
+        let filter = raw[y * (stride + 1)];
+        let line = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        for i in 0..stride {
+            let a = if i >= bpp { rows[y * stride + i - bpp] } else { 0 };
+            let b = if y > 0 { rows[(y - 1) * stride + i] } else { 0 };
+            let c = if y > 0 && i >= bpp { rows[(y - 1) * stride + i - bpp] } else { 0 };
+            let predicted = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return None,
+            };
+            rows[y * stride + i] = line[i].wrapping_add(predicted);
+        }
+    }
+    let sample = |row: &[u8], index: usize| -> u8 {
+        match depth {
+            8 => row[index],
+            16 => row[index * 2],
+            _ => {
+                let bit = index * depth;
+                let value = (row[bit / 8] >> (8 - depth - bit % 8)) & ((1 << depth) - 1);
+                // 调色板为索引，灰度放大到 0-255
+                if color == 3 { value } else { (value as u32 * 255 / ((1 << depth) - 1)) as u8 }
+            }
+        }
+    };
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let row = &rows[y * stride..(y + 1) * stride];
+        for x in 0..width {
+            let s = |c: usize| sample(row, x * channels + c);
+            let pixel = match color {
+                0 => [s(0), s(0), s(0), 255],
+                2 => [s(0), s(1), s(2), 255],
+                3 => {
+                    let i = s(0) as usize;
+                    let rgb = palette.get(i * 3..i * 3 + 3)?;
+                    [rgb[0], rgb[1], rgb[2], alpha.get(i).copied().unwrap_or(255)]
+                }
+                4 => [s(0), s(0), s(0), s(1)],
+                _ => [s(0), s(1), s(2), s(3)],
+            };
+            rgba.extend_from_slice(&pixel);
+        }
+    }
+    Some(RadarImage { width, height, rgba })
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+// 图形输出：图像或显示区域变化时才重新发送，离开雷达页面时清除
+#[derive(Default)]
+pub struct Overlay {
+    shown: Option<(DateTime<Local>, Rect)>,
+}
+
+// kitty 图像 id，重复发送时替换同一张图
+const KITTY_ID: u32 = 7351;
+
+impl Overlay {
+    // area 为 None 表示当前不在雷达页面；返回 true 时需要清屏重绘（清除残留的 sixel 图像）
+    pub fn update(&mut self, out: &mut impl Write, graphics: Graphics, area: Option<Rect>, frame: Option<&RadarFrame>) -> io::Result<bool> {
+        let target = match (area, frame) {
+            (Some(area), Some(frame)) if frame.image.is_some() && area.width > 0 && area.height > 0 => Some((frame.time, area)),
+            _ => None,
+        };
+        if target == self.shown {
+            return Ok(false);
+        }
+        let had_image = self.shown.take().is_some();
+        if had_image && graphics == Graphics::Kitty {
+            write!(out, "\x1b_Ga=d,d=I,i={},q=2\x1b\\", KITTY_ID)?;
+        }
+        let (Some((_, area)), Some(image)) = (target, frame.and_then(|f| f.image.clone())) else {
+            out.flush()?;
+            return Ok(had_image && graphics == Graphics::Sixel);
+        };
+        // 光标移到区域左上角（终端坐标从 1 开始）
+        write!(out, "\x1b7\x1b[{};{}H", area.y + 1, area.x + 1)?;
+        match graphics {
+            Graphics::Kitty => write_kitty(out, &image, area)?,
+            Graphics::Sixel => write_sixel(out, &image, area)?,
+            _ => {}
+        }
+        write!(out, "\x1b8")?;
+        out.flush()?;
+        self.shown = target;
+        Ok(false)
+    }
+}
+
+// kitty：zlib 压缩的 RGBA，按 4096 字节分块，缩放到 area 的单元格
+fn write_kitty(out: &mut impl Write, image: &RadarImage, area: Rect) -> io::Result<()> {
+    let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&image.rgba, 6);
+    let encoded = base64::engine::general_purpose::STANDARD.encode(compressed);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            write!(
+                out,
+                "\x1b_Ga=T,f=32,o=z,s={},v={},i={},c={},r={},C=1,q=2,m={};",
+                image.width, image.height, KITTY_ID, area.width, area.height, more
+            )?;
+        } else {
+            write!(out, "\x1b_Gm={};", more)?;
+        }
+        out.write_all(chunk)?;
+        write!(out, "\x1b\\")?;
+    }
+    Ok(())
+}
+
+// sixel：按终端单元格像素大小缩放（未知时按 8x16），颜色量化到 6x6x6 色板
+fn write_sixel(out: &mut impl Write, image: &RadarImage, area: Rect) -> io::Result<()> {
+    let (cell_w, cell_h) = match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.columns > 0 => (
+            (size.width / size.columns).max(1) as usize,
+            (size.height / size.rows.max(1)).max(1) as usize,
+        ),
+        _ => (8, 16),
+    };
+    let (w, h) = (area.width as usize * cell_w, area.height as usize * cell_h);
+    let level = |v: u8| (v as usize * 5 + 127) / 255;
+    let color_at = |x: usize, y: usize| {
+        let (sx, sy) = (x * image.width / w, y * image.height / h);
+        let p = &image.rgba[(sy * image.width + sx) * 4..][..3];
+        level(p[0]) * 36 + level(p[1]) * 6 + level(p[2])
+    };
+    let mut text = String::from("\x1bP0;1;0q");
+    text.push_str(&format!("\"1;1;{};{}", w, h));
+    let grid: Vec<usize> = (0..h).flat_map(|y| (0..w).map(move |x| (x, y))).map(|(x, y)| color_at(x, y)).collect();
+    let mut used = [false; 216];
+    for &c in &grid {
+        used[c] = true;
+    }
+    for (c, _) in used.iter().enumerate().filter(|(_, u)| **u) {
+        let pct = |l: usize| l * 100 / 5;
+        text.push_str(&format!("#{};2;{};{};{}", c, pct(c / 36), pct(c / 6 % 6), pct(c % 6)));
+    }
+    for band in (0..h).step_by(6) {
+        let rows = (h - band).min(6);
+        let mut colors: Vec<usize> = (band..band + rows).flat_map(|y| grid[y * w..(y + 1) * w].iter().copied()).collect();
+        colors.sort_unstable();
+        colors.dedup();
+        for (n, &c) in colors.iter().enumerate() {
+            if n > 0 {
+                text.push('$');
+            }
+            text.push_str(&format!("#{}", c));
+            let mut run: Option<(char, usize)> = None;
+            for x in 0..w {
+                let bits = (0..rows).filter(|&r| grid[(band + r) * w + x] == c).fold(0u8, |acc, r| acc | (1 << r));
+                let ch = (63 + bits) as char;
+                run = match run {
+                    Some((prev, count)) if prev == ch => Some((prev, count + 1)),
+                    Some((prev, count)) => {
+                        push_run(&mut text, prev, count);
+                        Some((ch, 1))
+                    }
+                    None => Some((ch, 1)),
+                };
+            }
+            if let Some((prev, count)) = run {
+                push_run(&mut text, prev, count);
+            }
+        }
+        text.push('-');
+    }
+    text.push_str("\x1b\\");
+    out.write_all(text.as_bytes())
+}
+
+fn push_run(text: &mut String, ch: char, count: usize) {
+    if count > 3 {
+        text.push_str(&format!("!{}{}", count, ch));
+    } else {
+        text.extend(std::iter::repeat_n(ch, count));
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+// 请求限流：各数据源的最短请求间隔 + 全局并发请求上限，避免配置的刷新间隔过短时
+// 频繁请求 wttr.in 或传感器 API（对方可能限流或封禁信息屏的 IP）
+
+// 各数据源的最短刷新间隔（秒），配置的间隔更短时按此执行
+pub const SENSOR_API_MIN_SECS: u64 = 5;
+pub const CUSTOM_SOURCE_MIN_SECS: u64 = 10;
+pub const TRANSIT_MIN_SECS: u64 = 15;
+pub const UPCOMING_MIN_SECS: u64 = 60;
+pub const RADAR_MIN_SECS: u64 = 300;
+// 公共 NTP 服务器要求客户端请求间隔不低于 64 秒
+pub const NTP_MIN_SECS: u64 = 64;
+// wttr.in 约每小时更新，回退获取和室内外对比共用同一结果
+pub const WTTR_MIN_SECS: u64 = 900;
+
+pub const DEFAULT_MAX_CONCURRENT: usize = 4;
+
+struct Limiter {
+    max: usize,
+    active: usize,
+}
+
+static LIMITER: Mutex<Limiter> = Mutex::new(Limiter { max: DEFAULT_MAX_CONCURRENT, active: 0 });
+static RELEASED: Condvar = Condvar::new();
+// 按间隔缓存的结果：键 -> (请求时间, 结果)
+type Cached = HashMap<String, (Instant, Result<String, String>)>;
+static CACHE: Mutex<Option<Cached>> = Mutex::new(None);
+
+// 启动时按配置设置并发上限
+pub fn configure(max_concurrent: usize) {
+    if let Ok(mut limiter) = LIMITER.lock() {
+        limiter.max = max_concurrent.max(1);
+    }
+}
+
+// 持有期间占用一个并发名额，离开作用域时释放
+pub struct Permit(());
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Ok(mut limiter) = LIMITER.lock() {
+            limiter.active -= 1;
+        }
+        RELEASED.notify_one();
+    }
+}
+
+// 等待空闲名额；各请求都有超时，等待时间有限
+pub fn permit() -> Permit {
+    let mut limiter = LIMITER.lock().unwrap_or_else(|e| e.into_inner());
+    while limiter.active >= limiter.max {
+        limiter = RELEASED.wait(limiter).unwrap_or_else(|e| e.into_inner());
+    }
+    limiter.active += 1;
+    Permit(())
+}
+
+// 距上次请求不足 min 时直接返回上次的结果（包括错误），不发请求
+pub fn cached(key: &str, min: Duration, fetch: impl FnOnce() -> Result<String, String>) -> Result<String, String> {
+    if let Ok(cache) = CACHE.lock()
+        && let Some((at, result)) = cache.as_ref().and_then(|c| c.get(key))
+        && at.elapsed() < min
+    {
+        return result.clone();
+    }
+    let result = fetch();
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.get_or_insert_with(HashMap::new).insert(key.to_string(), (Instant::now(), result.clone()));
+    }
+    result
+}
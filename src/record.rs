@@ -0,0 +1,130 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr;
+
+// 将渲染结果转换为 ANSI 文本（SGR 颜色/样式），用于截图和 asciicast
+pub fn buffer_to_ansi(buf: &Buffer) -> String {
+    let area = buf.area;
+    let mut out = String::new();
+    for y in area.top()..area.bottom() {
+        let mut style: Option<(Color, Color, Modifier)> = None;
+        let mut skip = 0;
+        for x in area.left()..area.right() {
+            let cell = buf.get(x, y);
+            // 宽字符之后的占位格不输出
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let current = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(current) {
+                out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                style = Some(current);
+            }
+            out.push_str(cell.symbol());
+            skip = cell.symbol().width().saturating_sub(1);
+        }
+        out.push_str("\x1b[0m");
+        if y + 1 < area.bottom() {
+            out.push_str("\r\n");
+        }
+    }
+    out
+}
+
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(c) = color_code(fg, false) { codes.push(c); }
+    if let Some(c) = color_code(bg, true) { codes.push(c); }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", if background { 48 } else { 38 }, r, g, b)),
+        Color::Indexed(i) => return Some(format!("{};5;{}", if background { 48 } else { 38 }, i)),
+    };
+    Some((if background { base + 10 } else { base }).to_string())
+}
+
+// asciinema v2 录制：每帧内容变化时写入一次完整重绘事件
+pub struct CastRecorder {
+    out: BufWriter<File>,
+    started: Instant,
+    last_frame: String,
+}
+
+impl CastRecorder {
+    pub fn create(path: &Path, width: u16, height: u16) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let header = serde_json::json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": chrono::Utc::now().timestamp(),
+            "title": "termclock",
+        });
+        writeln!(out, "{}", header)?;
+        out.flush()?;
+        Ok(Self { out, started: Instant::now(), last_frame: String::new() })
+    }
+
+    pub fn frame(&mut self, buf: &Buffer) -> io::Result<()> {
+        let frame = buffer_to_ansi(buf);
+        if frame == self.last_frame {
+            return Ok(());
+        }
+        let data = format!("\x1b[H\x1b[2J{}", frame);
+        let event = serde_json::json!([self.started.elapsed().as_secs_f64(), "o", data]);
+        writeln!(self.out, "{}", event)?;
+        self.out.flush()?;
+        self.last_frame = frame;
+        Ok(())
+    }
+}
+
+// 保存当前画面为 ANSI 文本文件（`cat` 即可查看），返回文件路径
+pub fn save_screenshot(buf: &Buffer, dir: &Path) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let name = format!("termclock-{}.ansi", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let path = dir.join(name);
+    let mut content = buffer_to_ansi(buf);
+    content.push('\n');
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+// Redis 数据源：读取字符串键（组件显示）或列表键（组件列表 / 待办），
+// 可订阅频道，收到消息时立即重新读取
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn connect(url: &str) -> Result<redis::Connection, String> {
+    let client = redis::Client::open(url).map_err(|e| e.to_string())?;
+    let con = client.get_connection_with_timeout(TIMEOUT).map_err(|e| e.to_string())?;
+    con.set_read_timeout(Some(TIMEOUT)).map_err(|e| e.to_string())?;
+    Ok(con)
+}
+
+// 字符串为 JSON 时按 JSON 解析，否则作为文本；列表的每一项同样处理
+pub fn read(url: &str, key: &str) -> Result<Value, String> {
+    let mut con = connect(url)?;
+    let kind: String = redis::cmd("TYPE").arg(key).query(&mut con).map_err(|e| e.to_string())?;
+    let parse = |text: String| serde_json::from_str(&text).unwrap_or(Value::String(text));
+    match kind.as_str() {
+        "string" => {
+            let text: String = redis::cmd("GET").arg(key).query(&mut con).map_err(|e| e.to_string())?;
+            Ok(parse(text))
+        }
+        "list" => {
+            let items: Vec<String> = redis::cmd("LRANGE").arg(key).arg(0).arg(-1).query(&mut con).map_err(|e| e.to_string())?;
+            Ok(Value::Array(items.into_iter().map(parse).collect()))
+        }
+        "none" => Err(format!("key {} not found", key)),
+        other => Err(format!("key {} is a {}, expected string or list", key, other)),
+    }
+}
+
+// 待办：列表键从头读取
+pub fn list(url: &str, key: &str, limit: Option<usize>) -> Result<Vec<String>, String> {
+    let mut con = connect(url)?;
+    let end = limit.map_or(-1, |n| n as isize - 1);
+    redis::cmd("LRANGE").arg(key).arg(0).arg(end).query(&mut con).map_err(|e| e.to_string())
+}
+
+// 订阅频道，每条消息或每 refresh 无消息时调用 on_update；返回断开原因
+pub fn subscribe(url: &str, channel: &str, refresh: Duration, mut on_update: impl FnMut()) -> String {
+    let mut con = match connect(url) {
+        Ok(con) => con,
+        Err(e) => return e,
+    };
+    let mut pubsub = con.as_pubsub();
+    if let Err(e) = pubsub.subscribe(channel).and_then(|_| pubsub.set_read_timeout(Some(refresh))) {
+        return e.to_string();
+    }
+    // 订阅成功后先读一次，避免错过订阅前的更新
+    on_update();
+    loop {
+        match pubsub.get_message() {
+            Ok(_) => on_update(),
+            Err(e) if e.is_timeout() => on_update(),
+            Err(e) => return e.to_string(),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime, Timelike};
+use chrono_tz::Tz;
+
+// 简化版 cron 表达式：分 时 日 月 周
+// 支持 `*`、列表 `0,30`、范围 `1-5`、步长 `*/15` / `8-18/2`，周日可写作 0 或 7
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days_of_month: Vec<bool>,
+    months: Vec<bool>,
+    days_of_week: Vec<bool>,
+    dom_any: bool,
+    dow_any: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Option<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return None;
+        }
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        // 7 和 0 都表示周日
+        if days_of_week[7] {
+            days_of_week[0] = true;
+        }
+        days_of_week.truncate(7);
+        Some(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            dom_any: fields[2] == "*",
+            dow_any: fields[4] == "*",
+        })
+    }
+
+    pub fn matches(&self, t: &DateTime<Local>) -> bool {
+        if !self.minutes[t.minute() as usize]
+            || !self.hours[t.hour() as usize]
+            || !self.months[t.month() as usize]
+        {
+            return false;
+        }
+        let dom = self.days_of_month[t.day() as usize];
+        let dow = self.days_of_week[t.weekday().num_days_from_sunday() as usize];
+        // 与标准 cron 一致：日和周都受限时，满足其一即可
+        match (self.dom_any, self.dow_any) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+}
+
+// 解析单个字段，返回下标 0..=max 的命中表
+fn parse_field(field: &str, min: u32, max: u32) -> Option<Vec<bool>> {
+    let mut hits = vec![false; max as usize + 1];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (r, s.parse::<u32>().ok().filter(|s| *s > 0)?),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (a.parse().ok()?, b.parse().ok()?)
+        } else {
+            let v: u32 = range.parse().ok()?;
+            // `5/10` 表示从 5 开始直到上限
+            if part.contains('/') { (v, max) } else { (v, v) }
+        };
+        if start < min || end > max || start > end {
+            return None;
+        }
+        for v in (start..=end).step_by(step as usize) {
+            hits[v as usize] = true;
+        }
+    }
+    Some(hits)
+}
+
+// 工作时间段，例如 09:00-18:00，周一至周五，可指定团队所在时区
+#[derive(Debug, Clone)]
+pub struct WorkHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub days: Vec<bool>,
+    pub tz: Option<Tz>,
+}
+
+impl WorkHours {
+    // range 形如 "09:00-18:00"（支持跨午夜，如 "22:00-06:00"），days 为 cron 周字段，如 "1-5"
+    pub fn parse(range: &str, days: &str, tz: Option<&str>) -> Option<Self> {
+        let (start, end) = range.split_once('-')?;
+        let mut days = parse_field(days.trim(), 0, 7)?;
+        if days[7] {
+            days[0] = true;
+        }
+        days.truncate(7);
+        Some(Self {
+            start: NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?,
+            end: NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?,
+            days,
+            tz: match tz {
+                Some(name) => Some(name.parse().ok()?),
+                None => None,
+            },
+        })
+    }
+
+    pub fn is_working(&self, now: &DateTime<Local>) -> bool {
+        let (time, weekday) = match self.tz {
+            Some(tz) => {
+                let t = now.with_timezone(&tz);
+                (t.time(), t.weekday())
+            }
+            None => (now.time(), now.weekday()),
+        };
+        if self.start <= self.end {
+            self.days[weekday.num_days_from_sunday() as usize] && time >= self.start && time < self.end
+        } else {
+            // 跨午夜：凌晨部分属于前一天的班次
+            let prev = weekday.pred().num_days_from_sunday() as usize;
+            (self.days[weekday.num_days_from_sunday() as usize] && time >= self.start)
+                || (self.days[prev] && time < self.end)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use proptest::prelude::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn hits(field: &str, min: u32, max: u32) -> Vec<u32> {
+        let table = parse_field(field, min, max).unwrap();
+        (min..=max).filter(|&v| table[v as usize]).collect()
+    }
+
+    #[test]
+    fn field_syntax() {
+        assert_eq!(hits("*", 1, 12), (1..=12).collect::<Vec<_>>());
+        assert_eq!(hits("0,30", 0, 59), vec![0, 30]);
+        assert_eq!(hits("1-5", 0, 7), vec![1, 2, 3, 4, 5]);
+        assert_eq!(hits("*/15", 0, 59), vec![0, 15, 30, 45]);
+        assert_eq!(hits("8-18/4", 0, 23), vec![8, 12, 16]);
+        assert_eq!(hits("50/5", 0, 59), vec![50, 55]);
+        assert_eq!(hits("1-3,10,20-21", 1, 31), vec![1, 2, 3, 10, 20, 21]);
+    }
+
+    #[test]
+    fn rejects_invalid_fields() {
+        for expr in [
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * 32 * *",
+            "* * * 0 *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "1-2-3 * * * *",
+            "*/x * * * *",
+            "a * * * *",
+            "1,,2 * * * *",
+            "-1 * * * *",
+            "* * * *",
+            "* * * * * *",
+            "",
+        ] {
+            assert!(CronSchedule::parse(expr).is_none(), "{:?} should be rejected", expr);
+        }
+    }
+
+    #[test]
+    fn matches_times() {
+        let every_quarter = CronSchedule::parse("*/15 9-17 * * *").unwrap();
+        assert!(every_quarter.matches(&at(2025, 6, 2, 9, 45)));
+        assert!(!every_quarter.matches(&at(2025, 6, 2, 9, 46)));
+        assert!(!every_quarter.matches(&at(2025, 6, 2, 18, 0)));
+        let new_year = CronSchedule::parse("0 0 1 1 *").unwrap();
+        assert!(new_year.matches(&at(2026, 1, 1, 0, 0)));
+        assert!(!new_year.matches(&at(2026, 2, 1, 0, 0)));
+    }
+
+    #[test]
+    fn day_of_week() {
+        // 2025-06-01 是周日，2025-06-02 是周一
+        let weekdays = CronSchedule::parse("0 8 * * 1-5").unwrap();
+        assert!(weekdays.matches(&at(2025, 6, 2, 8, 0)));
+        assert!(!weekdays.matches(&at(2025, 6, 1, 8, 0)));
+        for sunday in ["0 8 * * 0", "0 8 * * 7"] {
+            let schedule = CronSchedule::parse(sunday).unwrap();
+            assert!(schedule.matches(&at(2025, 6, 1, 8, 0)), "{}", sunday);
+            assert!(!schedule.matches(&at(2025, 6, 2, 8, 0)), "{}", sunday);
+        }
+        // 日和周都受限时满足其一即可：每月 15 日或每个周一
+        let either = CronSchedule::parse("0 8 15 * 1").unwrap();
+        assert!(either.matches(&at(2025, 6, 15, 8, 0)));
+        assert!(either.matches(&at(2025, 6, 2, 8, 0)));
+        assert!(!either.matches(&at(2025, 6, 3, 8, 0)));
+    }
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(s in "\\PC*") {
+            let _ = CronSchedule::parse(&s);
+        }
+
+        #[test]
+        fn minute_step_matches_multiples(step in 1u32..=60, minute in 0u32..60) {
+            let schedule = CronSchedule::parse(&format!("*/{} * * * *", step)).unwrap();
+            prop_assert_eq!(schedule.matches(&at(2025, 6, 2, 12, minute)), minute % step == 0);
+        }
+    }
+}
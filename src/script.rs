@@ -0,0 +1,147 @@
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::lenient;
+
+// 标准输出和标准错误各最多读取 1 MiB，超出部分丢弃
+pub const MAX_OUTPUT_BYTES: u64 = 1 << 20;
+// 超时上限为 1 小时
+pub const MAX_TIMEOUT_SECS: u64 = 3600;
+
+// 脚本数据源：定期运行用户的可执行文件（任意语言），标准输出为 JSON：
+// {"values": [{"label": "CPU", "value": 42, "color": "yellow"}],
+//  "items": ["deploy at 14:00"],
+//  "alerts": [{"text": "disk almost full", "level": "error"}]}
+// 各字段均可省略，由通用组件显示
+#[derive(Debug, Clone)]
+pub struct ScriptSource {
+    pub name: String,
+    // 通过 sh -c 运行，可带参数
+    pub command: String,
+    pub refresh_secs: u64,
+    pub timeout_secs: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptOutput {
+    pub values: Vec<ScriptValue>,
+    pub items: Vec<String>,
+    pub alerts: Vec<ScriptAlert>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptValue {
+    #[serde(default, deserialize_with = "lenient::string")]
+    pub label: String,
+    // 数字和字符串都可以
+    #[serde(deserialize_with = "lenient::string")]
+    pub value: String,
+    // 颜色名（red、yellow、green、cyan 等），无法识别时使用默认颜色
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptAlert {
+    #[serde(deserialize_with = "lenient::string")]
+    pub text: String,
+    #[serde(default)]
+    pub level: AlertLevel,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertLevel {
+    Info,
+    #[default]
+    #[serde(alias = "warning")]
+    Warn,
+    #[serde(alias = "critical")]
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ScriptState {
+    Loading,
+    Ready(ScriptOutput),
+    Error(String),
+}
+
+pub fn spawn_worker(script: ScriptSource) -> Arc<Mutex<ScriptState>> {
+    let state = Arc::new(Mutex::new(ScriptState::Loading));
+    let shared = state.clone();
+    std::thread::spawn(move || loop {
+        let next = match run(&script) {
+            Ok(output) => ScriptState::Ready(output),
+            Err(e) => ScriptState::Error(e),
+        };
+        if let Ok(mut guard) = shared.lock() {
+            *guard = next;
+        }
+        std::thread::sleep(crate::power::stretch(Duration::from_secs(script.refresh_secs)));
+    });
+    state
+}
+
+// 运行一次脚本并解析输出
+pub fn run(script: &ScriptSource) -> Result<ScriptOutput, String> {
+    let text = run_command(&script.command, &script.name, script.timeout_secs)?;
+    serde_json::from_str(text.trim()).map_err(|e| format!("invalid JSON: {}", e))
+}
+
+// 通过 sh -c 运行命令，返回标准输出；超时则结束进程。
+// 标准输出在单独线程读取，避免管道写满后互相等待；输出超过 MAX_OUTPUT_BYTES 时报错
+pub fn run_command(command: &str, name: &str, timeout_secs: u64) -> Result<String, String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("TERMCLOCK_SOURCE", name)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("{}: {}", command, e))?;
+    let mut stdout = child.stdout.take().ok_or("no stdout")?;
+    let mut stderr = child.stderr.take().ok_or("no stderr")?;
+    let reader = std::thread::spawn(move || read_capped(&mut stdout));
+    let errors = std::thread::spawn(move || read_capped(&mut stderr));
+    let timeout_secs = timeout_secs.min(MAX_TIMEOUT_SECS);
+    let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {}s", timeout_secs));
+            }
+            None => std::thread::sleep(Duration::from_millis(50)),
+        }
+    };
+    let (text, truncated) = reader.join().map_err(|_| "stdout reader panicked")?.map_err(|e| e.to_string())?;
+    if !status.success() {
+        // 显示标准错误的最后一行，方便排查
+        let (stderr, _) = errors.join().ok().and_then(Result::ok).unwrap_or_default();
+        return Err(match stderr.lines().rev().find(|l| !l.trim().is_empty()) {
+            Some(line) => format!("{} ({})", line.trim(), status),
+            None => status.to_string(),
+        });
+    }
+    if truncated {
+        return Err(format!("output larger than {} KiB", MAX_OUTPUT_BYTES / 1024));
+    }
+    Ok(text)
+}
+
+// 最多保留 MAX_OUTPUT_BYTES 字节，其余读出后丢弃，使进程不会因管道写满而阻塞；返回文本和是否被截断
+fn read_capped(pipe: &mut impl Read) -> std::io::Result<(String, bool)> {
+    let mut bytes = Vec::new();
+    pipe.take(MAX_OUTPUT_BYTES).read_to_end(&mut bytes)?;
+    let rest = std::io::copy(pipe, &mut std::io::sink())?;
+    Ok((String::from_utf8_lossy(&bytes).into_owned(), rest > 0))
+}
@@ -0,0 +1,52 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+// 本地传感器（蓝牙温湿度计、串口设备、DHT22 等）的读数，按标签显示在侧栏，不依赖网关或云端
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensorReading {
+    pub label: String,
+    pub temp: Option<f64>,
+    pub humidity: Option<f64>,
+    pub updated: Option<DateTime<Local>>,
+    pub error: Option<String>,
+}
+
+pub type SharedReadings = Arc<Mutex<Vec<SensorReading>>>;
+
+// 超过该秒数未更新的读数变暗
+pub const STALE_AFTER_SECS: i64 = 600;
+
+// 启动时按配置顺序登记，收到第一条数据之前显示为 …
+pub fn register(shared: &SharedReadings, label: &str) {
+    if let Ok(mut readings) = shared.lock()
+        && !readings.iter().any(|r| r.label == label)
+    {
+        readings.push(SensorReading { label: label.to_string(), temp: None, humidity: None, updated: None, error: None });
+    }
+}
+
+pub fn record(shared: &SharedReadings, label: &str, temp: Option<f64>, humidity: Option<f64>) {
+    update(shared, label, |r| {
+        r.temp = temp.or(r.temp);
+        r.humidity = humidity.or(r.humidity);
+        r.updated = Some(Local::now());
+        r.error = None;
+    });
+}
+
+// 保留上次的读数，界面按 updated 判断是否过时
+pub fn record_error(shared: &SharedReadings, label: &str, error: String) {
+    update(shared, label, |r| r.error = Some(error));
+}
+
+fn update(shared: &SharedReadings, label: &str, change: impl FnOnce(&mut SensorReading)) {
+    register(shared, label);
+    if let Ok(mut readings) = shared.lock()
+        && let Some(reading) = readings.iter_mut().find(|r| r.label == label)
+    {
+        change(reading);
+    }
+}
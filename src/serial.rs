@@ -0,0 +1,74 @@
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::sensors::{self, SharedReadings};
+
+// 串口传感器：Arduino 等设备通过 /dev/ttyUSB* 逐行输出读数，后台线程读取，
+// 拔出后定期重连
+
+#[derive(Debug, Clone)]
+pub struct SerialConfig {
+    pub port: String,
+    pub baud: u32,
+    // 命名分组 temp / humidity；没有命名分组时第 1、2 个分组分别为温度和湿度
+    pub pattern: Regex,
+    pub label: String,
+}
+
+// 默认取每行的第一个数字作为温度
+pub const DEFAULT_PATTERN: &str = r"(-?\d+(?:\.\d+)?)";
+pub const DEFAULT_BAUD: u32 = 9600;
+
+// 打开失败或断开后的重连间隔
+const RECONNECT: Duration = Duration::from_secs(5);
+// 读取超时（只用于检测断开，超时后继续等待）
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn spawn_worker(config: SerialConfig, shared: SharedReadings) {
+    sensors::register(&shared, &config.label);
+    std::thread::spawn(move || loop {
+        if let Err(e) = read_port(&config, &shared) {
+            sensors::record_error(&shared, &config.label, format!("{}: {}", config.port, e));
+        }
+        std::thread::sleep(RECONNECT);
+    });
+}
+
+// 读到端口出错（通常是设备被拔出）为止
+fn read_port(config: &SerialConfig, shared: &SharedReadings) -> Result<(), String> {
+    let port = serialport::new(&config.port, config.baud)
+        .timeout(READ_TIMEOUT)
+        .open()
+        .map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(port);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => return Err("device disconnected".to_string()),
+            Ok(_) => {
+                if let Some((temp, humidity)) = parse_line(&config.pattern, &line) {
+                    sensors::record(shared, &config.label, temp, humidity);
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::TimedOut => continue,
+            // 设备重启时的半行乱码
+            Err(e) if e.kind() == ErrorKind::InvalidData => continue,
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+// 不匹配或两个值都无法解析时返回 None
+pub fn parse_line(pattern: &Regex, line: &str) -> Option<(Option<f64>, Option<f64>)> {
+    let caps = pattern.captures(line.trim())?;
+    let named = pattern.capture_names().flatten().any(|n| n == "temp" || n == "humidity");
+    let value = |name: &str, index: usize| {
+        let m = if named { caps.name(name) } else { caps.get(index) };
+        m.and_then(|m| m.as_str().trim().parse::<f64>().ok())
+    };
+    let (temp, humidity) = (value("temp", 1), value("humidity", 2));
+    (temp.is_some() || humidity.is_some()).then_some((temp, humidity))
+}
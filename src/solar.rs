@@ -0,0 +1,40 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+// 日出日落计算（日出方程，精度约 1 分钟），经度东正西负
+
+pub struct SunTimes {
+    pub sunrise: DateTime<Utc>,
+    pub noon: DateTime<Utc>,
+    pub sunset: DateTime<Utc>,
+}
+
+// 儒略日 -> UTC 时间
+fn from_julian(jd: f64) -> Option<DateTime<Utc>> {
+    let millis = ((jd - 2_440_587.5) * 86_400_000.0).round() as i64;
+    Utc.timestamp_millis_opt(millis).single()
+}
+
+// 极昼、极夜时返回 None
+pub fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> Option<SunTimes> {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+    // 当天正午（UTC）的儒略日相对 J2000 的天数
+    let n = (date - epoch).num_days() as f64 + 2_440_588.0 - 2_451_545.0 + 0.0008;
+    let j_star = n - longitude / 360.0;
+    let m = (357.5291 + 0.985_600_28 * j_star).rem_euclid(360.0).to_radians();
+    let c = 1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let lambda = (m.to_degrees() + c + 180.0 + 102.9372).rem_euclid(360.0).to_radians();
+    let transit = 2_451_545.0 + j_star + 0.0053 * m.sin() - 0.0069 * (2.0 * lambda).sin();
+    let declination = (lambda.sin() * 23.4397_f64.to_radians().sin()).asin();
+    let phi = latitude.to_radians();
+    // -0.833°：大气折射与太阳视半径
+    let cos_omega = ((-0.833_f64).to_radians().sin() - phi.sin() * declination.sin()) / (phi.cos() * declination.cos());
+    if !(-1.0..=1.0).contains(&cos_omega) {
+        return None;
+    }
+    let omega = cos_omega.acos().to_degrees();
+    Some(SunTimes {
+        sunrise: from_julian(transit - omega / 360.0)?,
+        noon: from_julian(transit)?,
+        sunset: from_julian(transit + omega / 360.0)?,
+    })
+}
@@ -0,0 +1,271 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::conditional::Validators;
+use crate::jsonpath::{self, JsonPath};
+
+// 自定义数据源：任意 REST 接口或 WebSocket 推送 + JSONPath 提取
+#[derive(Debug, Clone)]
+pub struct CustomSource {
+    pub name: String,
+    pub url: String,
+    pub transport: Transport,
+    pub method: String,
+    pub headers: Vec<(String, String)>,
+    // 请求体模板（WebSocket 为连接后发送的订阅消息），支持 {{now}}、{{date}}、{{timestamp}}、{{env.NAME}}
+    pub body: Option<String>,
+    pub extract: Extract,
+    // 显示格式，{} 替换为提取的值
+    pub format: Option<String>,
+    pub refresh_secs: u64,
+    pub limit: usize,
+    // GraphQL 接口：提取失败时显示响应 errors 中的消息
+    pub graphql: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transport {
+    // 按 refresh_secs 轮询
+    Poll,
+    // 长连接，每条消息更新一次
+    WebSocket,
+    // url 为 redis://；按 refresh_secs 读取键，订阅频道时收到消息立即重新读取
+    Redis { key: String, channel: Option<String> },
+}
+
+#[derive(Debug, Clone)]
+pub enum Extract {
+    Value(JsonPath),
+    List(JsonPath),
+    // 未指定 value / list 的 Redis 键：列表显示为列表，其余显示为单个值
+    Auto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SourceValue {
+    Value(String),
+    List(Vec<String>),
+}
+
+// 后台线程与界面共享的数据源状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SourceState {
+    Loading,
+    Ready(SourceValue),
+    Error(String),
+}
+
+const WS_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+// 启动数据源后台线程：REST 按 refresh_secs 定期请求，WebSocket 和 Redis 订阅断线后退避重连
+pub fn spawn_worker(source: CustomSource) -> Arc<Mutex<SourceState>> {
+    let state = Arc::new(Mutex::new(SourceState::Loading));
+    let shared = state.clone();
+    std::thread::spawn(move || {
+        let set = |s: SourceState| {
+            if let Ok(mut guard) = shared.lock() { *guard = s; }
+        };
+        match &source.transport {
+            Transport::Poll => {
+                let mut validators = Validators::default();
+                loop {
+                    match fetch(&source, &mut validators) {
+                        Ok(Some(value)) => set(SourceState::Ready(value)),
+                        // 304：沿用当前值
+                        Ok(None) => {}
+                        Err(e) => {
+                            validators.clear();
+                            set(SourceState::Error(e));
+                        }
+                    }
+                    std::thread::sleep(crate::power::stretch(Duration::from_secs(source.refresh_secs)));
+                }
+            }
+            Transport::WebSocket => {
+                let mut backoff = Duration::from_secs(1);
+                loop {
+                    let started = std::time::Instant::now();
+                    let err = stream_ws(&source, &set);
+                    // 连接保持过一段时间则视为恢复正常，退避重新计时
+                    if started.elapsed() > WS_BACKOFF_MAX {
+                        backoff = Duration::from_secs(1);
+                    }
+                    set(SourceState::Error(format!("{} (retry in {}s)", err, backoff.as_secs())));
+                    std::thread::sleep(backoff);
+                    backoff = (backoff * 2).min(WS_BACKOFF_MAX);
+                }
+            }
+            Transport::Redis { key, channel: None } => loop {
+                set(read_redis(&source, key));
+                std::thread::sleep(crate::power::stretch(Duration::from_secs(source.refresh_secs)));
+            },
+            Transport::Redis { key, channel: Some(channel) } => {
+                let mut backoff = Duration::from_secs(1);
+                loop {
+                    let started = std::time::Instant::now();
+                    let refresh = Duration::from_secs(source.refresh_secs);
+                    let err = crate::redis_source::subscribe(&source.url, channel, refresh, || set(read_redis(&source, key)));
+                    if started.elapsed() > WS_BACKOFF_MAX {
+                        backoff = Duration::from_secs(1);
+                    }
+                    set(SourceState::Error(format!("{} (retry in {}s)", err, backoff.as_secs())));
+                    std::thread::sleep(backoff);
+                    backoff = (backoff * 2).min(WS_BACKOFF_MAX);
+                }
+            }
+        }
+    });
+    state
+}
+
+fn read_redis(source: &CustomSource, key: &str) -> SourceState {
+    match crate::redis_source::read(&source.url, key).and_then(|json| extract(source, &json)) {
+        Ok(value) => SourceState::Ready(value),
+        Err(e) => SourceState::Error(e),
+    }
+}
+
+// 保持 WebSocket 连接并持续更新状态，返回断开原因
+fn stream_ws(source: &CustomSource, set: &impl Fn(SourceState)) -> String {
+    use tungstenite::client::IntoClientRequest;
+    use tungstenite::Message;
+
+    let mut request = match source.url.as_str().into_client_request() {
+        Ok(r) => r,
+        Err(e) => return e.to_string(),
+    };
+    for (name, value) in &source.headers {
+        if let (Ok(name), Ok(value)) = (
+            tungstenite::http::HeaderName::from_bytes(name.as_bytes()),
+            tungstenite::http::HeaderValue::from_str(value),
+        ) {
+            request.headers_mut().insert(name, value);
+        }
+    }
+    let (mut socket, _) = match tungstenite::connect(request) {
+        Ok(conn) => conn,
+        Err(e) => return e.to_string(),
+    };
+    if let Some(template) = &source.body
+        && let Err(e) = socket.send(Message::text(render_template(template)))
+    {
+        return e.to_string();
+    }
+    loop {
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text.to_string(),
+            Ok(Message::Binary(data)) => String::from_utf8_lossy(&data).into_owned(),
+            Ok(Message::Close(_)) => return "connection closed".to_string(),
+            Ok(_) => continue,
+            Err(e) => return e.to_string(),
+        };
+        // 非 JSON 消息按纯文本处理
+        let json = serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+        // 不匹配的消息（如心跳）不覆盖当前值
+        match extract(source, &json) {
+            Ok(SourceValue::List(items)) if items.is_empty() => {}
+            Ok(value) => set(SourceState::Ready(value)),
+            Err(_) => {}
+        }
+    }
+}
+
+// GET 请求使用条件请求，返回 Ok(None) 表示内容没有变化
+pub fn fetch(source: &CustomSource, validators: &mut Validators) -> Result<Option<SourceValue>, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let method = reqwest::Method::from_bytes(source.method.to_uppercase().as_bytes())
+        .map_err(|_| format!("invalid method {}", source.method))?;
+    let conditional = method == reqwest::Method::GET;
+    let mut request = client.request(method, &source.url);
+    for (name, value) in &source.headers {
+        request = request.header(name, value);
+    }
+    if let Some(template) = &source.body {
+        request = request.body(render_template(template));
+        if !source.headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-type")) {
+            request = request.header("Content-Type", "application/json");
+        }
+    }
+    let _permit = crate::ratelimit::permit();
+    let response = match conditional {
+        true => validators.send(request),
+        false => request.send().and_then(|r| r.error_for_status()).map(Some),
+    };
+    let Some(response) = response.map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let json: serde_json::Value = response.json().map_err(|e| format!("invalid JSON: {}", e))?;
+    match extract(source, &json) {
+        // GraphQL 出错时仍返回 200，data 为 null 或缺少字段
+        Err(e) if source.graphql => Err(graphql_error(&json).unwrap_or(e)),
+        result => result.map(Some),
+    }
+}
+
+fn graphql_error(json: &serde_json::Value) -> Option<String> {
+    let errors = json.get("errors")?.as_array()?;
+    let messages: Vec<&str> = errors.iter().filter_map(|e| e.get("message")?.as_str()).collect();
+    (!messages.is_empty()).then(|| messages.join("; "))
+}
+
+fn extract(source: &CustomSource, json: &serde_json::Value) -> Result<SourceValue, String> {
+    let format = |v: &serde_json::Value| {
+        let text = jsonpath::display(v);
+        match &source.format {
+            Some(fmt) => fmt.replace("{}", &text),
+            None => text,
+        }
+    };
+    match &source.extract {
+        Extract::Value(path) => path
+            .select(json)
+            .first()
+            .map(|v| SourceValue::Value(format(v)))
+            .ok_or_else(|| "no match".to_string()),
+        Extract::List(path) => Ok(SourceValue::List(
+            path.select(json).into_iter().take(source.limit).map(format).collect(),
+        )),
+        Extract::Auto => Ok(match json.as_array() {
+            Some(items) => SourceValue::List(items.iter().take(source.limit).map(format).collect()),
+            None => SourceValue::Value(format(json)),
+        }),
+    }
+}
+
+// 环境变量只替换一遍，值中的 {{env.X}} 原样保留，不会递归展开
+pub fn render_template(template: &str) -> String {
+    let now = chrono::Local::now();
+    let out = template
+        .replace("{{now}}", &now.to_rfc3339())
+        .replace("{{date}}", &now.format("%Y-%m-%d").to_string())
+        .replace("{{timestamp}}", &now.timestamp().to_string());
+    let mut rendered = String::with_capacity(out.len());
+    let mut rest = out.as_str();
+    while let Some(start) = rest.find("{{env.")
+        && let Some(len) = rest[start..].find("}}")
+    {
+        rendered.push_str(&rest[..start]);
+        rendered.push_str(&std::env::var(&rest[start + 6..start + len]).unwrap_or_default());
+        rest = &rest[start + len + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_values_are_not_expanded_again() {
+        // 只在本测试中使用的变量名，其他线程不会读取
+        unsafe { std::env::set_var("TERMCLOCK_TEST_LOOP", "a{{env.TERMCLOCK_TEST_LOOP}}") };
+        assert_eq!(render_template("x {{env.TERMCLOCK_TEST_LOOP}} y"), "x a{{env.TERMCLOCK_TEST_LOOP}} y");
+        assert_eq!(render_template("{{env.TERMCLOCK_TEST_UNSET}}|{{env.}}|{{env.open"), "||{{env.open");
+    }
+}
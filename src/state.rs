@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+// 提供者结果未变化时，至少间隔这么久才重写状态文件
+const REWRITE_SECS: i64 = 60;
+
+// 运行时状态：在界面中切换的设置（如主题），重启后保留
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    pub theme: Option<String>,
+    // 时钟显示的时区（:tz 命令）
+    pub tz: Option<String>,
+    // 当天温度范围
+    pub temp_range: Option<crate::temperature::DailyRange>,
+    // 当天湿度范围
+    pub humidity_range: Option<crate::temperature::DailyRange>,
+    // 各数据提供者（传感器 API、wttr.in、降水预报）最近一次成功的结果，按提供者名称
+    pub providers: BTreeMap<String, CachedResult>,
+    // 运行中的倒计时（到期时刻为绝对时间），重启后恢复
+    pub timer: Option<SavedTimer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedTimer {
+    pub until: DateTime<Local>,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResult {
+    pub at: DateTime<Local>,
+    pub value: serde_json::Value,
+}
+
+// 默认状态文件：~/.local/state/termclock/state.json
+pub fn default_state_path() -> PathBuf {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| Path::new(&h).join(".local").join("state")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    base.join("termclock").join("state.json")
+}
+
+// 文件不存在或损坏时使用默认状态
+pub fn load(path: &Path) -> State {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(path: &Path, state: &State) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let content = serde_json::to_string_pretty(state).map_err(io::Error::other)?;
+    crate::todo_store::write_atomic(path, &content)
+}
+
+// 读取-修改-写回，保留其他字段
+pub fn update(path: &Path, change: impl FnOnce(&mut State)) -> io::Result<()> {
+    let mut state = load(path);
+    change(&mut state);
+    save(path, &state)
+}
+
+// 提供者缓存中不超过 max_age 的结果及其时长
+pub fn cached_result<T: DeserializeOwned>(path: &Path, provider: &str, max_age: Duration) -> Option<(T, Duration)> {
+    let state = load(path);
+    let entry = state.providers.get(provider)?;
+    let age = (Local::now() - entry.at).to_std().ok().filter(|age| *age <= max_age)?;
+    Some((serde_json::from_value(entry.value.clone()).ok()?, age))
+}
+
+// 记录提供者的成功结果；结果未变化且上次记录不久时不写文件，避免频繁写盘
+pub fn store_result<T: Serialize>(path: &Path, provider: &str, value: &T) {
+    let Ok(value) = serde_json::to_value(value) else { return };
+    let mut state = load(path);
+    let now = Local::now();
+    if let Some(entry) = state.providers.get(provider)
+        && entry.value == value
+        && (now - entry.at).num_seconds() < REWRITE_SECS
+    {
+        return;
+    }
+    state.providers.insert(provider.to_string(), CachedResult { at: now, value });
+    let _ = save(path, &state);
+}
@@ -0,0 +1,81 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crossterm::event::DisableMouseCapture;
+use crossterm::execute;
+use crossterm::terminal::{LeaveAlternateScreen, disable_raw_mode};
+
+// --supervise：父进程不打开界面，只运行 TUI 子进程，子进程崩溃（panic、被信号终止）后自动重启，
+// 适合开机自动启动、无人值守的墙面显示屏。子进程以其他方式退出（按 q、参数错误、锁被占用等）时父进程随之退出
+const MIN_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+// 子进程运行超过这么久再崩溃时，重启等待时间回到 MIN_DELAY
+const STABLE_AFTER: Duration = Duration::from_secs(300);
+// Rust 在主线程 panic 时的退出码
+const PANIC_EXIT: i32 = 101;
+
+// 崩溃日志：状态文件所在目录的 crash.log
+pub fn crash_log_path(state_file: &Path) -> PathBuf {
+    state_file.with_file_name("crash.log")
+}
+
+pub fn run(crash_log: &Path) -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let args: Vec<String> = std::env::args().skip(1).filter(|arg| arg != "--supervise").collect();
+    let mut delay = MIN_DELAY;
+    loop {
+        let started = Instant::now();
+        let status = Command::new(&exe).args(&args).status()?;
+        // 只在 panic 或被信号终止时重启；其他非 0 退出码是重启也无法恢复的错误
+        if let Some(code) = status.code().filter(|&code| code != PANIC_EXIT) {
+            if code != 0 {
+                log_crash(crash_log, &format!("termclock exited ({}), not restarting", status));
+            }
+            std::process::exit(code);
+        }
+        // 被信号终止时子进程来不及恢复终端，由父进程恢复
+        restore_terminal();
+        if started.elapsed() >= STABLE_AFTER {
+            delay = MIN_DELAY;
+        }
+        log_crash(crash_log, &format!("termclock exited ({}) after {}s, restarting in {}s", status, started.elapsed().as_secs(), delay.as_secs()));
+        eprintln!("termclock exited ({}), restarting in {}s; see {}", status, delay.as_secs(), crash_log.display());
+        std::thread::sleep(delay);
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
+
+// 崩溃时先恢复终端（退出备用屏幕、关闭原始模式），再把错误和调用栈追加到 crash.log。
+// 后台线程的 panic 只记录日志，不打断仍在运行的界面
+pub fn install_panic_hook(crash_log: PathBuf, tui: bool) {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let name = thread.name().unwrap_or("unnamed");
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        log_crash(&crash_log, &format!("thread '{}' {}\n{}", name, info, backtrace));
+        if name != "main" && tui {
+            return;
+        }
+        if tui {
+            restore_terminal();
+        }
+        default(info);
+    }));
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show);
+}
+
+fn log_crash(path: &Path, message: &str) {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{} {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), message);
+    }
+}
@@ -0,0 +1,67 @@
+// systemd 集成（需启用 `systemd` feature）：就绪通知、看门狗、SIGHUP 重载配置。
+// 未启用 feature 时为空实现，调用方无需区分。
+
+#[cfg(feature = "systemd")]
+mod imp {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, Instant};
+
+    use sd_notify::NotifyState;
+
+    pub struct Systemd {
+        reload: Arc<AtomicBool>,
+        watchdog_interval: Option<Duration>,
+        last_ping: Instant,
+    }
+
+    impl Systemd {
+        pub fn init() -> Self {
+            let reload = Arc::new(AtomicBool::new(false));
+            let _ = signal_hook::flag::register(signal_hook::consts::SIGHUP, reload.clone());
+            let mut usec = 0;
+            // 按看门狗超时的一半发送心跳
+            let watchdog_interval = sd_notify::watchdog_enabled(false, &mut usec)
+                .then(|| Duration::from_micros(usec / 2));
+            Self { reload, watchdog_interval, last_ping: Instant::now() }
+        }
+
+        pub fn notify_ready(&self) {
+            let _ = sd_notify::notify(false, &[NotifyState::Ready]);
+        }
+
+        // 主循环每次调用：按需发送看门狗心跳，返回是否收到 SIGHUP
+        pub fn tick(&mut self) -> bool {
+            if let Some(interval) = self.watchdog_interval
+                && self.last_ping.elapsed() >= interval
+            {
+                let _ = sd_notify::notify(false, &[NotifyState::Watchdog]);
+                self.last_ping = Instant::now();
+            }
+            self.reload.swap(false, Ordering::Relaxed)
+        }
+
+        pub fn notify_reloaded(&self) {
+            let _ = sd_notify::notify(false, &[NotifyState::Ready]);
+        }
+
+        pub fn notify_stopping(&self) {
+            let _ = sd_notify::notify(false, &[NotifyState::Stopping]);
+        }
+    }
+}
+
+#[cfg(not(feature = "systemd"))]
+mod imp {
+    pub struct Systemd;
+
+    impl Systemd {
+        pub fn init() -> Self { Systemd }
+        pub fn notify_ready(&self) {}
+        pub fn tick(&mut self) -> bool { false }
+        pub fn notify_reloaded(&self) {}
+        pub fn notify_stopping(&self) {}
+    }
+}
+
+pub use imp::Systemd;
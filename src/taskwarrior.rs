@@ -0,0 +1,118 @@
+use std::process::Command;
+
+use serde::Deserialize;
+
+use crate::todo::TodoItem;
+
+// Taskwarrior 待办：通过 `task` 命令读写，显示格式与其他后端一致（"截止时间 | !优先级 任务"）
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Task {
+    uuid: String,
+    description: String,
+    due: Option<String>,
+    priority: Option<String>,
+}
+
+impl Task {
+    fn display(&self) -> String {
+        let priority = match self.priority.as_deref() {
+            Some("H") => "!1 ",
+            Some("M") => "!2 ",
+            Some("L") => "!3 ",
+            _ => "",
+        };
+        // 导出时间为 UTC：20241018T160000Z
+        let due = self.due.as_deref().and_then(|d| chrono::NaiveDateTime::parse_from_str(d, "%Y%m%dT%H%M%SZ").ok());
+        match due {
+            Some(due) => {
+                let local = due.and_utc().with_timezone(&chrono::Local);
+                format!("{} | {}{}", local.format("%Y-%m-%d %H:%M"), priority, self.description)
+            }
+            None => format!("{}{}", priority, self.description),
+        }
+    }
+}
+
+fn task(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("task")
+        .args(["rc.confirmation=off", "rc.verbose=nothing", "rc.hooks=off"])
+        .args(args)
+        .output()
+        .map_err(|e| format!("task: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("task: {}", stderr.lines().next().unwrap_or("failed")));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// status: status:pending / status:completed
+fn export(filter: &[String], status: &str) -> Result<Vec<Task>, String> {
+    let mut args: Vec<&str> = filter.iter().map(String::as_str).collect();
+    args.extend([status, "export"]);
+    serde_json::from_str(&task(&args)?).map_err(|e| format!("task export: {}", e))
+}
+
+pub fn pending(filter: &[String], limit: Option<usize>) -> Result<Vec<String>, String> {
+    Ok(export(filter, "status:pending")?.iter().take(limit.unwrap_or(usize::MAX)).map(Task::display).collect())
+}
+
+pub fn completed(filter: &[String]) -> Result<Vec<String>, String> {
+    Ok(export(filter, "status:completed")?.iter().map(Task::display).collect())
+}
+
+// "2024-10-18 | !1 写周报" -> task add due:2024-10-18 priority:H -- 写周报
+pub fn add(text: &str) -> Result<(), String> {
+    create("add", text)
+}
+
+// 直接记录一条已完成的任务（task log），用于导入
+pub fn log(text: &str) -> Result<(), String> {
+    create("log", text)
+}
+
+fn create(command: &str, text: &str) -> Result<(), String> {
+    let item = TodoItem::parse(text);
+    let mut args = vec![command.to_string()];
+    if let Some(due) = item.due_date(chrono::Local::now().date_naive()) {
+        args.push(format!("due:{}", due.format("%Y-%m-%d")));
+    }
+    if let Some(p) = item.priority.and_then(|p| ["H", "M", "L"].get(p as usize - 1)) {
+        args.push(format!("priority:{}", p));
+    }
+    args.push("--".to_string());
+    args.push(item.task.to_string());
+    task(&args.iter().map(String::as_str).collect::<Vec<_>>()).map(|_| ())
+}
+
+pub fn complete(filter: &[String], index: usize, expected: Option<&str>) -> Result<Option<String>, String> {
+    modify(filter, index, expected, "done")
+}
+
+pub fn remove(filter: &[String], index: usize, expected: Option<&str>) -> Result<Option<String>, String> {
+    modify(filter, index, expected, "delete")
+}
+
+fn modify(filter: &[String], index: usize, expected: Option<&str>, command: &str) -> Result<Option<String>, String> {
+    let tasks = export(filter, "status:pending")?;
+    let found = match expected {
+        Some(text) => tasks.iter().find(|t| t.display() == text),
+        None => tasks.get(index),
+    };
+    let Some(found) = found else { return Ok(None) };
+    task(&[&found.uuid, command])?;
+    Ok(Some(found.display()))
+}
+
+// 今日完成数与剩余数
+pub fn summary(filter: &[String]) -> Result<(usize, usize), String> {
+    let count = |extra: &[&str]| -> Result<usize, String> {
+        let mut args: Vec<&str> = filter.iter().map(String::as_str).collect();
+        args.extend(extra);
+        args.push("count");
+        task(&args)?.trim().parse().map_err(|_| "task count: unexpected output".to_string())
+    };
+    Ok((count(&["status:completed", "end.after:today"])?, count(&["status:pending"])?))
+}
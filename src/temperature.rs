@@ -0,0 +1,149 @@
+// 温度字符串解析，兼容 API、wttr.in 等来源的写法：
+// "29℃"、"24.5°C"、"-3°C"、"+29°C"、"−3 °C"（Unicode 减号）、"24,5℃"（逗号小数点），
+// 华氏度（"77°F"）和开尔文（"296.15K"）换算为摄氏度
+pub fn parse_celsius(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let split = s
+        .char_indices()
+        .find(|&(_, c)| !matches!(c, '0'..='9' | '+' | '-' | '−' | '.' | ','))
+        .map_or(s.len(), |(i, _)| i);
+    let value = parse_number(&s[..split])?;
+    let unit: String = s[split..].chars().filter(|c| !c.is_whitespace() && *c != '°').collect();
+    let celsius = match unit.to_lowercase().as_str() {
+        "" | "c" | "℃" => value,
+        "f" | "℉" => (value - 32.0) * 5.0 / 9.0,
+        "k" => value - 273.15,
+        _ => return None,
+    };
+    Some(celsius)
+}
+
+// 四舍五入到整数摄氏度（温度计显示）
+pub fn parse_celsius_rounded(s: &str) -> Option<i32> {
+    parse_celsius(s).map(|c| c.round() as i32)
+}
+
+// 可带正负号的十进制数，小数点可以是 . 或 ,
+fn parse_number(s: &str) -> Option<f64> {
+    let (sign, digits) = match s.chars().next()? {
+        '-' | '−' => (-1.0, &s[s.chars().next()?.len_utf8()..]),
+        '+' => (1.0, &s[1..]),
+        _ => (1.0, s),
+    };
+    let digits = digits.replacen(',', ".", 1);
+    let (int, frac) = digits.split_once('.').unwrap_or((&digits, ""));
+    let all_digits = |p: &str| p.chars().all(|c| c.is_ascii_digit());
+    if (int.is_empty() && frac.is_empty()) || !all_digits(int) || !all_digits(frac) {
+        return None;
+    }
+    digits.parse::<f64>().ok().filter(|v| v.is_finite()).map(|v| sign * v)
+}
+
+// 当天的最低、最高温度（跨天时重新开始），保存在状态文件中
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DailyRange {
+    pub date: chrono::NaiveDate,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl DailyRange {
+    // 记录一个读数，范围有变化时返回 true
+    pub fn record(range: &mut Option<DailyRange>, date: chrono::NaiveDate, value: f64) -> bool {
+        let next = match *range {
+            Some(r) if r.date == date => DailyRange { date, min: r.min.min(value), max: r.max.max(value) },
+            _ => DailyRange { date, min: value, max: value },
+        };
+        let changed = *range != Some(next);
+        *range = Some(next);
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn known_formats() {
+        assert_eq!(parse_celsius("29℃"), Some(29.0));
+        assert_eq!(parse_celsius("24.5°C"), Some(24.5));
+        assert_eq!(parse_celsius("-3°C"), Some(-3.0));
+        assert_eq!(parse_celsius("+29°C"), Some(29.0));
+        assert_eq!(parse_celsius("−3 °C"), Some(-3.0));
+        assert_eq!(parse_celsius("24,5℃"), Some(24.5));
+        assert_eq!(parse_celsius(" 21 "), Some(21.0));
+        assert!(close(parse_celsius("77°F").unwrap(), 25.0));
+        assert!(close(parse_celsius("296.15K").unwrap(), 23.0));
+        assert_eq!(parse_celsius_rounded("-0.4°C"), Some(0));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for s in ["", "--", "°C", "abc", "1.2.3", "NaN", "inf", "1e3", "12 apples", "+-3", "3-"] {
+            assert_eq!(parse_celsius(s), None, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn daily_range_resets_on_new_day() {
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+        let mut range = None;
+        assert!(DailyRange::record(&mut range, day(1), 10.0));
+        assert!(DailyRange::record(&mut range, day(1), 4.0));
+        assert!(!DailyRange::record(&mut range, day(1), 7.0));
+        assert_eq!(range, Some(DailyRange { date: day(1), min: 4.0, max: 10.0 }));
+        assert!(DailyRange::record(&mut range, day(2), 7.0));
+        assert_eq!(range, Some(DailyRange { date: day(2), min: 7.0, max: 7.0 }));
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(s in "\\PC*") {
+            if let Some(v) = parse_celsius(&s) {
+                prop_assert!(v.is_finite());
+            }
+        }
+
+        #[test]
+        fn celsius_suffixes(v in -100.0f64..100.0, suffix in prop::sample::select(vec!["", "℃", "°C", " °C", "C", "°", " c"])) {
+            let parsed = parse_celsius(&format!("{:.1}{}", v, suffix)).unwrap();
+            prop_assert!(close(parsed, (v * 10.0).round() / 10.0));
+        }
+
+        #[test]
+        fn explicit_sign(v in 0.0f64..100.0) {
+            let text = format!("{:.2}", v);
+            let plain = parse_celsius(&text).unwrap();
+            prop_assert_eq!(parse_celsius(&format!("+{}°C", text)), Some(plain));
+            prop_assert_eq!(parse_celsius(&format!("-{}°C", text)), Some(-plain));
+            prop_assert_eq!(parse_celsius(&format!("−{}°C", text)), Some(-plain));
+        }
+
+        #[test]
+        fn comma_decimal(v in -100.0f64..100.0) {
+            let text = format!("{:.1}℃", v);
+            prop_assert_eq!(parse_celsius(&text.replace('.', ",")), parse_celsius(&text));
+        }
+
+        #[test]
+        fn fahrenheit_and_kelvin(v in -100.0f64..100.0) {
+            let f = parse_celsius(&format!("{:.3}°F", v * 9.0 / 5.0 + 32.0)).unwrap();
+            let k = parse_celsius(&format!("{:.3}K", v + 273.15)).unwrap();
+            prop_assert!((f - v).abs() < 1e-2);
+            prop_assert!((k - v).abs() < 1e-2);
+        }
+
+        #[test]
+        fn rounds_to_nearest(v in -100.0f64..100.0) {
+            let text = format!("{:.1}°C", v);
+            let expected = parse_celsius(&text).unwrap().round() as i32;
+            prop_assert_eq!(parse_celsius_rounded(&text), Some(expected));
+        }
+    }
+}
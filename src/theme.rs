@@ -0,0 +1,113 @@
+use ratatui::style::Color;
+
+use crate::weather::Condition;
+
+// 配色主题：t 键在主题间循环切换，未设置的颜色沿用配置文件中的颜色
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub time_color: Option<Color>,
+    pub date_color: Option<Color>,
+    pub todos_color: Option<Color>,
+    // 状态颜色；未设置时使用默认配色
+    pub palette: Option<Palette>,
+}
+
+impl Theme {
+    fn builtin(name: &str, time: Color, date: Color, todos: Color) -> Self {
+        Self { name: name.to_string(), time_color: Some(time), date_color: Some(date), todos_color: Some(todos), palette: None }
+    }
+
+    fn with_palette(self, palette: Palette) -> Self {
+        Self { palette: Some(palette), ..self }
+    }
+}
+
+// 语义颜色：界面上表示状态的颜色（错误、逾期、警告、正常、过期数据、提示）都从这里取，
+// 色盲友好主题只需替换这一组颜色
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    // 错误、逾期待办、闹钟响铃、即将开始的会议
+    pub alert: Color,
+    // 请求失败、离线、贪睡、下一个时刻
+    pub warn: Color,
+    // 正常状态（工作时间、完成统计、舒适湿度）
+    pub ok: Color,
+    // 过期或不活跃的数据
+    pub stale: Color,
+    // 提示信息（推送消息、降水提醒、发车时间）
+    pub info: Color,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self { alert: Color::LightRed, warn: Color::Yellow, ok: Color::Green, stale: Color::DarkGray, info: Color::Cyan }
+    }
+}
+
+// 天气强调色：按当前天气状况和温度给温度计和强调线着色（下雨蓝、下雪白、高温橙）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherAccents {
+    pub rain: Color,
+    pub snow: Color,
+    pub hot: Color,
+    // 温度（℃）达到该值时为高温
+    pub hot_above: f64,
+}
+
+impl Default for WeatherAccents {
+    fn default() -> Self {
+        Self { rain: Color::LightBlue, snow: Color::White, hot: Color::Rgb(255, 165, 0), hot_above: 30.0 }
+    }
+}
+
+// 当前的强调色和说明：雪优先于雨（含雷暴），其次是高温；其他天气不着色
+pub fn weather_accent(accents: &WeatherAccents, condition: Option<Condition>, celsius: Option<f64>) -> Option<(Color, &'static str)> {
+    match condition {
+        Some(Condition::Snow) => Some((accents.snow, "❄ snow")),
+        Some(Condition::Rain) => Some((accents.rain, "☂ rain")),
+        Some(Condition::Thunder) => Some((accents.rain, "☂ thunderstorm")),
+        _ if celsius.is_some_and(|c| c >= accents.hot_above) => Some((accents.hot, "☀ hot")),
+        _ => None,
+    }
+}
+
+// Okabe-Ito 色盲安全配色：用橙 / 蓝代替红 / 绿
+const DEUTERANOPIA: Palette = Palette {
+    alert: Color::Rgb(213, 94, 0),
+    warn: Color::Rgb(240, 228, 66),
+    ok: Color::Rgb(0, 114, 178),
+    stale: Color::Rgb(120, 120, 120),
+    info: Color::Rgb(86, 180, 233),
+};
+// 红色在红色盲下偏暗，警告色改用更亮的橙色
+const PROTANOPIA: Palette = Palette {
+    alert: Color::Rgb(230, 159, 0),
+    warn: Color::Rgb(240, 228, 66),
+    ok: Color::Rgb(0, 114, 178),
+    stale: Color::Rgb(140, 140, 140),
+    info: Color::Rgb(86, 180, 233),
+};
+// 高对比度：只用黑白，状态靠文字和符号区分
+const HIGH_CONTRAST: Palette = Palette { alert: Color::White, warn: Color::White, ok: Color::White, stale: Color::Gray, info: Color::White };
+
+// 未配置 themes 时的内置主题
+pub fn builtin_themes() -> Vec<Theme> {
+    vec![
+        Theme::builtin("nord", Color::Rgb(136, 192, 208), Color::Rgb(235, 203, 139), Color::Rgb(216, 222, 233)),
+        Theme::builtin("solarized", Color::Rgb(38, 139, 210), Color::Rgb(181, 137, 0), Color::Rgb(147, 161, 161)),
+        Theme::builtin("gruvbox", Color::Rgb(250, 189, 47), Color::Rgb(254, 128, 25), Color::Rgb(235, 219, 178)),
+        Theme::builtin("dracula", Color::Rgb(189, 147, 249), Color::Rgb(255, 121, 198), Color::Rgb(248, 248, 242)),
+        Theme::builtin("amber", Color::Rgb(255, 176, 0), Color::Rgb(204, 140, 0), Color::Rgb(255, 176, 0)),
+        Theme::builtin("phosphor", Color::Rgb(51, 255, 51), Color::Rgb(34, 170, 34), Color::Rgb(51, 255, 51)),
+        Theme::builtin("mono", Color::White, Color::Gray, Color::Gray),
+        Theme::builtin("deuteranopia", Color::Rgb(86, 180, 233), Color::Rgb(230, 159, 0), Color::White).with_palette(DEUTERANOPIA),
+        Theme::builtin("protanopia", Color::Rgb(86, 180, 233), Color::Rgb(240, 228, 66), Color::White).with_palette(PROTANOPIA),
+        Theme::builtin("high-contrast", Color::White, Color::White, Color::White).with_palette(HIGH_CONTRAST),
+    ]
+}
+
+// 按名称查找主题：先查配置的主题，再查内置主题（配置了 themes 时内置主题仍可用 theme: 选择）
+pub fn find(themes: &[Theme], name: &str) -> Option<Theme> {
+    themes.iter().find(|t| t.name == name).cloned().or_else(|| builtin_themes().into_iter().find(|t| t.name == name))
+}
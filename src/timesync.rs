@@ -0,0 +1,49 @@
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// 系统时间同步状态（Linux）：读取 timedatectl（systemd-timesyncd 等）或 chrony 报告的同步状态。
+// 时钟未同步时界面上的时间可能是错的却没有任何提示，这是信息屏最糟糕的故障
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// 是否已同步；没有可用的工具（非 Linux、容器中没有 systemd）时为 None
+pub fn check() -> Option<(bool, &'static str)> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    timedatectl().map(|synced| (synced, "timedatectl")).or_else(|| chrony().map(|synced| (synced, "chrony")))
+}
+
+pub fn spawn_worker() -> Arc<Mutex<Option<bool>>> {
+    let state = Arc::new(Mutex::new(None));
+    let shared = state.clone();
+    std::thread::spawn(move || loop {
+        let synced = check().map(|(synced, _)| synced);
+        if let Ok(mut guard) = shared.lock() {
+            *guard = synced;
+        }
+        std::thread::sleep(crate::power::stretch(CHECK_INTERVAL));
+    });
+    state
+}
+
+// timedatectl show -p NTPSynchronized --value -> yes / no
+fn timedatectl() -> Option<bool> {
+    let output = Command::new("timedatectl").args(["show", "-p", "NTPSynchronized", "--value"]).output().ok()?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "yes" if output.status.success() => Some(true),
+        "no" if output.status.success() => Some(false),
+        _ => None,
+    }
+}
+
+// chronyc tracking 中的 "Leap status : Normal"；未同步时为 "Not synchronised"
+fn chrony() -> Option<bool> {
+    let output = Command::new("chronyc").args(["-n", "tracking"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let leap = text.lines().find_map(|line| line.strip_prefix("Leap status")?.split_once(':').map(|(_, v)| v.trim().to_string()))?;
+    Some(leap != "Not synchronised")
+}
@@ -0,0 +1,162 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use ratatui::style::Color;
+
+// 解析后的待办：API 格式为 "截止时间 | 任务"，任务可带优先级前缀 !1 / !2 / !3 或 (A) / (B) / (C)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoItem<'a> {
+    pub priority: Option<u8>,
+    pub deadline: Option<&'a str>,
+    pub task: &'a str,
+}
+
+impl<'a> TodoItem<'a> {
+    pub fn parse(raw: &'a str) -> Self {
+        let (deadline, task) = match raw.split_once(" | ") {
+            Some((deadline, task)) => (Some(deadline.trim()).filter(|d| !d.is_empty()), task.trim()),
+            None => (None, raw.trim()),
+        };
+        let (priority, task) = parse_priority(task);
+        Self { priority, deadline, task }
+    }
+
+    // 截止日期：取截止时间的日期部分，支持 2024-10-18、2024/10/18、10-18（当年）
+    pub fn due_date(&self, today: NaiveDate) -> Option<NaiveDate> {
+        let date = self.deadline?.split([' ', 'T']).next()?;
+        ["%Y-%m-%d", "%Y/%m/%d"]
+            .iter()
+            .find_map(|fmt| NaiveDate::parse_from_str(date, fmt).ok())
+            .or_else(|| {
+                let (m, d) = date.split_once(['-', '/'])?;
+                NaiveDate::from_ymd_opt(today.year(), m.parse().ok()?, d.parse().ok()?)
+            })
+    }
+
+    // 截止时间：带时间的按时间，只有日期的按当天结束计
+    pub fn due_at(&self, today: NaiveDate) -> Option<NaiveDateTime> {
+        let deadline = self.deadline?.replace('/', "-");
+        ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+            .iter()
+            .find_map(|fmt| NaiveDateTime::parse_from_str(&deadline, fmt).ok())
+            .or_else(|| Some(self.due_date(today)?.and_time(NaiveTime::from_hms_opt(23, 59, 59)?)))
+    }
+
+    pub fn group(&self, today: NaiveDate) -> DueGroup {
+        match self.due_date(today) {
+            None => DueGroup::NoDate,
+            Some(d) if d < today => DueGroup::Overdue,
+            Some(d) if d == today => DueGroup::Today,
+            Some(d) if today.succ_opt() == Some(d) => DueGroup::Tomorrow,
+            Some(_) => DueGroup::Later,
+        }
+    }
+
+    // 显示文本（去掉优先级前缀）
+    pub fn display(&self) -> String {
+        match self.deadline {
+            Some(deadline) => format!("{} | {}", deadline, self.task),
+            None => self.task.to_string(),
+        }
+    }
+}
+
+// "!1 写周报" -> (1, "写周报")；"(B) 写周报" -> (2, "写周报")
+fn parse_priority(task: &str) -> (Option<u8>, &str) {
+    let (prefix, rest) = task.split_once(' ').unwrap_or((task, ""));
+    let priority = if let Some(n) = prefix.strip_prefix('!') {
+        n.parse::<u8>().ok().filter(|&n| n > 0)
+    } else if let Some(letter) = prefix.strip_prefix('(').and_then(|p| p.strip_suffix(')')) {
+        let mut chars = letter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c @ 'A'..='Z'), None) => Some(c as u8 - b'A' + 1),
+            _ => None,
+        }
+    } else {
+        None
+    };
+    match priority {
+        Some(p) if !rest.trim().is_empty() => (Some(p), rest.trim()),
+        _ => (None, task),
+    }
+}
+
+// 按截止日期分组显示的分组（按此顺序排列）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DueGroup {
+    Overdue,
+    Today,
+    Tomorrow,
+    Later,
+    NoDate,
+}
+
+impl DueGroup {
+    pub fn label(self) -> &'static str {
+        match self {
+            DueGroup::Overdue => "Overdue",
+            DueGroup::Today => "Today",
+            DueGroup::Tomorrow => "Tomorrow",
+            DueGroup::Later => "Later",
+            DueGroup::NoDate => "No date",
+        }
+    }
+}
+
+// 待办排序方式（todo_sort）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TodoSort {
+    // 按截止时间，无法解析的排最后
+    Deadline,
+    // 按创建时间（API 按 create_time，本地存储本身即创建顺序）
+    Created,
+    // 按优先级，同级按截止时间
+    Priority,
+    // 保持后端返回的顺序
+    None,
+}
+
+impl TodoSort {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "deadline" => Some(TodoSort::Deadline),
+            "created" => Some(TodoSort::Created),
+            "priority" => Some(TodoSort::Priority),
+            "none" => Some(TodoSort::None),
+            _ => None,
+        }
+    }
+}
+
+// 排序：先按分组，再按 sort；稳定排序，相同的保持原有顺序
+pub fn sort_todos(todos: &mut [String], by_group: bool, sort: TodoSort) {
+    let today = chrono::Local::now().date_naive();
+    // 无截止时间的排最后
+    let due = |t: &TodoItem| {
+        let at = t.due_at(today);
+        (at.is_none(), at)
+    };
+    todos.sort_by(|a, b| {
+        let (a, b) = (TodoItem::parse(a), TodoItem::parse(b));
+        let group = |t: &TodoItem| if by_group { Some(t.group(today)) } else { None };
+        let order = group(&a).cmp(&group(&b));
+        match sort {
+            TodoSort::Deadline => order.then_with(|| due(&a).cmp(&due(&b))),
+            TodoSort::Priority => order
+                .then_with(|| a.priority.unwrap_or(u8::MAX).cmp(&b.priority.unwrap_or(u8::MAX)))
+                .then_with(|| due(&a).cmp(&due(&b))),
+            TodoSort::Created | TodoSort::None => order,
+        }
+    });
+}
+
+// 有可识别的截止日期时才显示分组标题
+pub fn has_due_dates(todos: &[String], today: NaiveDate) -> bool {
+    todos.iter().any(|t| TodoItem::parse(t).due_date(today).is_some())
+}
+
+// 优先级颜色：1..=3 档依次取配置颜色，其余使用默认待办颜色
+pub fn priority_color(priority: Option<u8>, colors: &[Color; 3], default: Color) -> Color {
+    match priority {
+        Some(p @ 1..=3) => colors[p as usize - 1],
+        _ => default,
+    }
+}
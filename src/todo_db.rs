@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+// 本地 SQLite 待办存储；待办按创建顺序排列，操作按列表序号（从 0 开始）定位，
+// 给定 expected 时优先按内容定位（界面排序后序号与存储顺序不同）
+pub struct TodoDb {
+    conn: Connection,
+}
+
+impl TodoDb {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS todos (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                task TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                completed_at TEXT
+            )",
+        )?;
+        Ok(Self { conn })
+    }
+
+    pub fn add(&self, task: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO todos (task, created_at) VALUES (?1, ?2)",
+            params![task, chrono::Local::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    // 直接写入一条已完成的待办（导入）
+    pub fn add_completed(&self, task: &str) -> rusqlite::Result<()> {
+        let now = chrono::Local::now().to_rfc3339();
+        self.conn.execute(
+            "INSERT INTO todos (task, created_at, completed_at) VALUES (?1, ?2, ?2)",
+            params![task, now],
+        )?;
+        Ok(())
+    }
+
+    // 已完成的待办，按完成时间排列
+    pub fn completed(&self) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT task FROM todos WHERE completed_at IS NOT NULL ORDER BY completed_at, id")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    // 未完成的待办
+    pub fn pending(&self, limit: Option<usize>) -> rusqlite::Result<Vec<String>> {
+        let limit = limit.map_or(-1, |n| n as i64);
+        let mut stmt = self.conn.prepare("SELECT task FROM todos WHERE completed_at IS NULL ORDER BY id LIMIT ?1")?;
+        let rows = stmt.query_map([limit], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    // 今日完成数与剩余数
+    pub fn summary(&self) -> rusqlite::Result<(usize, usize)> {
+        let today = format!("{}%", chrono::Local::now().format("%Y-%m-%d"));
+        self.conn.query_row(
+            "SELECT
+                (SELECT COUNT(*) FROM todos WHERE completed_at LIKE ?1),
+                (SELECT COUNT(*) FROM todos WHERE completed_at IS NULL)",
+            [today],
+            |row| Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as usize)),
+        )
+    }
+
+    // 标记完成，返回被完成的待办
+    pub fn complete(&self, index: usize, expected: Option<&str>) -> rusqlite::Result<Option<String>> {
+        let Some((id, task)) = self.find_pending(index, expected)? else { return Ok(None) };
+        self.conn.execute(
+            "UPDATE todos SET completed_at = ?1 WHERE id = ?2",
+            params![chrono::Local::now().to_rfc3339(), id],
+        )?;
+        Ok(Some(task))
+    }
+
+    // 删除，返回被删除的待办
+    pub fn remove(&self, index: usize, expected: Option<&str>) -> rusqlite::Result<Option<String>> {
+        let Some((id, task)) = self.find_pending(index, expected)? else { return Ok(None) };
+        self.conn.execute("DELETE FROM todos WHERE id = ?1", [id])?;
+        Ok(Some(task))
+    }
+
+    fn find_pending(&self, index: usize, expected: Option<&str>) -> rusqlite::Result<Option<(i64, String)>> {
+        if let Some(task) = expected {
+            return self
+                .conn
+                .query_row(
+                    "SELECT id, task FROM todos WHERE completed_at IS NULL AND task = ?1 ORDER BY id LIMIT 1",
+                    [task],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional();
+        }
+        self.conn
+            .query_row(
+                "SELECT id, task FROM todos WHERE completed_at IS NULL ORDER BY id LIMIT 1 OFFSET ?1",
+                [index as i64],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+    }
+}
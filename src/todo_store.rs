@@ -0,0 +1,272 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use ratatui::style::Color;
+
+use crate::model::{Config, TodoSummary};
+use crate::taskwarrior;
+use crate::todo::TodoItem;
+use crate::todo_db::TodoDb;
+
+const DEFAULT_TODOS_FILE: &str = "todos.txt";
+// 导出时从 API 读取的条数上限（未完成和已完成各一页）
+const EXPORT_PAGE_SIZE: usize = 500;
+
+// 当前配置的待办后端（优先级与显示时一致：todo_db > API > todos_file > todos.txt）
+#[derive(Debug, Clone)]
+pub enum TodoBackend {
+    Db(PathBuf),
+    Api { base_url: String, limit: usize, schema: Box<crate::api_schema::ApiSchema> },
+    File(PathBuf),
+    // 额外的 task 过滤参数，如 project:home
+    Taskwarrior(Vec<String>),
+    // Redis 列表键，只读
+    Redis { url: String, key: String },
+}
+
+// todo_sources 中的一项：后端 + 显示标签和颜色
+#[derive(Debug, Clone)]
+pub struct TodoSource {
+    pub backend: TodoBackend,
+    pub tag: Option<String>,
+    pub color: Option<Color>,
+}
+
+impl TodoBackend {
+    // 配置了 todo_sources 时为其中第一个可写的来源（命令行和添加待办使用）
+    pub fn from_config(config: &Config) -> Self {
+        if let Some(source) = config.todo_sources.iter().find(|s| s.backend.is_writable()).or(config.todo_sources.first()) {
+            return source.backend.clone();
+        }
+        if let Some(path) = &config.todo_db {
+            return TodoBackend::Db(path.clone());
+        }
+        if let Some(base_url) = &config.api_base_url {
+            return TodoBackend::Api { base_url: base_url.clone(), limit: config.todo_limit.unwrap_or(4), schema: Box::new(config.api_schema.clone()) };
+        }
+        TodoBackend::File(config.todos_file.clone().unwrap_or_else(|| DEFAULT_TODOS_FILE.into()))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            TodoBackend::Db(path) => format!("sqlite {}", path.display()),
+            TodoBackend::Api { base_url, .. } => format!("api {}", base_url),
+            TodoBackend::File(path) => format!("file {}", path.display()),
+            TodoBackend::Taskwarrior(filter) => format!("taskwarrior {}", filter.join(" ")).trim_end().to_string(),
+            TodoBackend::Redis { url, key } => format!("redis {} {}", url, key),
+        }
+    }
+
+    pub fn list(&self, limit: Option<usize>) -> Result<Vec<String>, String> {
+        match self {
+            TodoBackend::Db(path) => TodoDb::open(path).and_then(|db| db.pending(limit)).map_err(|e| e.to_string()),
+            TodoBackend::Api { base_url, limit: api_limit, schema } => {
+                crate::api::fetch_todos_api(base_url, limit.unwrap_or(*api_limit), false, &schema.todos)
+            }
+            TodoBackend::File(path) => {
+                let content = std::fs::read_to_string(path).unwrap_or_default();
+                let mut todos = parse_file(&content);
+                if let Some(limit) = limit {
+                    todos.truncate(limit);
+                }
+                Ok(todos)
+            }
+            TodoBackend::Taskwarrior(filter) => taskwarrior::pending(filter, limit),
+            TodoBackend::Redis { url, key } => crate::redis_source::list(url, key, limit),
+        }
+    }
+
+    pub fn add(&self, task: &str) -> Result<(), String> {
+        match self {
+            TodoBackend::Db(path) => TodoDb::open(path).and_then(|db| db.add(task)).map_err(|e| e.to_string()),
+            TodoBackend::Api { base_url, schema, .. } => crate::api::add_todo_api(base_url, task, schema),
+            TodoBackend::File(path) => {
+                let mut content = std::fs::read_to_string(path).unwrap_or_default();
+                if !content.is_empty() && !content.ends_with('\n') {
+                    content.push('\n');
+                }
+                content.push_str(task);
+                content.push('\n');
+                write_atomic(path, &content).map_err(|e| e.to_string())
+            }
+            TodoBackend::Taskwarrior(_) => taskwarrior::add(task),
+            TodoBackend::Redis { .. } => Err(read_only("Redis")),
+        }
+    }
+
+    // 导出用：全部待办（内容，是否已完成），未完成的在前；文件和 Redis 后端没有完成记录
+    pub fn all(&self) -> Result<Vec<(String, bool)>, String> {
+        let (pending, completed) = match self {
+            TodoBackend::Db(path) => {
+                let db = TodoDb::open(path).map_err(|e| e.to_string())?;
+                (db.pending(None).map_err(|e| e.to_string())?, db.completed().map_err(|e| e.to_string())?)
+            }
+            TodoBackend::Api { base_url, schema, .. } => (
+                crate::api::fetch_todos_api(base_url, EXPORT_PAGE_SIZE, false, &schema.todos)?,
+                crate::api::fetch_done_todos_api(base_url, EXPORT_PAGE_SIZE, &schema.todos)?,
+            ),
+            TodoBackend::File(_) | TodoBackend::Redis { .. } => (self.list(None)?, Vec::new()),
+            TodoBackend::Taskwarrior(filter) => (taskwarrior::pending(filter, None)?, taskwarrior::completed(filter)?),
+        };
+        Ok(pending.into_iter().map(|t| (t, false)).chain(completed.into_iter().map(|t| (t, true))).collect())
+    }
+
+    // 添加一条已完成的待办（导入）；不支持完成状态的后端返回 Ok(false)，由调用方跳过
+    pub fn add_completed(&self, task: &str) -> Result<bool, String> {
+        match self {
+            TodoBackend::Db(path) => TodoDb::open(path).and_then(|db| db.add_completed(task)).map(|_| true).map_err(|e| e.to_string()),
+            // API 没有直接写入已完成待办的接口：先添加，再按内容找到该行标记完成
+            TodoBackend::Api { .. } => {
+                self.add(task)?;
+                match self.complete(usize::MAX, Some(task))? {
+                    Some(_) => Ok(true),
+                    None => Err(format!("\"{}\" was added but could not be found to mark it done", task)),
+                }
+            }
+            TodoBackend::File(_) => Ok(false),
+            TodoBackend::Taskwarrior(_) => taskwarrior::log(task).map(|_| true),
+            TodoBackend::Redis { .. } => Err(read_only("Redis")),
+        }
+    }
+
+    // 按列表序号（从 0 开始）完成待办；文件后端没有完成状态，直接移除该行。
+    // expected 为界面上显示的内容：排序或外部修改导致序号错位时按内容重新定位
+    pub fn complete(&self, index: usize, expected: Option<&str>) -> Result<Option<String>, String> {
+        match self {
+            TodoBackend::Db(path) => TodoDb::open(path).and_then(|db| db.complete(index, expected)).map_err(|e| e.to_string()),
+            TodoBackend::Api { base_url, schema, .. } => crate::api::update_todo_api(base_url, index, expected, schema, &schema.todo_done),
+            TodoBackend::File(_) => self.remove(index, expected),
+            TodoBackend::Taskwarrior(filter) => taskwarrior::complete(filter, index, expected),
+            TodoBackend::Redis { .. } => Err(read_only("Redis")),
+        }
+    }
+
+    pub fn remove(&self, index: usize, expected: Option<&str>) -> Result<Option<String>, String> {
+        match self {
+            TodoBackend::Db(path) => TodoDb::open(path).and_then(|db| db.remove(index, expected)).map_err(|e| e.to_string()),
+            TodoBackend::Api { base_url, schema, .. } => crate::api::update_todo_api(base_url, index, expected, schema, &schema.todo_remove),
+            TodoBackend::File(path) => {
+                let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+                let tasks: Vec<(usize, &str)> = file_tasks(&content).collect();
+                let found = match expected {
+                    Some(text) => tasks
+                        .get(index)
+                        .filter(|(_, task)| *task == text)
+                        .or_else(|| tasks.iter().find(|(_, task)| *task == text)),
+                    None => tasks.get(index),
+                };
+                let Some(&(line_no, task)) = found else {
+                    return match expected {
+                        Some(text) => Err(format!("\"{}\" was changed in {} by another program", text, path.display())),
+                        None => Ok(None),
+                    };
+                };
+                let task = task.to_string();
+                // 保留其他行（包括注释和空行）原样
+                let mut out: String = content
+                    .lines()
+                    .enumerate()
+                    .filter(|(i, _)| *i != line_no)
+                    .map(|(_, line)| format!("{}\n", line))
+                    .collect();
+                if !content.ends_with('\n') {
+                    out.pop();
+                }
+                write_atomic(path, &out).map_err(|e| e.to_string())?;
+                Ok(Some(task))
+            }
+            TodoBackend::Taskwarrior(filter) => taskwarrior::remove(filter, index, expected),
+            TodoBackend::Redis { .. } => Err(read_only("Redis")),
+        }
+    }
+
+    // 今日完成 / 剩余统计；文件后端没有完成记录，不提供统计
+    pub fn summary(&self) -> Result<Option<TodoSummary>, String> {
+        match self {
+            TodoBackend::Db(path) => TodoDb::open(path)
+                .and_then(|db| db.summary())
+                .map(|(done_today, remaining)| Some(TodoSummary { done_today, remaining }))
+                .map_err(|e| e.to_string()),
+            TodoBackend::Api { base_url, schema, .. } => crate::api::fetch_todo_summary_api(base_url, &schema.todos).map(Some),
+            TodoBackend::File(_) | TodoBackend::Redis { .. } => Ok(None),
+            TodoBackend::Taskwarrior(filter) => taskwarrior::summary(filter)
+                .map(|(done_today, remaining)| Some(TodoSummary { done_today, remaining })),
+        }
+    }
+
+    pub fn is_writable(&self) -> bool {
+        !matches!(self, TodoBackend::Redis { .. })
+    }
+}
+
+// 文件中的待办：非空且不以 # 开头的行（行号，去除首尾空白后的内容）
+fn file_tasks(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+pub fn parse_file(content: &str) -> Vec<String> {
+    file_tasks(content).map(|(_, task)| task.to_string()).collect()
+}
+
+// 原子写入：写临时文件后 rename，避免外部程序读到半截内容
+pub fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let tmp = dir.join(format!(".{}.{}.tmp", name, std::process::id()));
+    let mut file = std::fs::File::create(&tmp)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    if let Ok(meta) = std::fs::metadata(path) {
+        let _ = std::fs::set_permissions(&tmp, meta.permissions());
+    }
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+// 合并 todo_sources 的待办（内容，来源序号）：按配置顺序拼接，相同任务（忽略截止时间、优先级和大小写）只保留第一个；
+// 各来源的错误以 "标签: 错误" 写入 error
+pub fn load_merged(sources: &[TodoSource], limit: Option<usize>, error: &mut Option<String>) -> Vec<(String, usize)> {
+    let mut seen = std::collections::HashSet::new();
+    let mut merged = Vec::new();
+    let mut errors = Vec::new();
+    for (i, source) in sources.iter().enumerate() {
+        match source.backend.list(limit) {
+            Ok(todos) => merged.extend(
+                todos
+                    .into_iter()
+                    .filter(|t| seen.insert(TodoItem::parse(t).task.to_lowercase()))
+                    .map(|t| (t, i)),
+            ),
+            Err(e) => errors.push(format!("{}: {}", source.label(), e)),
+        }
+    }
+    *error = (!errors.is_empty()).then(|| errors.join("; "));
+    merged
+}
+
+impl TodoSource {
+    pub fn label(&self) -> String {
+        self.tag.clone().unwrap_or_else(|| self.backend.name())
+    }
+}
+
+// 文件后端的修改时间，用于发现外部修改（多个文件来源取最新）
+pub fn file_mtime(config: &Config) -> Option<std::time::SystemTime> {
+    let mtime = |backend: &TodoBackend| match backend {
+        TodoBackend::File(path) => std::fs::metadata(path).and_then(|m| m.modified()).ok(),
+        _ => None,
+    };
+    if !config.todo_sources.is_empty() {
+        return config.todo_sources.iter().filter_map(|s| mtime(&s.backend)).max();
+    }
+    mtime(&TodoBackend::from_config(config))
+}
+
+fn read_only(backend: &str) -> String {
+    format!("the todo {} backend is read-only", backend)
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::conditional::Validators;
+use crate::jsonpath::{self, JsonPath};
+
+// 公交发车：GTFS-realtime（TripUpdates）或简单的 JSON 接口，显示某个站点接下来的发车；
+// 请求失败时保留上次的数据并标记为过时
+
+#[derive(Debug, Clone)]
+pub struct TransitConfig {
+    // 标题
+    pub stop: String,
+    // GTFS stop_id（GTFS-realtime 必填；JSON 地址中的 {stop_id} 会被替换）
+    pub stop_id: Option<String>,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub feed: TransitFeed,
+    // route_id -> 显示名称（GTFS-realtime 只有 route_id）
+    pub route_names: HashMap<String, String>,
+    pub limit: usize,
+    pub refresh_secs: u64,
+    pub stale_after_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum TransitFeed {
+    GtfsRealtime,
+    // departures 选出各班次；time 为绝对时间，或 minutes 为剩余分钟数
+    Json {
+        departures: JsonPath,
+        route: JsonPath,
+        destination: Option<JsonPath>,
+        time: Option<JsonPath>,
+        minutes: Option<JsonPath>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Departure {
+    pub route: String,
+    pub destination: String,
+    pub at: DateTime<Local>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransitState {
+    pub departures: Vec<Departure>,
+    // 最近一次成功获取的时间
+    pub updated: Option<DateTime<Local>>,
+    pub error: Option<String>,
+}
+
+pub fn spawn_worker(config: TransitConfig) -> Arc<Mutex<TransitState>> {
+    let state = Arc::new(Mutex::new(TransitState::default()));
+    let shared = state.clone();
+    std::thread::spawn(move || {
+        let mut validators = Validators::default();
+        loop {
+            let result = fetch(&config, &mut validators);
+            if result.is_err() {
+                validators.clear();
+            }
+            if let Ok(mut guard) = shared.lock() {
+                match result {
+                    Ok(Some(departures)) => {
+                        *guard = TransitState { departures, updated: Some(Local::now()), error: None };
+                    }
+                    // 304：时刻表没有变化，数据仍然是最新的
+                    Ok(None) => {
+                        guard.updated = Some(Local::now());
+                        guard.error = None;
+                    }
+                    // 保留旧数据，界面按 updated 判断是否过时
+                    Err(e) => guard.error = Some(e),
+                }
+            }
+            std::thread::sleep(crate::power::stretch(Duration::from_secs(config.refresh_secs)));
+        }
+    });
+    state
+}
+
+// 发车数据没有变化（304）时返回 Ok(None)
+fn fetch(config: &TransitConfig, validators: &mut Validators) -> Result<Option<Vec<Departure>>, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = config.url.replace("{stop_id}", config.stop_id.as_deref().unwrap_or(""));
+    let mut request = client.get(&url);
+    for (name, value) in &config.headers {
+        request = request.header(name, value);
+    }
+    let _permit = crate::ratelimit::permit();
+    let Some(response) = validators.send(request).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let body = response.bytes().map_err(|e| e.to_string())?;
+    let now = Local::now();
+    let mut departures = match &config.feed {
+        TransitFeed::GtfsRealtime => {
+            let stop_id = config.stop_id.as_deref().ok_or("transit: GTFS-realtime needs stop_id")?;
+            parse_gtfs_realtime(&body, stop_id).ok_or("invalid GTFS-realtime feed")?
+        }
+        TransitFeed::Json { departures, route, destination, time, minutes } => {
+            let json: serde_json::Value = serde_json::from_slice(&body).map_err(|e| format!("invalid JSON: {}", e))?;
+            let text = |path: &JsonPath, item: &serde_json::Value| path.select(item).first().map(|v| jsonpath::display(v));
+            departures
+                .select(&json)
+                .into_iter()
+                .filter_map(|item| {
+                    let at = match (time, minutes) {
+                        (Some(path), _) => parse_time(path.select(item).first()?)?,
+                        // 超出范围的分钟数跳过该条，避免工作线程 panic
+                        (None, Some(path)) => {
+                            now.checked_add_signed(chrono::Duration::try_seconds((path.select(item).first()?.as_f64()? * 60.0) as i64)?)?
+                        }
+                        (None, None) => return None,
+                    };
+                    Some(Departure {
+                        route: text(route, item)?,
+                        destination: destination.as_ref().and_then(|p| text(p, item)).unwrap_or_default(),
+                        at,
+                    })
+                })
+                .collect()
+        }
+    };
+    for departure in &mut departures {
+        if let Some(name) = config.route_names.get(&departure.route) {
+            departure.route = name.clone();
+        }
+    }
+    departures.retain(|d| d.at > now - chrono::Duration::minutes(1));
+    departures.sort_by_key(|d| d.at);
+    Ok(Some(departures))
+}
+
+// Unix 秒数或 RFC 3339
+fn parse_time(value: &serde_json::Value) -> Option<DateTime<Local>> {
+    match value.as_i64() {
+        Some(secs) => Local.timestamp_opt(secs, 0).single(),
+        None => DateTime::parse_from_rfc3339(value.as_str()?).ok().map(|at| at.with_timezone(&Local)),
+    }
+}
+
+// protobuf 字段值（只需要 varint 和 length-delimited）
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Other,
+}
+
+fn read_varint(buf: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *buf.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn decode_fields(buf: &[u8]) -> Option<Vec<(u64, Field<'_>)>> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while pos < buf.len() {
+        let key = read_varint(buf, &mut pos)?;
+        let value = match key & 7 {
+            0 => Field::Varint(read_varint(buf, &mut pos)?),
+            1 => {
+                pos += 8;
+                Field::Other
+            }
+            2 => {
+                let len = read_varint(buf, &mut pos)? as usize;
+                let bytes = buf.get(pos..pos.checked_add(len)?)?;
+                pos += len;
+                Field::Bytes(bytes)
+            }
+            5 => {
+                pos += 4;
+                Field::Other
+            }
+            _ => return None,
+        };
+        fields.push((key >> 3, value));
+    }
+    (pos == buf.len()).then_some(fields)
+}
+
+fn bytes_of<'a>(fields: &[(u64, Field<'a>)], number: u64) -> impl Iterator<Item = &'a [u8]> {
+    fields.iter().filter_map(move |(n, f)| match f {
+        Field::Bytes(b) if *n == number => Some(*b),
+        _ => None,
+    })
+}
+
+fn varint_of(fields: &[(u64, Field<'_>)], number: u64) -> Option<u64> {
+    fields.iter().find_map(|(n, f)| match f {
+        Field::Varint(v) if *n == number => Some(*v),
+        _ => None,
+    })
+}
+
+// FeedMessage.entity(2) -> FeedEntity.trip_update(3) -> TripUpdate.trip(1).route_id(5)
+// 与 stop_time_update(2)：stop_id(4)、arrival(2) / departure(3) 的 time(2)
+fn parse_gtfs_realtime(buf: &[u8], stop_id: &str) -> Option<Vec<Departure>> {
+    let mut departures = Vec::new();
+    for entity in bytes_of(&decode_fields(buf)?, 2) {
+        let entity = decode_fields(entity)?;
+        for update in bytes_of(&entity, 3) {
+            let update = decode_fields(update)?;
+            let route = bytes_of(&update, 1)
+                .next()
+                .and_then(decode_fields)
+                .and_then(|trip| bytes_of(&trip, 5).next().map(|r| String::from_utf8_lossy(r).into_owned()))
+                .unwrap_or_default();
+            for stop in bytes_of(&update, 2) {
+                let stop = decode_fields(stop)?;
+                if bytes_of(&stop, 4).next() != Some(stop_id.as_bytes()) {
+                    continue;
+                }
+                // 优先取发车时间
+                let time = bytes_of(&stop, 3)
+                    .chain(bytes_of(&stop, 2))
+                    .filter_map(decode_fields)
+                    .find_map(|event| varint_of(&event, 2));
+                if let Some(at) = time.and_then(|secs| Local.timestamp_opt(secs as i64, 0).single()) {
+                    departures.push(Departure { route: route.clone(), destination: String::new(), at });
+                }
+            }
+        }
+    }
+    Some(departures)
+}
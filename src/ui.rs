@@ -2,33 +2,143 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{List, ListItem, Paragraph},
+    widgets::{block::Padding, Block, Borders, Gauge, List, ListItem, Paragraph},
     Frame,
 };
-use chrono::Datelike;
-use crate::model::{App, Config};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use chrono::{Datelike, Timelike};
+use unicode_width::UnicodeWidthStr;
+use crate::model::{App, BigTemperature, ClockFace, Config, HumidityGauge, ThermometerStyle, TimeEffect, TimeFont, WidgetStyle};
+use crate::theme::Palette;
+use crate::alarm::{ActiveAlarm, AlarmState};
+use crate::gcal::GcalStatus;
+use crate::markers::DailyMarker;
+use crate::upcoming::FeedState;
+use crate::transit::{TransitConfig, TransitState};
+use crate::ntp::NtpState;
+use crate::events::{Event, EventKind};
+use crate::history::Heatmap;
+use crate::temperature::DailyRange;
+use crate::comfort::{Comfort, ComfortThresholds};
+use crate::sensors::SensorReading;
+use crate::script::{AlertLevel, ScriptState};
+use crate::panel::PanelState;
+use crate::radar::{Graphics, RadarState};
+use crate::source::{SourceState, SourceValue};
+use crate::todo::{has_due_dates, priority_color, DueGroup, TodoItem};
 
-// 绘制时钟
-pub fn draw_clock(f: &mut Frame, area: Rect, config: &Config) {
-    let now = chrono::Local::now();
-    let time_str = now.format("%H:%M:%S").to_string();
-    let lines = render_big_time(&time_str, config.time_scale_x, config.time_scale_y);
+// 绘制时钟（flash 为视觉报时：反色并显示边框）
+// face 为该时钟的设置（多时钟时各不相同），now 为该时钟所在时区的时间
+pub fn draw_clock(
+    f: &mut Frame,
+    area: Rect,
+    app: &App,
+    face: &ClockFace,
+    now: chrono::DateTime<chrono::FixedOffset>,
+    flash: bool,
+    flip: Option<usize>,
+) -> Rect {
+    let format = clock_format(app);
+    let time_str = now.format(format).to_string();
+    let big_style = clock_big_style(face);
+    let mut lines = render_big_time(&time_str, &big_style);
+    // 翻页动画：与上一秒的字形逐字符混合
+    if let Some(frame) = flip {
+        let prev_str = (now - chrono::Duration::seconds(1)).format(format).to_string();
+        if prev_str != time_str {
+            let prev = render_big_time(&prev_str, &big_style);
+            let offset = usize::from(face.time_effect == TimeEffect::Outline);
+            lines = flip_lines(&prev, &lines, &prev_str, &time_str, big_char_width(face.time_scale_x), offset, frame);
+        }
+    }
 
-    let mut text: Vec<Line> = lines
-        .into_iter()
-        .map(|s| Line::from(Span::styled(s, Style::default().fg(config.time_color).add_modifier(Modifier::BOLD))))
-        .collect();
+    // 字形两侧自带的空列，日期左/右对齐时对齐到笔画边缘
+    let blank_cols = |count: fn(&String) -> usize| lines.iter().map(count).min().unwrap_or(0) as u16;
+    let (ink_left, ink_right) = (
+        blank_cols(|s| s.chars().take_while(|&c| c == ' ').count()),
+        blank_cols(|s| s.chars().rev().take_while(|&c| c == ' ').count()),
+    );
+
+    let mut time_style = Style::default().fg(face.time_color).add_modifier(Modifier::BOLD);
+    if flash {
+        time_style = time_style.add_modifier(Modifier::REVERSED);
+    }
+    let mut text: Vec<Line> = match face.time_colors.is_set() || face.time_effect != TimeEffect::None || face.time_font == TimeFont::Dots {
+        // 按源字符切分每行，时/分/秒/分隔符分别着色，阴影/描边单元格使用 time_effect_color，点阵的暗点变暗
+        true => {
+            let styles: Vec<Style> = time_colors_for(&time_str, face).into_iter().map(|c| time_style.fg(c)).collect();
+            let effect_style = time_style.fg(face.time_effect_color).remove_modifier(Modifier::BOLD);
+            let width = big_char_width(face.time_scale_x);
+            // 描边在左侧多出一列
+            let offset = usize::from(face.time_effect == TimeEffect::Outline);
+            lines
+                .into_iter()
+                .map(|s| {
+                    let mut spans: Vec<Span> = Vec::new();
+                    let mut run = String::new();
+                    let mut run_style = None;
+                    for (i, c) in s.chars().enumerate() {
+                        let style = match c {
+                            SHADOW_CELL | OUTLINE_CELL => effect_style,
+                            DOT_OFF => time_style.add_modifier(Modifier::DIM).remove_modifier(Modifier::BOLD),
+                            _ => styles.get(i.saturating_sub(offset) / width).copied().unwrap_or(time_style),
+                        };
+                        if run_style.is_some_and(|s| s != style) {
+                            spans.push(Span::styled(std::mem::take(&mut run), run_style.unwrap_or(style)));
+                        }
+                        run_style = Some(style);
+                        run.push(c);
+                    }
+                    spans.push(Span::styled(run, run_style.unwrap_or(time_style)));
+                    Line::from(spans)
+                })
+                .collect()
+        }
+        false => lines
+            .into_iter()
+            .map(|s| Line::from(Span::styled(s, time_style)))
+            .collect(),
+    };
     
     // Append centered date line right under time using smallest characters
-    let gap_lines = ((config.time_scale_y as usize) + 1) / 2;
-    for _ in 0..gap_lines {
+    let gap_lines = (face.time_scale_y as usize).div_ceil(2);
+    // 小数秒占用第一行间隔，整体高度不变
+    let subseconds = face.show_subseconds.format(now.timestamp_subsec_nanos()).filter(|_| app.show_seconds());
+    let blank_lines = gap_lines.saturating_sub(usize::from(subseconds.is_some()));
+    let digits_width = text.first().map_or(0, Line::width);
+    let digit_rows = text.len();
+    // 装饰边框：大字上下各留一行
+    if app.config.clock_frame.is_some() {
+        text.insert(0, Line::from(""));
         text.push(Line::from(""));
     }
-    let date_small = format_date_cn();
-    text.push(Line::from(Span::styled(
+    if let Some(fraction) = subseconds {
+        // 左侧补空格到大字宽度，居中后读数与秒数字右对齐
+        text.push(Line::from(Span::styled(format!("{:>digits_width$}", fraction), Style::default().fg(face.date_color))));
+    }
+    for _ in 0..blank_lines {
+        text.push(Line::from(""));
+    }
+    // 多时钟的标签显示在大字上方
+    if let Some(label) = &face.label {
+        text.insert(0, Line::from(Span::styled(label.clone(), Style::default().fg(face.date_color).add_modifier(Modifier::BOLD))));
+    }
+    let mut date_small = format_date_cn(now);
+    if let Some(zone) = &face.zone {
+        date_small.push_str(&format!(" ({})", zone));
+    }
+    let mut date_lines = vec![Line::from(Span::styled(
         date_small,
-        Style::default().fg(config.date_color),
-    )));
+        Style::default().fg(face.date_color),
+    ))];
+    // 第二历法日期
+    if let Some(calendar) = face.secondary_calendar {
+        date_lines.push(Line::from(Span::styled(calendar.format(now.date_naive()), Style::default().fg(face.date_color))));
+    }
+    // 日期单独绘制在大字所在的列中，先留出空行
+    let date_row = text.len();
+    text.extend(date_lines.iter().map(|_| Line::from("")));
     
     // Vertical centering within the given area by pre-padding empty lines
     let content_lines = text.len();
@@ -44,53 +154,1250 @@ pub fn draw_clock(f: &mut Frame, area: Rect, config: &Config) {
     }
     centered.extend(text);
 
-    let para = Paragraph::new(centered).alignment(ratatui::layout::Alignment::Center);
+    let mut para = Paragraph::new(centered).alignment(ratatui::layout::Alignment::Center);
+    let mut inner = area;
+    if flash {
+        let block = Block::default().borders(Borders::ALL).border_style(Style::default().fg(face.time_color));
+        inner = block.inner(area);
+        para = para.block(block);
+    }
     f.render_widget(para, area);
+
+    // 日期按 widgets.date.align 与大字的左/右边缘对齐（默认居中）；比大字宽时向另一侧延伸
+    let align = app.config.widgets.get("date").and_then(|s| s.align);
+    let digits = align_rect(inner, digits_width as u16, None);
+    let ink = Rect { x: digits.x + ink_left.min(digits.width), width: digits.width.saturating_sub(ink_left + ink_right), ..digits };
+    let column = match align {
+        Some(ratatui::layout::Alignment::Left) => Rect { width: inner.right() - ink.x, ..ink },
+        Some(ratatui::layout::Alignment::Right) => Rect { x: inner.x, width: ink.right() - inner.x, ..ink },
+        _ => inner,
+    };
+    let y = inner.y + (pad_top + date_row) as u16;
+    if y < inner.bottom() {
+        let rect = Rect { y, height: (date_lines.len() as u16).min(inner.bottom() - y), ..column };
+        f.render_widget(Paragraph::new(date_lines).alignment(align.unwrap_or(ratatui::layout::Alignment::Center)), rect);
+    }
+    let top = inner.y + (pad_top + usize::from(face.label.is_some())) as u16;
+    if let Some(frame) = &app.config.clock_frame {
+        let rect = Rect { x: ink.x.saturating_sub(2), y: top, width: ink.width + 4, height: digit_rows as u16 + 2 };
+        let style = Style::default().fg(frame.color.unwrap_or(face.time_color));
+        crate::frame::render(f.buffer_mut(), rect.intersection(inner), frame, style);
+    }
+    // 返回大字笔画所在的区域
+    let top = top + u16::from(app.config.clock_frame.is_some());
+    Rect { y: top, height: digit_rows as u16, ..ink }
+}
+
+// 低带宽和省电模式不显示秒，大字每分钟才变化一次
+fn clock_format(app: &App) -> &'static str {
+    if app.show_seconds() { "%H:%M:%S" } else { "%H:%M" }
+}
+
+fn clock_big_style(face: &ClockFace) -> BigTextStyle {
+    BigTextStyle {
+        effect: face.time_effect,
+        font: face.time_font,
+        ..BigTextStyle::new(face.time_scale_x, face.time_scale_y)
+    }
+}
+
+// 时钟大字的宽度（列）
+pub fn clock_width(app: &App, face: &ClockFace, now: chrono::DateTime<chrono::FixedOffset>) -> u16 {
+    let rows = render_big_time(&now.format(clock_format(app)).to_string(), &clock_big_style(face));
+    rows.iter().map(|row| row.width()).max().unwrap_or(0) as u16
+}
+
+// 大字温度与时钟之间的列数
+pub const BIG_TEMPERATURE_GAP: u16 = 8;
+
+// 大字温度（如 23°），字体与时钟相同，缩放默认与时钟相同
+pub fn big_temperature_lines(app: &App, face: &ClockFace, big: &BigTemperature) -> Vec<Line<'static>> {
+    let temp = crate::temperature::parse_celsius_rounded(&app.temperature());
+    let text = temp.map_or_else(|| "--°".to_string(), |v| format!("{v}°"));
+    let style = BigTextStyle {
+        font: face.time_font,
+        ..BigTextStyle::new(big.scale_x.unwrap_or(face.time_scale_x), big.scale_y.unwrap_or(face.time_scale_y))
+    };
+    let color = big.color.unwrap_or(face.time_color);
+    render_big_time(&text, &style)
+        .into_iter()
+        .map(|row| Line::from(Span::styled(row, Style::default().fg(color).add_modifier(Modifier::BOLD))))
+        .collect()
+}
+
+// 大字温度画在时钟笔画右侧，与时钟底边对齐（比时钟高时顶边对齐）
+pub fn draw_big_temperature(f: &mut Frame, area: Rect, ink: Rect, lines: Vec<Line<'static>>) {
+    let height = lines.len() as u16;
+    let x = ink.right() + BIG_TEMPERATURE_GAP;
+    let y = ink.bottom().saturating_sub(height).max(ink.y);
+    let rect = Rect { x, y, width: area.right().saturating_sub(x), height }.intersection(area);
+    f.render_widget(Paragraph::new(lines), rect);
+}
+
+// 背景色：填充整个区域，之后绘制的内容没有设置背景色时保留该颜色
+pub fn fill_background(f: &mut Frame, area: Rect, color: Color) {
+    f.render_widget(Block::default().style(Style::default().bg(color)), area);
+}
+
+// 空闲时的小时钟：暗色 HH:MM，每分钟换一个位置，避免烧屏
+pub fn draw_idle_clock(f: &mut Frame, area: Rect, now: chrono::DateTime<chrono::FixedOffset>) {
+    let text = now.format("%H:%M").to_string();
+    let minute = now.hour() as u16 * 60 + now.minute() as u16;
+    let x = area.x + (minute * 13) % area.width.saturating_sub(text.len() as u16).max(1);
+    let y = area.y + (minute * 7) % area.height.max(1);
+    let rect = Rect::new(x, y, (text.len() as u16).min(area.width), area.height.min(1));
+    f.render_widget(Paragraph::new(Span::styled(text, Style::default().fg(Color::DarkGray))), rect);
+}
+
+// 工作时间标签（时钟区域左上角）
+pub fn draw_work_tag(f: &mut Frame, area: Rect, config: &Config, now: chrono::DateTime<chrono::Local>) {
+    if let Some(work) = &config.work_hours {
+        let (tag, color) = if work.is_working(&now) { (" ● WORK", config.palette.ok) } else { (" ○ OFF", config.palette.stale) };
+        let rect = Rect::new(area.x, area.y, area.width.min(8), area.height.min(1));
+        f.render_widget(Paragraph::new(Span::styled(tag, Style::default().fg(color))), rect);
+    }
+}
+
+// 绘制静音图标（右上角），可附带剩余时间
+pub fn draw_mute_indicator(f: &mut Frame, area: Rect, remaining: Option<std::time::Duration>) {
+    let label = match remaining {
+        Some(d) => format!(" 🔕 {}m ", d.as_secs().div_ceil(60)),
+        None => " 🔕 ".to_string(),
+    };
+    let width = (label.chars().count() as u16 + 1).min(area.width);
+    let rect = Rect::new(area.x + area.width - width, area.y, width, 1);
+    let para = Paragraph::new(Span::styled(label, Style::default().fg(Color::DarkGray)))
+        .alignment(ratatui::layout::Alignment::Right);
+    f.render_widget(para, rect);
+}
+
+// 时间同步（时钟区域右下角）：系统同步状态，未同步时醒目显示；
+// NTP 偏差超过 warn_ms 为警告色，查询失败时为过时色
+pub fn draw_time_sync(f: &mut Frame, area: Rect, synced: Option<bool>, ntp: Option<(&NtpState, u64)>, palette: &Palette) {
+    let mut spans = Vec::new();
+    match synced {
+        Some(true) => spans.push(Span::styled("✓ synced ", Style::default().fg(Color::DarkGray))),
+        Some(false) => spans.push(Span::styled("⚠ CLOCK NOT SYNCED ", Style::default().fg(palette.alert).add_modifier(Modifier::BOLD))),
+        None => {}
+    }
+    if let Some((state, warn_ms)) = ntp {
+        let (label, color) = match (state.offset_ms, &state.error) {
+            (Some(ms), _) if ms.unsigned_abs() > warn_ms => (format!("NTP {} ", crate::ntp::format_offset(ms)), palette.warn),
+            (Some(ms), None) => (format!("NTP {} ", crate::ntp::format_offset(ms)), Color::DarkGray),
+            (Some(ms), Some(_)) => (format!("NTP {}? ", crate::ntp::format_offset(ms)), palette.stale),
+            (None, Some(_)) => ("NTP ? ".to_string(), palette.stale),
+            (None, None) => (String::new(), Color::DarkGray),
+        };
+        spans.push(Span::styled(label, Style::default().fg(color)));
+    }
+    let line = Line::from(spans);
+    let width = (line.width() as u16).min(area.width);
+    if width == 0 {
+        return;
+    }
+    let rect = Rect::new(area.x + area.width - width, area.y + area.height.saturating_sub(1), width, area.height.min(1));
+    f.render_widget(Paragraph::new(line), rect);
+}
+
+// 暂停标签（顶部居中）
+pub fn draw_paused_indicator(f: &mut Frame, area: Rect) {
+    let label = " PAUSED ";
+    let width = (label.len() as u16).min(area.width);
+    let rect = Rect::new(area.x + (area.width - width) / 2, area.y, width, 1);
+    let para = Paragraph::new(Span::styled(label, Style::default().fg(Color::Black).bg(Color::Yellow)));
+    f.render_widget(para, rect);
+}
+
+// 特殊时刻倒计时：大字剩余秒数，下方为标签
+pub fn draw_countdown(f: &mut Frame, area: Rect, config: &Config, remaining: i64, label: &str) {
+    let big_style = BigTextStyle { font: config.time_font, ..BigTextStyle::new(config.time_scale_x, config.time_scale_y) };
+    let style = Style::default().fg(config.time_color).add_modifier(Modifier::BOLD);
+    let mut text: Vec<Line> = render_big_time(&remaining.to_string(), &big_style)
+        .into_iter()
+        .map(|s| Line::from(Span::styled(s, style)))
+        .collect();
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(label.to_string(), Style::default().fg(config.date_color))));
+    let pad_top = (area.height as usize).saturating_sub(text.len()) / 2;
+    let mut centered = vec![Line::from(""); pad_top];
+    centered.extend(text);
+    f.render_widget(Paragraph::new(centered).alignment(ratatui::layout::Alignment::Center), area);
+}
+
+// 庆祝动画：每 400ms 在随机位置绽放一朵烟花（持续 1.2 秒），只画在空白单元格；顶部显示标签
+pub fn draw_celebration(f: &mut Frame, area: Rect, elapsed_ms: i64, label: &str) {
+    const COLORS: [Color; 6] = [Color::LightRed, Color::Yellow, Color::LightMagenta, Color::LightCyan, Color::LightGreen, Color::LightBlue];
+    const LIFE_MS: i64 = 1200;
+    const EVERY_MS: i64 = 400;
+    if area.width < 4 || area.height < 4 {
+        return;
+    }
+    let buf = f.buffer_mut();
+    let newest = elapsed_ms / EVERY_MS;
+    for burst in (newest - LIFE_MS / EVERY_MS).max(0)..=newest {
+        let age = elapsed_ms - burst * EVERY_MS;
+        let hash = (burst as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let cx = (hash % area.width as u64) as f64;
+        let cy = ((hash >> 20) % area.height as u64) as f64;
+        // 终端单元格高约为宽的两倍，横向半径加倍
+        let radius = (area.height as f64 / 3.0) * age as f64 / LIFE_MS as f64;
+        let (glyph, modifier) = if age < LIFE_MS / 2 { ('*', Modifier::BOLD) } else { ('·', Modifier::DIM) };
+        let style = Style::default().fg(COLORS[burst as usize % COLORS.len()]).add_modifier(modifier);
+        for i in 0..16 {
+            let angle = i as f64 * std::f64::consts::TAU / 16.0;
+            let x = cx + radius * 2.0 * angle.cos();
+            let y = cy + radius * angle.sin();
+            if x < 0.0 || y < 0.0 || x >= area.width as f64 || y >= area.height as f64 {
+                continue;
+            }
+            let cell = buf.get_mut(area.x + x as u16, area.y + y as u16);
+            if cell.symbol() == " " {
+                cell.set_char(glyph).set_style(style);
+            }
+        }
+    }
+    let text = format!(" {} ", label);
+    let width = (text.width() as u16).min(area.width);
+    let rect = Rect::new(area.x + (area.width - width) / 2, area.y, width, 1);
+    let style = Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD);
+    f.render_widget(Paragraph::new(Span::styled(text, style)), rect);
+}
+
+// 倒计时（右上角第二行）
+pub fn draw_timer_indicator(f: &mut Frame, area: Rect, remaining: std::time::Duration, label: &str) {
+    let secs = remaining.as_secs();
+    let clock = match secs / 3600 {
+        0 => format!("{:02}:{:02}", secs / 60, secs % 60),
+        h => format!("{}:{:02}:{:02}", h, secs % 3600 / 60, secs % 60),
+    };
+    let text = format!(" ⏱ {} ({}) ", clock, label);
+    let width = (text.width() as u16).min(area.width);
+    let rect = Rect::new(area.x + area.width - width, area.y + 1.min(area.height.saturating_sub(1)), width, 1);
+    f.render_widget(Paragraph::new(Span::styled(text, Style::default().fg(Color::LightCyan))), rect);
+}
+
+// 命令面板补全候选（输入框上一行，Tab 选用第一个）
+pub fn draw_completions(f: &mut Frame, area: Rect, candidates: &[String]) {
+    if candidates.is_empty() || area.height < 2 {
+        return;
+    }
+    let rect = Rect::new(area.x, area.y + area.height - 2, area.width, 1);
+    let mut spans = Vec::new();
+    for (i, candidate) in candidates.iter().take(8).enumerate() {
+        let style = if i == 0 { Style::default().fg(Color::Black).bg(Color::Gray) } else { Style::default().fg(Color::Gray) };
+        spans.push(Span::styled(format!(" {} ", candidate), style));
+    }
+    f.render_widget(ratatui::widgets::Clear, rect);
+    f.render_widget(Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::DarkGray)), rect);
+}
+
+// 底部状态栏：左侧按键提示，右侧配置档、静音状态和数据更新时间（请求失败时标黄）
+pub fn draw_status_bar(f: &mut Frame, area: Rect, app: &mut App, profile: Option<&str>) {
+    let (muted, updated, failing) = (app.is_muted(), app.data_updated, app.offline_since.is_some());
+    let rect = Rect::new(area.x, area.y + area.height.saturating_sub(1), area.width, 1);
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut right = Vec::new();
+    if let Some(profile) = profile {
+        right.push(Span::styled(format!("[{}] ", profile), Style::default().fg(Color::Cyan)));
+    }
+    right.push(Span::styled(if muted { "muted " } else { "sound on " }, dim));
+    let updated = match updated {
+        Some(t) => format!("· updated {} ", t.format("%H:%M")),
+        None => "· no data yet ".to_string(),
+    };
+    right.push(Span::styled(updated, if failing { Style::default().fg(app.config.palette.warn) } else { dim }));
+    let right_width: u16 = right.iter().map(|s| s.width() as u16).sum();
+    let right_width = right_width.min(rect.width);
+    let left = Rect { width: rect.width - right_width, ..rect };
+    f.render_widget(ratatui::widgets::Clear, rect);
+    f.render_widget(Paragraph::new(Span::styled(format!(" {}", app.key_hints()), dim)), left);
+    f.render_widget(Paragraph::new(Line::from(right)), Rect { x: left.x + left.width, width: right_width, ..rect });
+}
+
+// 底部左侧状态栏：显示 API 错误；授权过期时红色显示
+pub fn draw_status_line(f: &mut Frame, area: Rect, message: &str, palette: &Palette) {
+    let rect = Rect::new(area.x, area.y + area.height.saturating_sub(1), area.width, 1);
+    let style = match crate::api::is_auth_error(message) {
+        true => Style::default().fg(palette.alert).add_modifier(Modifier::BOLD),
+        false => Style::default().fg(Color::DarkGray),
+    };
+    let para = Paragraph::new(Span::styled(format!("⚠ {}", message), style));
+    f.render_widget(para, rect);
+}
+
+// 顶部离线横幅
+pub fn draw_offline_banner(f: &mut Frame, area: Rect, since: chrono::DateTime<chrono::Local>, retry_secs: u64, palette: &Palette) {
+    let text = format!(" offline since {} · retrying every {}s · Enter to dismiss ", since.format("%H:%M"), retry_secs);
+    let width = (text.width() as u16).min(area.width);
+    let rect = Rect::new(area.x + (area.width - width) / 2, area.y, width, 1);
+    f.render_widget(ratatui::widgets::Clear, rect);
+    f.render_widget(Paragraph::new(Span::styled(text, Style::default().fg(Color::Black).bg(palette.warn))), rect);
+}
+
+// 推送的消息（顶部居中，离线横幅显示时被覆盖）
+pub fn draw_message_banner(f: &mut Frame, area: Rect, message: &str, palette: &Palette) {
+    let text = format!(" {} ", message);
+    let width = (text.width() as u16).min(area.width);
+    let rect = Rect::new(area.x + (area.width - width) / 2, area.y, width, 1);
+    f.render_widget(ratatui::widgets::Clear, rect);
+    f.render_widget(Paragraph::new(Span::styled(text, Style::default().fg(Color::Black).bg(palette.info))), rect);
+}
+
+// 底部输入框
+pub fn draw_input(f: &mut Frame, area: Rect, label: &str, text: &str) {
+    let rect = Rect::new(area.x, area.y + area.height.saturating_sub(1), area.width, 1);
+    let prompt = label.to_string();
+    let cursor_x = area.x + (prompt.width() + text.width()) as u16;
+    f.render_widget(ratatui::widgets::Clear, rect);
+    f.render_widget(Paragraph::new(Line::from(vec![
+        Span::styled(prompt, Style::default().fg(Color::Yellow)),
+        Span::raw(text.to_string()),
+    ])), rect);
+    f.set_cursor(cursor_x.min(area.x + area.width.saturating_sub(1)), rect.y);
+}
+
+// 底部右侧的短暂提示
+pub fn draw_notice(f: &mut Frame, area: Rect, message: &str) {
+    let label = format!(" {} ", message);
+    let width = (label.width() as u16).min(area.width);
+    let rect = Rect::new(area.x + area.width - width, area.y + area.height.saturating_sub(1), width, 1);
+    f.render_widget(Paragraph::new(Span::styled(label, Style::default().fg(Color::Black).bg(Color::Gray))), rect);
+}
+
+// 绘制闹钟横幅（居中浮层）
+pub fn draw_alarm_banner(f: &mut Frame, area: Rect, alarm: &ActiveAlarm, palette: &Palette) {
+    let status = match alarm.state {
+        AlarmState::Ringing { .. } => "⏰ RINGING".to_string(),
+        AlarmState::Snoozed { until } => {
+            let left = until.saturating_duration_since(std::time::Instant::now()).as_secs();
+            format!("💤 snoozed {}:{:02}", left / 60, left % 60)
+        }
+    };
+    let mut title = alarm.label.clone();
+    if alarm.snooze_count > 0 {
+        title.push_str(&format!("  (snoozed ×{})", alarm.snooze_count));
+    }
+    let color = if alarm.is_ringing() { palette.alert } else { palette.warn };
+    let lines = vec![
+        Line::from(Span::styled(title, Style::default().fg(color).add_modifier(Modifier::BOLD))),
+        Line::from(Span::styled(status, Style::default().fg(color))),
+        Line::from(Span::styled("[s] snooze  [Enter] dismiss", Style::default().fg(Color::DarkGray))),
+    ];
+    let width = area.width.min(44);
+    let height = area.height.min(5);
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let para = Paragraph::new(lines)
+        .alignment(ratatui::layout::Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(color)));
+    f.render_widget(ratatui::widgets::Clear, rect);
+    f.render_widget(para, rect);
 }
 
 // 绘制侧边栏（温度和待办事项）
 pub fn draw_sidebar(
     f: &mut Frame,
     area: Rect,
-    app: &mut App,
+    app: &App,
 ) {
     // 横向拆分：左=温度计+Todo，右=二维码
     let hchunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(100), Constraint::Percentage(0)])
         .split(area);
-    let left = hchunks[0];
-    
-    // 左列：原有垂直布局
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(4),  // temperature
-            Constraint::Min(1),     // todos
-        ])
-        .split(left);
+    let mut left = hchunks[0];
+    let style = |name: &str| app.config.widgets.get(name);
+    let spacing = app.config.layout;
+    // 从 left 顶部切出组件，下方留出组件间距
+    let take = |f: &mut Frame, left: &mut Rect, height: u16, style: Option<&WidgetStyle>| {
+        let rect = take_widget(f, left, height, style, spacing.padding);
+        let margin = spacing.margin.min(left.height);
+        left.y += margin;
+        left.height -= margin;
+        rect
+    };
+
+    // 天气强调线：顶部一行
+    if let Some((color, label)) = app.weather_accent() {
+        let rect = take(f, &mut left, 1, style("accent"));
+        draw_accent_line(f, rect, color, label);
+    }
+
+    // 日历：顶部一行显示下一场会议
+    if let Some(status) = &app.gcal_status {
+        let rect = take(f, &mut left, 1, style("meeting"));
+        let status = status.lock().map(|s| s.clone()).unwrap_or(GcalStatus::Connecting);
+        draw_meeting_widget(f, rect, &status, &app.config.palette);
+    }
+
+    // 每日时刻标记：一行，高亮下一个
+    if !app.markers.is_empty() {
+        let rect = take(f, &mut left, 1, style("markers"));
+        draw_markers_widget(f, rect, &app.markers, app.now(), &app.config.palette);
+    }
+
+    // 日照进度：一行
+    if app.config.sun_bar
+        && let Some((lat, lon)) = app.config.location
+    {
+        let rect = take(f, &mut left, 1, style("sun"));
+        draw_sun_widget(f, rect, app.now(), lat, lon);
+    }
+
+    // 自定义数据源：数值占一行，列表为标题行 + 各项
+    for (name, state) in app.source_states() {
+        let height = match &state {
+            SourceState::Ready(SourceValue::List(items)) => 1 + items.len().max(1),
+            _ => 1,
+        };
+        let height = u16::try_from(height).unwrap_or(u16::MAX);
+        let rect = take(f, &mut left, height, style(&name));
+        draw_source_widget(f, rect, &name, &state, &app.config.palette);
+    }
+
+    // 脚本数据源：标题行 + 提醒 + 数值一行 + 列表各项
+    for (name, state) in app.script_states() {
+        let height = match &state {
+            ScriptState::Ready(output) => 1 + output.alerts.len() + usize::from(!output.values.is_empty()) + output.items.len(),
+            _ => 2,
+        };
+        let rect = take(f, &mut left, u16::try_from(height).unwrap_or(u16::MAX), style(&name));
+        draw_script_widget(f, rect, &name, &state, &app.config.palette);
+    }
+
+    // 命令输出面板：边框（含标题）+ 输出各行
+    for (title, state) in app.panel_states() {
+        let height = match &state {
+            PanelState::Ready(lines) => 2 + lines.len().max(1),
+            _ => 3,
+        };
+        let rect = take(f, &mut left, u16::try_from(height).unwrap_or(u16::MAX), None);
+        draw_panel_widget(f, rect, &title, &state, &app.config.palette);
+    }
+
+    // “接下来”数据：标题行 + 最近的 N 条
+    for (name, limit, state) in app.upcoming_states() {
+        let rect = take(f, &mut left, 1 + limit as u16, style(&name));
+        draw_upcoming_widget(f, rect, &name, limit, &state, app.now(), &app.config.palette);
+    }
+
+    // 公交发车：标题行 + 接下来的 N 班
+    if let (Some(config), Some(state)) = (&app.config.transit, app.transit_state()) {
+        let rect = take(f, &mut left, 1 + config.limit as u16, style("transit"));
+        draw_transit_widget(f, rect, config, &state, app.now(), &app.config.palette);
+    }
+
+    // 降水提醒：一行
+    if let Some(alert) = app.rain_alert() {
+        let rect = take(f, &mut left, 1, style("rain"));
+        let text = crate::weather::describe(&alert, app.now());
+        let line = Line::from(vec![
+            Span::styled(text, Style::default().fg(app.config.palette.info).add_modifier(Modifier::BOLD)),
+            Span::styled("  s snooze", Style::default().fg(Color::DarkGray)),
+        ]);
+        f.render_widget(Paragraph::new(line).alignment(ratatui::layout::Alignment::Center), rect);
+    }
+
+    // 湿度舒适度：一行
+    if app.config.show_comfort
+        && let Some(humidity) = app.cached_humidity
+    {
+        let rect = take(f, &mut left, 1, style("comfort"));
+        let temp = crate::temperature::parse_celsius(&app.temperature());
+        draw_comfort_widget(f, rect, humidity, temp, &app.config.comfort, &app.config.palette);
+    }
+
+    // 湿度条：四行，有湿度读数时显示
+    if let Some(gauge) = app.config.humidity_gauge
+        && let Some(humidity) = app.cached_humidity
+    {
+        let rect = take(f, &mut left, 4, style("humidity"));
+        let align = style("humidity").and_then(|s| s.align);
+        draw_humidity_widget(f, rect, humidity, app.today_humidity_range(), &app.config.comfort, &gauge, align);
+    }
+
+    // 本地传感器：一行，各设备依次排列
+    let readings = app.local_sensor_readings();
+    if !readings.is_empty() {
+        let rect = take(f, &mut left, 1, style("sensors"));
+        draw_sensors_widget(f, rect, &readings, app.now(), &app.config.palette);
+    }
+
+    // 左列：原有垂直布局；对比室内外时温度区多两行（室外温度条、温差）
+    let compare = app.config.compare_indoor_outdoor;
+    let temp_rect = take(f, &mut left, if compare { 6 } else { 4 }, style("temperature"));
+    let todos_rect = frame_widget(f, left, style("todos"), spacing.padding);
 
     let temp_str = app.temperature();
-    let parsed = parse_temp_celsius(&temp_str);
-    draw_temperature_widget(f, chunks[0], parsed);
-    draw_todos_widget(f, chunks[1], app);
+    let range = app.today_temp_range();
+    let temp_align = style("temperature").and_then(|s| s.align);
+    let accent = app.weather_accent().map(|(color, _)| color);
+    if compare {
+        let outdoor = app.cached_outdoor_temp.clone().unwrap_or_else(|| "--".into());
+        draw_indoor_outdoor_widget(f, temp_rect, &temp_str, &outdoor, range, temp_align, accent);
+    } else {
+        let parsed = crate::temperature::parse_celsius_rounded(&temp_str);
+        draw_temperature_widget(f, temp_rect, parsed, range, temp_align, accent, app.config.thermometer_style);
+    }
+    draw_todos_widget(f, todos_rect, app);
+}
+
+// 天气强调线：整行横线，中间为天气说明
+fn draw_accent_line(f: &mut Frame, area: Rect, color: Color, label: &str) {
+    let label = format!(" {} ", label);
+    let side = (area.width as usize).saturating_sub(label.width()) / 2;
+    let rule = "─".repeat(side);
+    let line = Line::from(vec![
+        Span::styled(rule.clone(), Style::default().fg(color)),
+        Span::styled(label, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        Span::styled(rule, Style::default().fg(color)),
+    ]);
+    f.render_widget(Paragraph::new(line).alignment(ratatui::layout::Alignment::Center), area);
+}
+
+// 在 area 中按对齐方式放置宽度为 width 的一列（默认居中），两侧的留白即布局边距
+fn align_rect(area: Rect, width: u16, align: Option<ratatui::layout::Alignment>) -> Rect {
+    let width = width.min(area.width);
+    let x = match align.unwrap_or(ratatui::layout::Alignment::Center) {
+        ratatui::layout::Alignment::Left => area.x,
+        ratatui::layout::Alignment::Center => area.x + (area.width - width) / 2,
+        ratatui::layout::Alignment::Right => area.x + area.width - width,
+    };
+    Rect { x, width, ..area }
+}
+
+// 从 area 顶部切出高度为 height 的组件，加上 widgets 配置的标题行、边框和 layout 的内边距；
+// 返回内容区域，area 缩小为剩余部分
+fn take_widget(f: &mut Frame, area: &mut Rect, height: u16, style: Option<&WidgetStyle>, padding: (u16, u16)) -> Rect {
+    let block = widget_block(style, padding);
+    let chrome = block.as_ref().map_or(0, |b| 100 - b.inner(Rect::new(0, 0, 100, 100)).height);
+    let rect = Rect { height: height.saturating_add(chrome).min(area.height), ..*area };
+    area.y += rect.height;
+    area.height -= rect.height;
+    match block {
+        Some(block) => {
+            let inner = block.inner(rect);
+            f.render_widget(block, rect);
+            inner
+        }
+        None => rect,
+    }
+}
+
+// 在整个区域绘制组件的标题和边框，返回内容区域（已减去内边距）
+fn frame_widget(f: &mut Frame, area: Rect, style: Option<&WidgetStyle>, padding: (u16, u16)) -> Rect {
+    let Some(block) = widget_block(style, padding) else { return area };
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    inner
+}
+
+// widgets 配置和内边距对应的 Block；既没有标题、边框、背景色也没有内边距时为 None
+fn widget_block(style: Option<&WidgetStyle>, (rows, cols): (u16, u16)) -> Option<Block<'_>> {
+    let style = style.filter(|s| s.title.is_some() || s.border || s.background.is_some());
+    if style.is_none() && rows == 0 && cols == 0 {
+        return None;
+    }
+    let mut block = Block::default().padding(Padding::new(cols, cols, rows, rows));
+    let Some(style) = style else { return Some(block) };
+    if let Some(color) = style.background {
+        block = block.style(Style::default().bg(color));
+    }
+    if style.border {
+        block = block.borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray));
+    }
+    // 标题在边框上时两侧留空格
+    if let Some(title) = &style.title {
+        let title = if style.border { format!(" {} ", title) } else { title.clone() };
+        block = block.title(Span::styled(title, Style::default().add_modifier(Modifier::BOLD)));
+    }
+    Some(block)
 }
 
-// 绘制温度组件
+// 发车列表：超过 stale_after_secs 未更新时标题显示数据年龄、各班次变暗
+fn draw_transit_widget(f: &mut Frame, area: Rect, config: &TransitConfig, state: &TransitState, now: chrono::DateTime<chrono::Local>, palette: &Palette) {
+    let label = Style::default().fg(Color::DarkGray);
+    let age = state.updated.map(|at| (now - at).num_seconds().max(0) as u64);
+    let stale = age.is_none_or(|secs| secs > config.stale_after_secs);
+    let mut title = vec![Span::styled(format!("🚏 {}", config.stop), label)];
+    match (age, &state.error) {
+        (Some(secs), _) if stale => title.push(Span::styled(
+            format!(" (stale, updated {} min ago)", secs / 60),
+            Style::default().fg(palette.warn),
+        )),
+        (None, Some(e)) => title.push(Span::styled(format!(" {}", e), Style::default().fg(palette.alert))),
+        _ => {}
+    }
+    let mut lines = vec![Line::from(title)];
+    if state.updated.is_none() && state.error.is_none() {
+        lines.push(Line::from(Span::styled("  …", label)));
+    }
+    let upcoming: Vec<_> = state.departures.iter().filter(|d| d.at > now).take(config.limit).collect();
+    if state.updated.is_some() && upcoming.is_empty() {
+        lines.push(Line::from(Span::styled("  no departures", label)));
+    }
+    let color = if stale { palette.stale } else { Color::Cyan };
+    for departure in upcoming {
+        let mins = (departure.at - now).num_seconds() / 60;
+        let when = if mins < 1 { "due".to_string() } else { format!("in {} min", mins) };
+        let mut spans = vec![Span::styled(departure.route.clone(), Style::default().fg(color).add_modifier(Modifier::BOLD))];
+        if !departure.destination.is_empty() {
+            spans.push(Span::styled(format!(" → {}", departure.destination), Style::default().fg(color)));
+        }
+        spans.push(Span::styled(format!("  {}", when), if mins < 1 { Style::default().fg(palette.alert) } else { label }));
+        lines.push(Line::from(spans));
+    }
+    let para = Paragraph::new(lines).alignment(ratatui::layout::Alignment::Center);
+    f.render_widget(para, area);
+}
+
+// 最近的 N 条（已过去的跳过），每条带倒计时
+fn draw_upcoming_widget(f: &mut Frame, area: Rect, name: &str, limit: usize, state: &FeedState, now: chrono::DateTime<chrono::Local>, palette: &Palette) {
+    let label = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![Line::from(Span::styled(name.to_string(), label))];
+    match state {
+        FeedState::Loading => lines.push(Line::from(Span::styled("  …", label))),
+        FeedState::Error(e) => lines.push(Line::from(Span::styled(format!("  {}", e), Style::default().fg(palette.alert)))),
+        FeedState::Ready(items) => {
+            let next: Vec<_> = items.iter().filter(|item| item.at > now).take(limit).collect();
+            if next.is_empty() {
+                lines.push(Line::from(Span::styled("  (nothing upcoming)", label)));
+            }
+            for item in next {
+                let mins = ((item.at - now).num_seconds() as u64).div_ceil(60);
+                let when = match mins {
+                    0..=59 => format!("in {} min", mins),
+                    60..=1439 => format!("in {}h{:02}m", mins / 60, mins % 60),
+                    _ => format!("in {}d", mins / 1440),
+                };
+                // 今天以外的条目显示日期
+                let at = match item.at.date_naive() == now.date_naive() {
+                    true => item.at.format("%H:%M").to_string(),
+                    false => item.at.format("%m/%d %H:%M").to_string(),
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {} ", at), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                    Span::styled(item.label.clone(), Style::default().fg(Color::Cyan)),
+                    Span::styled(format!(" ({})", when), label),
+                ]));
+            }
+        }
+    }
+    let para = Paragraph::new(lines).alignment(ratatui::layout::Alignment::Center);
+    f.render_widget(para, area);
+}
+
+// 绘制下一场会议（或授权提示）
+fn draw_source_widget(f: &mut Frame, area: Rect, name: &str, state: &SourceState, palette: &Palette) {
+    let label = Style::default().fg(Color::DarkGray);
+    let lines = match state {
+        SourceState::Loading => vec![Line::from(vec![Span::styled(format!("{}: ", name), label), Span::styled("…", label)])],
+        SourceState::Error(e) => vec![Line::from(vec![
+            Span::styled(format!("{}: ", name), label),
+            Span::styled(e.clone(), Style::default().fg(palette.alert)),
+        ])],
+        SourceState::Ready(SourceValue::Value(value)) => vec![Line::from(vec![
+            Span::styled(format!("{}: ", name), label),
+            Span::styled(value.clone(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        ])],
+        SourceState::Ready(SourceValue::List(items)) => {
+            let mut lines = vec![Line::from(Span::styled(name.to_string(), label))];
+            if items.is_empty() {
+                lines.push(Line::from(Span::styled("  (empty)", label)));
+            }
+            lines.extend(items.iter().map(|item| Line::from(Span::styled(format!("  • {}", item), Style::default().fg(Color::Cyan)))));
+            lines
+        }
+    };
+    let para = Paragraph::new(lines).alignment(ratatui::layout::Alignment::Center);
+    f.render_widget(para, area);
+}
+
+// 脚本输出：提醒按级别着色，数值排成一行，列表逐项显示
+fn draw_script_widget(f: &mut Frame, area: Rect, name: &str, state: &ScriptState, palette: &Palette) {
+    let label = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![Line::from(Span::styled(name.to_string(), label))];
+    match state {
+        ScriptState::Loading => lines.push(Line::from(Span::styled("  …", label))),
+        ScriptState::Error(e) => lines.push(Line::from(Span::styled(format!("  {}", e), Style::default().fg(palette.alert)))),
+        ScriptState::Ready(output) => {
+            for alert in &output.alerts {
+                let (icon, color) = match alert.level {
+                    AlertLevel::Info => ("ℹ", palette.info),
+                    AlertLevel::Warn => ("⚠", palette.warn),
+                    AlertLevel::Error => ("✖", palette.alert),
+                };
+                lines.push(Line::from(Span::styled(format!("{} {}", icon, alert.text), Style::default().fg(color).add_modifier(Modifier::BOLD))));
+            }
+            if !output.values.is_empty() {
+                let mut spans = Vec::new();
+                for (i, value) in output.values.iter().enumerate() {
+                    if i > 0 {
+                        spans.push(Span::styled("  ", label));
+                    }
+                    if !value.label.is_empty() {
+                        spans.push(Span::styled(format!("{} ", value.label), label));
+                    }
+                    let color = value.color.as_deref().and_then(crate::config::parse_color).unwrap_or(Color::Cyan);
+                    spans.push(Span::styled(value.value.clone(), Style::default().fg(color).add_modifier(Modifier::BOLD)));
+                }
+                lines.push(Line::from(spans));
+            }
+            lines.extend(output.items.iter().map(|item| Line::from(Span::styled(format!("  • {}", item), Style::default().fg(Color::Cyan)))));
+        }
+    }
+    f.render_widget(Paragraph::new(lines).alignment(ratatui::layout::Alignment::Center), area);
+}
+
+// 命令输出按原样左对齐显示，保留 ANSI 颜色
+fn draw_panel_widget(f: &mut Frame, area: Rect, title: &str, state: &PanelState, palette: &Palette) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let lines = match state {
+        PanelState::Loading => vec![Line::from(Span::styled("…", dim))],
+        PanelState::Error(e) => vec![Line::from(Span::styled(e.clone(), Style::default().fg(palette.alert)))],
+        PanelState::Ready(lines) => lines.iter().map(|l| crate::panel::parse_ansi(l)).collect(),
+    };
+    let block = Block::default().borders(Borders::ALL).border_style(dim).title(Span::styled(format!(" {} ", title), dim));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+// 日出到日落的进度条，● 为当前位置；夜间显示到日出的时间
+fn draw_sun_widget(f: &mut Frame, area: Rect, now: chrono::DateTime<chrono::Local>, lat: f64, lon: f64) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let Some(sun) = crate::solar::sun_times(now.date_naive(), lat, lon) else {
+        let para = Paragraph::new(Span::styled("☀ no sunrise or sunset today", dim)).alignment(ratatui::layout::Alignment::Center);
+        return f.render_widget(para, area);
+    };
+    let (rise, set) = (sun.sunrise.with_timezone(&chrono::Local), sun.sunset.with_timezone(&chrono::Local));
+    let span_hm = |d: chrono::Duration| format!("{}h{:02}m", d.num_hours(), d.num_minutes() % 60);
+    let (progress, note, color) = if now < rise {
+        (None, format!("sunrise in {}", span_hm(rise - now)), Color::DarkGray)
+    } else if now > set {
+        (None, "after sunset".to_string(), Color::DarkGray)
+    } else {
+        let fraction = (now - rise).num_seconds() as f64 / (set - rise).num_seconds().max(1) as f64;
+        (Some(fraction), format!("{} of daylight left", span_hm(set - now)), Color::Yellow)
+    };
+    let left = format!("☀ {} ", rise.format("%H:%M"));
+    let right = format!(" {}  {}", set.format("%H:%M"), note);
+    let width = (area.width as usize).saturating_sub(left.width() + right.width() + 2).clamp(10, 40);
+    let bar: String = (0..width)
+        .map(|i| match progress {
+            Some(p) if i == ((p * (width - 1) as f64).round() as usize) => '●',
+            Some(p) if (i as f64) < p * (width - 1) as f64 => '━',
+            _ => '─',
+        })
+        .collect();
+    let line = Line::from(vec![
+        Span::styled(left, dim),
+        Span::styled(bar, Style::default().fg(color)),
+        Span::styled(right, dim),
+    ]);
+    f.render_widget(Paragraph::new(line).alignment(ratatui::layout::Alignment::Center), area);
+}
+
+// 雷达页面：标题行、图像区域（支持图形协议时）和文字摘要；返回图像应绘制的位置
+pub fn draw_radar_page(f: &mut Frame, area: Rect, app: &App) -> Option<Rect> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)])
+        .split(area);
+    let title = Line::from(vec![
+        Span::styled("🌧 Radar", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled("  v back to clock", dim),
+    ]);
+    f.render_widget(Paragraph::new(title).alignment(ratatui::layout::Alignment::Center), rows[0]);
+    let graphics = app.config.radar.as_ref().map_or(Graphics::Text, |r| r.graphics);
+    let (message, style) = match app.radar_state() {
+        None | Some(RadarState::Loading) => ("loading radar…".to_string(), dim),
+        Some(RadarState::Error(e)) => (e, Style::default().fg(app.config.palette.alert)),
+        Some(RadarState::Ready(frame)) => {
+            let color = if frame.at_location { app.config.palette.info } else { Color::Gray };
+            let summary = crate::radar::summary(&frame);
+            if graphics != Graphics::Text && !app.config.low_bandwidth && frame.image.is_some() {
+                // 单元格约为 1:2，取正方形显示区域
+                let height = rows[1].height.min(rows[1].width / 2);
+                let width = height * 2;
+                let image = Rect { x: rows[1].x + (rows[1].width - width) / 2, y: rows[1].y, width, height };
+                f.render_widget(Paragraph::new(Span::styled(summary, Style::default().fg(color))).alignment(ratatui::layout::Alignment::Center), rows[2]);
+                return Some(image);
+            }
+            (summary, Style::default().fg(color))
+        }
+    };
+    let middle = Rect { y: rows[1].y + rows[1].height / 2, height: 1.min(rows[1].height), ..rows[1] };
+    f.render_widget(Paragraph::new(Span::styled(message, style)).alignment(ratatui::layout::Alignment::Center), middle);
+    None
+}
+
+// 事件页面：最近的报时、闹钟、提醒和错误，最新的在前；不是今天的事件显示日期
+pub fn draw_events_page(f: &mut Frame, area: Rect, events: &[Event], now: chrono::DateTime<chrono::Local>, palette: &Palette) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(area);
+    let title = Line::from(vec![
+        Span::styled("Events", Style::default().fg(palette.info).add_modifier(Modifier::BOLD)),
+        Span::styled("  e back to clock", dim),
+    ]);
+    f.render_widget(Paragraph::new(title).alignment(ratatui::layout::Alignment::Center), rows[0]);
+    if events.is_empty() {
+        f.render_widget(Paragraph::new(Span::styled("nothing happened yet", dim)).alignment(ratatui::layout::Alignment::Center), rows[1]);
+        return;
+    }
+    let items: Vec<ListItem> = events
+        .iter()
+        .take(rows[1].height as usize)
+        .map(|event| {
+            let at = match event.at.date_naive() == now.date_naive() {
+                true => event.at.format("      %H:%M:%S").to_string(),
+                false => event.at.format("%m-%d %H:%M:%S").to_string(),
+            };
+            let color = match event.kind {
+                EventKind::Chime => palette.ok,
+                EventKind::Alarm => palette.warn,
+                EventKind::Alert => palette.info,
+                EventKind::Error => palette.alert,
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {}  ", at), dim),
+                Span::styled(format!("{:<6}", event.kind.label()), Style::default().fg(color)),
+                Span::raw(event.text.clone()),
+            ]))
+        })
+        .collect();
+    f.render_widget(List::new(items), rows[1]);
+}
+
+// 历史页面：最近 7 天逐小时平均温度的热力图，每行一天、每列一小时，
+// 颜色从蓝（本周最低）到红（本周最高），没有记录的小时显示为 ·；heatmap 为 None 时仍在读取
+pub fn draw_history_page(f: &mut Frame, area: Rect, heatmap: Option<&Heatmap>, palette: &Palette) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(area);
+    let title = Line::from(vec![
+        Span::styled("Temperature · last 7 days", Style::default().fg(palette.info).add_modifier(Modifier::BOLD)),
+        Span::styled("  h back to clock", dim),
+    ]);
+    f.render_widget(Paragraph::new(title).alignment(ratatui::layout::Alignment::Center), rows[0]);
+    let Some(heatmap) = heatmap else {
+        return f.render_widget(Paragraph::new(Span::styled("loading history…", dim)).alignment(ratatui::layout::Alignment::Center), rows[1]);
+    };
+    let Some((min, max)) = heatmap.range() else {
+        let text = "no temperature history yet (history_enabled: true logs a reading every history_interval seconds)";
+        f.render_widget(Paragraph::new(Span::styled(text, dim)).alignment(ratatui::layout::Alignment::Center), rows[1]);
+        return;
+    };
+    // 日期标签占 10 列；宽度足够时每小时两列
+    const LABEL_WIDTH: usize = 10;
+    let cell = if rows[1].width as usize >= LABEL_WIDTH + 48 { 2 } else { 1 };
+    let mut header = " ".repeat(LABEL_WIDTH);
+    for hour in (0..24).step_by(3) {
+        header.push_str(&format!("{:<width$}", format!("{:02}", hour), width = 3 * cell));
+    }
+    let mut lines = vec![Line::from(Span::styled(header, dim))];
+    for (date, hours) in &heatmap.days {
+        let mut spans = vec![Span::styled(format!("{:<LABEL_WIDTH$}", date.format("%a %m/%d")), dim)];
+        spans.extend(hours.iter().map(|value| match value {
+            Some(temp) => Span::styled("█".repeat(cell), Style::default().fg(heat_color(*temp, min, max))),
+            None => Span::styled(format!("{:<cell$}", "·"), dim),
+        }));
+        lines.push(Line::from(spans));
+    }
+    // 图例：最低到最高温度的色阶
+    let mut legend = vec![Span::styled(format!("{:<LABEL_WIDTH$}", ""), dim), Span::styled(format!("{:.1}℃ ", min), dim)];
+    legend.extend(HEAT_COLORS.iter().map(|&color| Span::styled("██", Style::default().fg(color))));
+    legend.push(Span::styled(format!(" {:.1}℃", max), dim));
+    lines.push(Line::from(""));
+    lines.push(Line::from(legend));
+
+    let width = (LABEL_WIDTH + 24 * cell) as u16;
+    let height = lines.len() as u16;
+    let grid = align_rect(rows[1], width, None);
+    let top = rows[1].y + rows[1].height.saturating_sub(height) / 2;
+    f.render_widget(Paragraph::new(lines), Rect { y: top, height: height.min(rows[1].bottom() - top), ..grid });
+}
+
+// 热力图色阶，从低到高
+const HEAT_COLORS: [Color; 6] = [Color::Blue, Color::Cyan, Color::Green, Color::Yellow, Color::LightRed, Color::Red];
+
+fn heat_color(temp: f64, min: f64, max: f64) -> Color {
+    let fraction = if max > min { (temp - min) / (max - min) } else { 0.5 };
+    HEAT_COLORS[(fraction * (HEAT_COLORS.len() - 1) as f64).round() as usize]
+}
+
+// 湿度色带：0-100% 按阈值分为干燥（warn）、舒适（ok）、潮湿（info）三段，● 为当前湿度
+fn draw_comfort_widget(f: &mut Frame, area: Rect, humidity: f64, temp: Option<f64>, thresholds: &ComfortThresholds, palette: &Palette) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let band_color = |pct: f64| match pct {
+        p if p < thresholds.dry_below => palette.warn,
+        p if p > thresholds.humid_above => palette.info,
+        _ => palette.ok,
+    };
+    let comfort = crate::comfort::classify(humidity, temp, thresholds);
+    let color = match comfort {
+        Comfort::Dry => palette.warn,
+        Comfort::Comfortable => palette.ok,
+        Comfort::Humid => palette.info,
+    };
+    let left = format!("💧 {:.0}% ", humidity);
+    let mut right = format!(" {}", comfort.label());
+    if let Some(t) = temp {
+        right.push_str(&format!(" (dew point {:.0}℃)", crate::comfort::dew_point(t, humidity)));
+    }
+    let width = (area.width as usize).saturating_sub(left.width() + right.width() + 2).clamp(10, 30);
+    let current = ((humidity.clamp(0.0, 100.0) / 100.0) * (width - 1) as f64).round() as usize;
+    let mut spans = vec![Span::styled(left, dim)];
+    for i in 0..width {
+        let pct = (i as f64 + 0.5) * 100.0 / width as f64;
+        spans.push(match i == current {
+            true => Span::styled("●", Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+            false => Span::styled("▬", Style::default().fg(band_color(pct))),
+        });
+    }
+    spans.push(Span::styled(right, Style::default().fg(color)));
+    f.render_widget(Paragraph::new(Line::from(spans)).alignment(ratatui::layout::Alignment::Center), area);
+}
+
+// 湿度条：0-100% 刻度，舒适区间的刻度线加粗、条的空白处用 ░ 填充，▼▲ 为今天的最低、最高湿度
+fn draw_humidity_widget(
+    f: &mut Frame,
+    area: Rect,
+    humidity: f64,
+    range: Option<DailyRange>,
+    thresholds: &ComfortThresholds,
+    gauge: &HumidityGauge,
+    align: Option<ratatui::layout::Alignment>,
+) {
+    let area = align_rect(area, thermometer_width(area.width as usize) as u16, align);
+    let width = area.width as usize;
+    // 放不下刻度标签时不显示
+    if width < 10 {
+        return;
+    }
+    let index = |pct: f64| ((pct.clamp(0.0, 100.0) / 100.0) * (width - 1) as f64).round() as usize;
+    let in_zone = |i: usize| (thresholds.dry_below..=thresholds.humid_above).contains(&(i as f64 * 100.0 / (width - 1) as f64));
+    let (scale, zone) = (Style::default().fg(gauge.scale), Style::default().fg(gauge.zone));
+
+    let mut labels = vec![' '; width];
+    let mut ticks: Vec<char> = (0..width).map(|i| if in_zone(i) { '━' } else { '─' }).collect();
+    for pct in (0..=100).step_by(20) {
+        let (text, idx) = (pct.to_string(), index(pct as f64));
+        ticks[idx] = '┴';
+        let start = idx.saturating_sub(text.len() / 2).min(width - text.len());
+        labels.splice(start..start + text.len(), text.chars());
+    }
+    if let Some(range) = range {
+        ticks[index(range.min)] = '▼';
+        ticks[index(range.max)] = '▲';
+    }
+    let tick_spans: Vec<Span> = ticks
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| Span::styled(c.to_string(), if in_zone(i) && c == '━' { zone } else { scale }))
+        .collect();
+
+    let bar_len = ((humidity.clamp(0.0, 100.0) / 100.0) * width as f64).round() as usize;
+    let label = format!(" {:.0}%", humidity);
+    let label_at = bar_len.min(width.saturating_sub(label.len()));
+    let cell = |i: usize| match (i < bar_len, in_zone(i)) {
+        (true, _) => Span::styled("━", Style::default().fg(gauge.bar).add_modifier(Modifier::BOLD)),
+        (false, true) => Span::styled("░", zone),
+        (false, false) => Span::raw(" "),
+    };
+    let label_end = label_at + label.len();
+    let mut bar_spans: Vec<Span> = (0..label_at).map(cell).collect();
+    bar_spans.push(Span::styled(label, Style::default().fg(gauge.bar).add_modifier(Modifier::BOLD)));
+    bar_spans.extend((label_end..width).map(cell));
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut caption = vec![Span::styled("░", zone), Span::styled(format!(" comfort {:.0}-{:.0}%", thresholds.dry_below, thresholds.humid_above), dim)];
+    if let Some(range) = range {
+        caption.push(Span::styled(format!("  ▼ min {:.0}% / ▲ max {:.0}%", range.min, range.max), dim));
+    }
+    let lines = vec![
+        Line::from(Span::styled(labels.into_iter().collect::<String>(), scale)),
+        Line::from(tick_spans),
+        Line::from(bar_spans),
+        Line::from(caption),
+    ];
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+// 本地传感器：超过 STALE_AFTER_SECS 未更新的变暗，还没有读数时显示错误或 …
+fn draw_sensors_widget(f: &mut Frame, area: Rect, readings: &[SensorReading], now: chrono::DateTime<chrono::Local>, palette: &Palette) {
+    let mut spans = vec![Span::styled("🌡 ", Style::default().fg(Color::DarkGray))];
+    for (i, reading) in readings.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        }
+        let stale = reading.updated.is_none_or(|t| (now - t).num_seconds() > crate::sensors::STALE_AFTER_SECS);
+        let value = match (reading.temp, reading.humidity) {
+            (Some(t), Some(h)) => format!("{:.1}℃ {:.0}%", t, h),
+            (Some(t), None) => format!("{:.1}℃", t),
+            (None, Some(h)) => format!("{:.0}%", h),
+            (None, None) => reading.error.clone().unwrap_or_else(|| "…".to_string()),
+        };
+        let style = match (stale, reading.temp.is_some() || reading.humidity.is_some()) {
+            (_, false) if reading.error.is_some() => Style::default().fg(palette.alert),
+            (true, _) => Style::default().fg(palette.stale),
+            (false, _) => Style::default().fg(Color::White),
+        };
+        spans.push(Span::styled(format!("{} ", reading.label), Style::default().fg(Color::Gray)));
+        spans.push(Span::styled(value, style));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)).alignment(ratatui::layout::Alignment::Center), area);
+}
+
+// 每日时刻：已过的灰色，下一个黄色加倒计时；放不下时先省略已过的
+fn draw_markers_widget(f: &mut Frame, area: Rect, markers: &[DailyMarker], now: chrono::DateTime<chrono::Local>, palette: &Palette) {
+    let next = crate::markers::next_index(markers, now);
+    let item = |i: usize, marker: &DailyMarker| -> Span {
+        let label = format!("{} {}", marker.name, marker.time.format("%H:%M"));
+        match next {
+            Some(n) if n == i => {
+                let mins = crate::markers::today_at(marker, now).map_or(0, |at| ((at - now).num_seconds() as u64).div_ceil(60));
+                let when = if mins < 60 { format!("{} min", mins) } else { format!("{}h{:02}m", mins / 60, mins % 60) };
+                Span::styled(format!("▶ {} (in {})", label, when), Style::default().fg(palette.warn).add_modifier(Modifier::BOLD))
+            }
+            Some(n) if i > n => Span::styled(label, Style::default().fg(Color::Gray)),
+            _ => Span::styled(label, Style::default().fg(palette.stale)),
+        }
+    };
+    let build = |skip_passed: bool| -> Vec<Span> {
+        let mut spans = Vec::new();
+        for (i, marker) in markers.iter().enumerate() {
+            if skip_passed && next.is_some_and(|n| i < n) {
+                continue;
+            }
+            if !spans.is_empty() {
+                spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+            }
+            spans.push(item(i, marker));
+        }
+        spans
+    };
+    let mut spans = build(false);
+    if Line::from(spans.clone()).width() > area.width as usize {
+        spans = build(true);
+    }
+    let para = Paragraph::new(Line::from(spans)).alignment(ratatui::layout::Alignment::Center);
+    f.render_widget(para, area);
+}
+
+fn draw_meeting_widget(f: &mut Frame, area: Rect, status: &GcalStatus, palette: &Palette) {
+    let (text, color) = match status {
+        GcalStatus::Connecting => ("📅 connecting to Google Calendar…".to_string(), Color::DarkGray),
+        GcalStatus::AuthPending { url, code } => (format!("📅 visit {} and enter {}", url, code), palette.warn),
+        GcalStatus::Error(e) => (format!("📅 calendar error: {}", e), palette.stale),
+        GcalStatus::Ready(None) => ("📅 no upcoming meetings".to_string(), Color::DarkGray),
+        GcalStatus::Ready(Some(m)) => {
+            let mins = (m.start - chrono::Local::now()).num_minutes();
+            let when = if mins <= 0 { "now".to_string() } else if mins < 60 { format!("in {} min", mins) } else { format!("in {}h{:02}m", mins / 60, mins % 60) };
+            let color = if mins <= 5 { palette.alert } else { Color::Cyan };
+            (format!("📅 {} {} ({})", m.start.format("%H:%M"), m.title, when), color)
+        }
+    };
+    let para = Paragraph::new(Span::styled(text, Style::default().fg(color)))
+        .alignment(ratatui::layout::Alignment::Center);
+    f.render_widget(para, area);
+}
+
+// 绘制温度组件：按 thermometer_style 选择样式，均占四行
 fn draw_temperature_widget(
     f: &mut Frame,
     area: Rect,
     parsed_temp: Option<i32>,
+    range: Option<DailyRange>,
+    align: Option<ratatui::layout::Alignment>,
+    accent: Option<Color>,
+    style: ThermometerStyle,
 ) {
-    // Dual-line thermometer centered to 80% width: top labels, mid ticks, bottom bar
-    let width = area.width as usize;
+    // 天气强调色替换刻度和温度条的颜色
+    let scale = Style::default().fg(accent.unwrap_or(Color::LightRed));
+    let reading = Style::default().fg(accent.unwrap_or(Color::Yellow)).add_modifier(Modifier::BOLD);
+    let caption = range.map(|range| Line::from(Span::styled(range_caption(range), Style::default().fg(Color::DarkGray))));
+    match style {
+        ThermometerStyle::Bar | ThermometerStyle::Gauge => {
+            let area = align_rect(area, thermometer_width(area.width as usize) as u16, align);
+            let [labels, mut ticks, bar] = thermometer_layout(area.width as usize, parsed_temp);
+            if let Some(range) = range {
+                mark_range(&mut ticks, range);
+            }
+            let mut lines = vec![Line::from(Span::styled(labels, scale)), Line::from(Span::styled(ticks, scale))];
+            if style == ThermometerStyle::Bar {
+                lines.push(Line::from(Span::styled(bar, reading)));
+            } else {
+                // 第三行留给 Gauge
+                lines.push(Line::from(""));
+                let ratio = parsed_temp.map_or(0.0, |v| thermometer_fraction(v as f64));
+                let label = parsed_temp.map_or_else(|| "--".to_string(), |v| format!("{v}℃"));
+                let gauge = Gauge::default().gauge_style(reading).ratio(ratio).label(label).use_unicode(true);
+                f.render_widget(gauge, Rect { y: area.y + 2, height: 1, ..area }.intersection(area));
+            }
+            lines.extend(caption);
+            f.render_widget(Paragraph::new(lines).alignment(ratatui::layout::Alignment::Left), area);
+        }
+        ThermometerStyle::Dial => {
+            let label = parsed_temp.map_or_else(|| "--".to_string(), |v| format!("{v}℃"));
+            let mut lines = vec![Line::from(""), Line::from(Span::styled(label, reading))];
+            lines.extend(caption);
+            draw_beside(f, area, align, dial_width(area.height), lines, |f, rect| draw_dial(f, rect, parsed_temp, scale, reading));
+        }
+        ThermometerStyle::Number => {
+            let text = parsed_temp.map_or_else(|| "--".to_string(), |v| v.to_string());
+            let digits: Vec<Line> = render_big_time(&text, &BigTextStyle { font: TimeFont::Blocks, ..BigTextStyle::new(1, 1) })
+                .into_iter()
+                .map(|row| Line::from(Span::styled(row, reading)))
+                .collect();
+            let width = digits.iter().map(|line| line.width()).max().unwrap_or(0) as u16;
+            let mut lines = vec![Line::from(Span::styled("℃", reading)), Line::from("")];
+            lines.extend(caption);
+            draw_beside(f, area, align, width, lines, |f, rect| f.render_widget(Paragraph::new(digits), rect));
+        }
+    }
+}
+
+// 左侧宽度为 width 的图形加右侧文字，整体按对齐方式放置
+fn draw_beside(
+    f: &mut Frame,
+    area: Rect,
+    align: Option<ratatui::layout::Alignment>,
+    width: u16,
+    lines: Vec<Line>,
+    draw: impl FnOnce(&mut Frame, Rect),
+) {
+    let text_width = lines.iter().map(|line| line.width()).max().unwrap_or(0) as u16;
+    let area = align_rect(area, width + 2 + text_width, align);
+    let graphic = Rect { width: width.min(area.width), ..area };
+    draw(f, graphic);
+    let text = Rect { x: graphic.right() + 2, width: area.width.saturating_sub(graphic.width + 2), ..area };
+    f.render_widget(Paragraph::new(lines), text);
+}
+
+// 半圆表盘的宽度：盲文点阵每格 2×4 个点，宽为高的四倍时为正圆
+fn dial_width(height: u16) -> u16 {
+    height * 4
+}
+
+// 温度计表盘：-10℃ 在左端、50℃ 在右端的半圆刻度，每 10℃ 一个刻度，指针指向当前温度
+fn draw_dial(f: &mut Frame, area: Rect, parsed_temp: Option<i32>, scale: Style, reading: Style) {
+    use ratatui::widgets::canvas::{Canvas, Line as CanvasLine, Points};
+    use std::f64::consts::PI;
+    let scale_color = scale.fg.unwrap_or(Color::Reset);
+    let needle_color = reading.fg.unwrap_or(Color::Reset);
+    let angle = |celsius: f64| PI * (1.0 - thermometer_fraction(celsius));
+    let canvas = Canvas::default()
+        .marker(ratatui::symbols::Marker::Braille)
+        .x_bounds([-1.0, 1.0])
+        .y_bounds([0.0, 1.0])
+        .paint(|ctx| {
+            let arc: Vec<(f64, f64)> = (0..=120).map(|i| PI * i as f64 / 120.0).map(|a| (a.cos(), a.sin())).collect();
+            ctx.draw(&Points { coords: &arc, color: scale_color });
+            for deg in (THERMOMETER_MIN_C as i32..=THERMOMETER_MAX_C as i32).step_by(10) {
+                let a = angle(deg as f64);
+                ctx.draw(&CanvasLine { x1: 0.8 * a.cos(), y1: 0.8 * a.sin(), x2: a.cos(), y2: a.sin(), color: scale_color });
+            }
+            if let Some(v) = parsed_temp {
+                // 指针画在上一层，与刻度重叠的单元格使用指针颜色
+                ctx.layer();
+                let a = angle(v as f64);
+                ctx.draw(&CanvasLine { x1: 0.0, y1: 0.0, x2: 0.7 * a.cos(), y2: 0.7 * a.sin(), color: needle_color });
+            }
+        });
+    f.render_widget(canvas, area);
+}
+
+// 室内外对比：共用刻度，室内（黄）和室外（青）两条温度条，下方显示温差
+fn draw_indoor_outdoor_widget(f: &mut Frame, area: Rect, indoor: &str, outdoor: &str, range: Option<DailyRange>, align: Option<ratatui::layout::Alignment>, accent: Option<Color>) {
+    let (indoor, outdoor) = (crate::temperature::parse_celsius(indoor), crate::temperature::parse_celsius(outdoor));
+    let rounded = |v: Option<f64>| v.map(|c| c.round() as i32);
+    let area = align_rect(area, thermometer_width(area.width as usize) as u16, align);
+    let [labels, mut ticks, indoor_bar] = thermometer_layout(area.width as usize, rounded(indoor));
+    if let Some(range) = range {
+        mark_range(&mut ticks, range);
+    }
+    let [_, _, outdoor_bar] = thermometer_layout(area.width as usize, rounded(outdoor));
+    let row = |text: String, style: Style| Line::from(Span::styled(text, style));
+    let indoor_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let outdoor_style = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut caption = vec![
+        Span::styled("━ indoor", indoor_style),
+        Span::styled("  ", dim),
+        Span::styled("━ outdoor", outdoor_style),
+    ];
+    if let (Some(i), Some(o)) = (indoor, outdoor) {
+        let delta = (i - o).round() as i32;
+        let text = match delta {
+            0 => "  same inside and out".to_string(),
+            _ => format!("  {:+}℃ inside", delta),
+        };
+        caption.push(Span::styled(text, Style::default().fg(Color::White)));
+    }
+    if let Some(range) = range {
+        caption.push(Span::styled(format!("  {}", range_caption(range)), dim));
+    }
+    let lines = vec![
+        // 两条温度条保持各自的颜色，只有刻度使用天气强调色
+        row(labels, Style::default().fg(accent.unwrap_or(Color::LightRed))),
+        row(ticks, Style::default().fg(accent.unwrap_or(Color::LightRed))),
+        row(indoor_bar, indoor_style),
+        row(outdoor_bar, outdoor_style),
+        Line::from(caption),
+    ];
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+// 今天最低、最高温度说明
+fn range_caption(range: DailyRange) -> String {
+    format!("▼ min {}℃ / ▲ max {}℃", range.min.round() as i32, range.max.round() as i32)
+}
+
+// 在刻度线上标出今天的最低（▼）和最高（▲）温度
+fn mark_range(ticks: &mut String, range: DailyRange) {
+    let mut chars: Vec<char> = ticks.chars().collect();
+    let usable = chars.len();
+    for (value, mark) in [(range.min, '▼'), (range.max, '▲')] {
+        let idx = (thermometer_fraction(value) * usable as f64).round() as usize;
+        if let Some(ch) = chars.get_mut(idx.min(usable.saturating_sub(1))) {
+            *ch = mark;
+        }
+    }
+    *ticks = chars.into_iter().collect();
+}
+
+// 温度计刻度范围（℃）
+const THERMOMETER_MIN_C: f64 = -10.0;
+const THERMOMETER_MAX_C: f64 = 50.0;
+
+// 温度在刻度上的位置（0..=1）
+fn thermometer_fraction(celsius: f64) -> f64 {
+    ((celsius - THERMOMETER_MIN_C) / (THERMOMETER_MAX_C - THERMOMETER_MIN_C)).clamp(0.0, 1.0)
+}
+
+// 温度计宽度：90% 宽度，至少 30 列
+fn thermometer_width(width: usize) -> usize {
     let mut usable = ((width as f64) * 0.9).round() as usize;
     if usable > width { usable = width; }
     if usable < 30 { usable = 30.min(width); }
-    let pad = width.saturating_sub(usable) / 2;
-    let min_c = -10.0f64;
-    let max_c = 50.0f64;
-    let pos = parsed_temp.map(|v| ((v as f64 - min_c) / (max_c - min_c)).clamp(0.0,1.0)).unwrap_or(0.0);
+    usable
+}
+
+// 温度计布局：宽度为 width 的三行文本（刻度标签、刻度线、温度条），位置由调用方的布局决定
+pub fn thermometer_layout(width: usize, parsed_temp: Option<i32>) -> [String; 3] {
+    let usable = width;
+    let min_c = THERMOMETER_MIN_C;
+    let max_c = THERMOMETER_MAX_C;
+    let pos = parsed_temp.map(|v| thermometer_fraction(v as f64)).unwrap_or(0.0);
     let bar_len = (pos * (usable as f64)).round() as usize;
 
     let mut tick_chars: Vec<char> = vec!['─'; usable];
@@ -109,28 +1416,13 @@ fn draw_temperature_widget(
             if start + i < usable { label_chars[start + i] = ch; }
         }
     }
-    let pad_str = " ".repeat(pad);
-    let labels_line = Line::from(vec![
-        Span::raw(pad_str.clone()),
-        Span::styled(label_chars.into_iter().collect::<String>(), Style::default().fg(Color::LightRed)),
-    ]);
-    let ticks_line = Line::from(vec![
-        Span::raw(pad_str.clone()),
-        Span::styled(tick_chars.into_iter().collect::<String>(), Style::default().fg(Color::LightRed)),
-    ]);
 
     let mut bottom_chars: Vec<char> = vec![' '; usable];
-    for i in 0..usable { if i < bar_len { bottom_chars[i] = '━'; } }
+    for ch in bottom_chars.iter_mut().take(bar_len) { *ch = '━'; }
     let label = parsed_temp.map(|v| format!(" {v}℃")).unwrap_or_else(|| " --".to_string());
     let overlay_at = bar_len.min(usable.saturating_sub(label.len()));
     for (i, ch) in label.chars().enumerate() { if overlay_at + i < usable { bottom_chars[overlay_at + i] = ch; } }
-    let bottom_line = Line::from(vec![
-        Span::raw(pad_str.clone()),
-        Span::styled(bottom_chars.into_iter().collect::<String>(), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-    ]);
-
-    let temp_widget = Paragraph::new(vec![labels_line, ticks_line, bottom_line]).alignment(ratatui::layout::Alignment::Left);
-    f.render_widget(temp_widget, area);
+    [label_chars.into_iter().collect(), tick_chars.into_iter().collect(), bottom_chars.into_iter().collect()]
 }
 
 // 绘制待办事项组件
@@ -139,58 +1431,378 @@ fn draw_todos_widget(
     area: Rect,
     app: &App,
 ) {
-    // Todo 居中 80% 且区域内左对齐
-    let width = area.width as usize;
-    let mut usable = ((width as f64) * 0.8).round() as usize;
-    if usable > width { usable = width; }
-    let pad = width.saturating_sub(usable) / 2;
-    let pad_str = " ".repeat(pad);
+    // Todo 占 80% 宽度（按 widgets.todos.align 放置，默认居中），列内左对齐
+    let align = app.config.widgets.get("todos").and_then(|s| s.align);
+    let area = align_rect(area, ((area.width as f64) * 0.8).round() as u16, align);
 
-    let mut max_chars: Option<usize> = None;
-    if let Some(cfg) = crate::config::load_yaml_config() { 
-        max_chars = cfg.todo_task_max_chars; 
-    }
+    let max_chars = app.config.todo_task_max_chars;
     let truncate = |s: &str| -> String {
-        if let Some(m) = max_chars { 
-            if s.chars().count() > m { 
-                let mut c = s.chars(); 
-                return c.by_ref().take(m).collect::<String>() + "…"; 
-            } 
+        if let Some(m) = max_chars
+            && s.chars().count() > m
+        {
+            let mut c = s.chars();
+            return c.by_ref().take(m).collect::<String>() + "…";
         }
         s.to_string()
     };
 
-    let items: Vec<ListItem> = if app.todos.is_empty() {
-        vec![ListItem::new(Span::raw(format!("{}(no todos)", pad_str)))]
-    } else {
-        app.todos
-            .iter()
-            .map(|t| {
-                let content = truncate(t);
-                ListItem::new(Span::styled(format!("{}{}", pad_str, content), Style::default().fg(app.config.todos_color)))
-            })
-            .collect()
-    };
+    let mut area = area;
+    if let Some(summary) = app.todo_summary {
+        let text = format!("✓ {} done today / {} remaining", summary.done_today, summary.remaining);
+        f.render_widget(Paragraph::new(Span::styled(text, Style::default().fg(app.config.palette.ok))), Rect { height: 1.min(area.height), ..area });
+        area.y += 1.min(area.height);
+        area.height = area.height.saturating_sub(1);
+    }
+
+    let today = app.now().date_naive();
+    let visible = app.visible_todos();
+    let grouped = app.config.todo_group && has_due_dates(&app.todos, today);
+    let header_style = Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM);
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut current_group = None;
+    let mut selected_row = None;
+    for (i, t) in visible {
+        let item = TodoItem::parse(t);
+        // 分组标题（列表已按分组排序）
+        if grouped && current_group != Some(item.group(today)) {
+            current_group = Some(item.group(today));
+            // 逾期分组标题使用 alert 颜色
+            let style = match item.group(today) {
+                DueGroup::Overdue => Style::default().fg(app.config.palette.alert).add_modifier(Modifier::BOLD),
+                _ => header_style,
+            };
+            items.push(ListItem::new(Span::styled(item.group(today).label().to_string(), style)));
+        }
+        // 合并多个来源时：来源颜色作为默认颜色，标签显示在前
+        let source = app.todo_origins.get(t).and_then(|&i| app.config.todo_sources.get(i));
+        let base_color = source.and_then(|s| s.color).unwrap_or(app.config.todos_color);
+        let (content, color) = if app.config.todo_priority {
+            let color = priority_color(item.priority, &app.config.todo_priority_colors, base_color);
+            (truncate(&item.display()), color)
+        } else {
+            (truncate(t), base_color)
+        };
+        let mut style = Style::default().fg(color);
+        if app.selected_todo == Some(i) {
+            style = style.add_modifier(Modifier::REVERSED);
+            selected_row = Some(items.len());
+        }
+        let indent = if grouped { "  " } else { "" };
+        let mut spans = vec![Span::raw(indent)];
+        if let Some(tag) = source.and_then(|s| s.tag.as_ref()) {
+            spans.push(Span::styled(format!("[{}] ", tag), Style::default().fg(base_color).add_modifier(Modifier::DIM)));
+        }
+        spans.push(Span::styled(content, style));
+        items.push(ListItem::new(Line::from(spans)));
+    }
+    // 放不下时分页：有选中项时显示其所在页，否则按 todo_rotate_secs 轮换
+    let height = area.height as usize;
+    if let Some(secs) = app.config.todo_rotate_secs
+        && items.len() > height
+        && height > 1
+    {
+        let page_size = height - 1;
+        let pages = items.len().div_ceil(page_size);
+        let page = match selected_row {
+            Some(row) => row / page_size,
+            None => (app.now().timestamp() as u64 / secs) as usize % pages,
+        };
+        let page_items: Vec<ListItem> = items.drain(..).skip(page * page_size).take(page_size).collect();
+        f.render_widget(List::new(page_items), Rect { height: page_size as u16, ..area });
+        let indicator = format!("{}/{}", page + 1, pages);
+        let rect = Rect { y: area.y + page_size as u16, height: 1, ..area };
+        f.render_widget(
+            Paragraph::new(Span::styled(indicator, Style::default().fg(Color::DarkGray))).alignment(ratatui::layout::Alignment::Right),
+            rect,
+        );
+        return;
+    }
+    if items.is_empty() {
+        let empty = match &app.todo_filter {
+            Some(filter) => format!("(no todos matching \"{}\")", filter),
+            None => "(no todos)".to_string(),
+        };
+        items.push(ListItem::new(Span::raw(empty)));
+    }
     let todos_widget = List::new(items);
     f.render_widget(todos_widget, area);
 }
 
-// 解析温度值
-fn parse_temp_celsius(s: &str) -> Option<i32> {
-    // Accept formats like "29℃", "29°C", "29", "24.5℃", etc.
-    let trimmed = s.trim().trim_end_matches('C').trim_end_matches('°').trim_end_matches('℃').trim();
-    // 先尝试解析为f64，然后转换为i32
-    if let Ok(temp_f) = trimmed.parse::<f64>() {
-        Some(temp_f.round() as i32)
-    } else {
-        trimmed.parse::<i32>().ok()
+// "12:34:56" 中各字符的颜色：冒号分隔为时、分、秒，未配置的沿用 time_color
+fn time_colors_for(time: &str, face: &ClockFace) -> Vec<Color> {
+    let colors = &face.time_colors;
+    let mut field = 0;
+    time.chars()
+        .map(|c| {
+            let color = match (c, field) {
+                (':', _) => colors.separators,
+                (_, 0) => colors.hours,
+                (_, 1) => colors.minutes,
+                _ => colors.seconds,
+            };
+            if c == ':' {
+                field += 1;
+            }
+            color.unwrap_or(face.time_color)
+        })
+        .collect()
+}
+
+// 翻页动画的一帧：只改变发生变化的字符，上半部分为翻下的页、下半部分为旧字
+// 帧 0：旧字上半页向下折叠；帧 1：页面与折线重合，上半露出新字；帧 2：新字下半页展开
+fn flip_lines(prev: &[String], next: &[String], prev_str: &str, next_str: &str, width: usize, offset: usize, frame: usize) -> Vec<String> {
+    let mut old: Vec<Vec<char>> = prev.iter().map(|l| l.chars().collect()).collect();
+    let new: Vec<Vec<char>> = next.iter().map(|l| l.chars().collect()).collect();
+    let height = old.len().min(new.len());
+    let mid = height / 2;
+    // 把 [from, to) 行压缩为 len 行：返回每行取样的源行号
+    let squash = |from: usize, to: usize, len: usize| -> Vec<usize> {
+        (0..len).map(|j| from + j * (to - from) / len.max(1)).collect()
+    };
+    for (i, (a, b)) in prev_str.chars().zip(next_str.chars()).enumerate() {
+        if a == b {
+            continue;
+        }
+        let cols = offset + i * width..offset + (i + 1) * width;
+        let copy = |dst: &mut Vec<Vec<char>>, row: usize, src: &[Vec<char>], src_row: usize| {
+            for c in cols.clone() {
+                if let (Some(cell), Some(&ch)) = (dst[row].get_mut(c), src[src_row].get(c)) {
+                    *cell = ch;
+                }
+            }
+        };
+        let before = old.clone();
+        match frame {
+            0 => {
+                // 上半：新字上部 + 压缩到下半区的旧字上半页
+                let fold = mid / 2;
+                for row in 0..fold {
+                    copy(&mut old, row, &new, row);
+                }
+                for (k, src) in squash(0, mid, mid - fold).into_iter().enumerate() {
+                    copy(&mut old, fold + k, &before, src);
+                }
+            }
+            1 => {
+                for row in 0..mid {
+                    copy(&mut old, row, &new, row);
+                }
+                if mid < height {
+                    for c in cols.clone() {
+                        if let Some(cell) = old[mid].get_mut(c) {
+                            *cell = '─';
+                        }
+                    }
+                }
+            }
+            _ => {
+                // 新字上半 + 压缩的新字下半页（覆盖旧字下半的上部）
+                for row in 0..mid {
+                    copy(&mut old, row, &new, row);
+                }
+                let len = (height - mid).div_ceil(2);
+                for (k, src) in squash(mid, height, len).into_iter().enumerate() {
+                    copy(&mut old, mid + k, &new, src);
+                }
+            }
+        }
+    }
+    old.into_iter().map(String::from_iter).collect()
+}
+
+// 大字中每个源字符占用的列数（字形宽度 + 字符间隔）
+fn big_char_width(scale_x: u16) -> usize {
+    9 * scale_x.max(1) as usize
+}
+
+fn glyph_index(ch: char) -> usize {
+    match ch {
+        '0'..='9' => ch as usize - '0' as usize,
+        ':' => 10,
+        '-' => 12,
+        '°' => 13,
+        _ => 11,
+    }
+}
+
+// 大字渲染参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BigTextStyle {
+    pub scale_x: u16,
+    pub scale_y: u16,
+    pub effect: TimeEffect,
+    pub font: TimeFont,
+}
+
+impl BigTextStyle {
+    pub fn new(scale_x: u16, scale_y: u16) -> Self {
+        Self { scale_x, scale_y, effect: TimeEffect::None, font: TimeFont::Default }
     }
 }
 
+// 点阵字体的亮点/暗点
+const DOT_ON: char = '●';
+const DOT_OFF: char = '·';
+
+// 阴影、描边单元格（绘制时替换为 time_effect_color）
+const SHADOW_CELL: char = '▓';
+const OUTLINE_CELL: char = '▒';
+
+// 大字缓存：按 (字符, sx, sy) 缓存缩放后的字形行；时间字符串和样式不变时直接复用上次拼好的行
+#[derive(Default)]
+struct GlyphCache {
+    glyphs: HashMap<(char, TimeFont, usize, usize), Vec<String>>,
+    last: Option<(String, BigTextStyle, Vec<String>)>,
+}
+
+thread_local! {
+    static GLYPH_CACHE: RefCell<GlyphCache> = RefCell::new(GlyphCache::default());
+}
+
 // 渲染大字体时间
-fn render_big_time(time: &str, scale_x: u16, scale_y: u16) -> Vec<String> {
+pub fn render_big_time(time: &str, style: &BigTextStyle) -> Vec<String> {
+    GLYPH_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some((t, s, rows)) = &cache.last
+            && t == time
+            && s == style
+        {
+            return rows.clone();
+        }
+        let rows = compose_big_time(time, style, &mut cache.glyphs);
+        let rows = apply_effect(rows, style.effect);
+        cache.last = Some((time.to_string(), *style, rows.clone()));
+        rows
+    })
+}
+
+// 阴影：笔画向右下偏移一格（右侧、底部各多占一格）；描边：笔画周围一圈（四周各多占一格）
+fn apply_effect(rows: Vec<String>, effect: TimeEffect) -> Vec<String> {
+    let (before, after) = match effect {
+        TimeEffect::None => return rows,
+        TimeEffect::Shadow => (0, 1),
+        TimeEffect::Outline => (1, 1),
+    };
+    let width = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0) + before + after;
+    let blank = vec![' '; width];
+    let mut grid: Vec<Vec<char>> = vec![blank.clone(); before];
+    for row in &rows {
+        let mut cells = vec![' '; before];
+        cells.extend(row.chars());
+        cells.resize(width, ' ');
+        grid.push(cells);
+    }
+    grid.extend(std::iter::repeat_n(blank, after));
+    let filled = |g: &[Vec<char>], r: isize, c: isize| {
+        r >= 0 && c >= 0 && g.get(r as usize).and_then(|row| row.get(c as usize)).is_some_and(|&c| is_ink(c))
+    };
+    let source = grid.clone();
+    for (r, row) in grid.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            if *cell != ' ' && *cell != DOT_OFF {
+                continue;
+            }
+            let (r, c) = (r as isize, c as isize);
+            *cell = match effect {
+                TimeEffect::Shadow if filled(&source, r - 1, c - 1) => SHADOW_CELL,
+                TimeEffect::Outline if (-1..=1).any(|dr| (-1..=1).any(|dc| filled(&source, r + dr, c + dc))) => OUTLINE_CELL,
+                _ => continue,
+            };
+        }
+    }
+    grid.into_iter().map(|row| row.into_iter().collect()).collect()
+}
+
+// 四分格方块，下标为 左上<<3 | 右上<<2 | 左下<<1 | 右下
+const QUADRANTS: [char; 16] = [' ', '▗', '▖', '▄', '▝', '▐', '▞', '▟', '▘', '▚', '▌', '▙', '▀', '▜', '▛', '█'];
+
+// 笔画单元格（阴影/描边围绕这些单元格计算；冒号的 ░ 不算）
+fn is_ink(c: char) -> bool {
+    c == DOT_ON || (c != ' ' && QUADRANTS.contains(&c)) || ('\u{2801}'..='\u{28ff}').contains(&c)
+}
+
+// Scale2x：每个像素放大为 2x2，按上下左右邻居补齐斜边，避免锯齿
+fn scale2x(bitmap: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let h = bitmap.len();
+    let w = bitmap.first().map_or(0, Vec::len);
+    let mut out = vec![vec![false; w * 2]; h * 2];
+    for y in 0..h {
+        for x in 0..w {
+            let p = bitmap[y][x];
+            let a = if y > 0 { bitmap[y - 1][x] } else { p };
+            let b = if x + 1 < w { bitmap[y][x + 1] } else { p };
+            let c = if x > 0 { bitmap[y][x - 1] } else { p };
+            let d = if y + 1 < h { bitmap[y + 1][x] } else { p };
+            out[2 * y][2 * x] = if c == a && c != d && a != b { a } else { p };
+            out[2 * y][2 * x + 1] = if a == b && a != c && b != d { b } else { p };
+            out[2 * y + 1][2 * x] = if d == c && d != b && c != a { c } else { p };
+            out[2 * y + 1][2 * x + 1] = if b == d && b != a && d != c { d } else { p };
+        }
+    }
+    out
+}
+
+// Scale2x 后按缩放倍数放大，再把每 2x2 子像素合成一个四分格字符（占用单元格数与 default 相同）
+fn smooth_glyph(rows: &[&str], sx: usize, sy: usize) -> Vec<String> {
+    let bitmap: Vec<Vec<bool>> = rows.iter().map(|line| line.chars().map(|c| c != ' ').collect()).collect();
+    let fine: Vec<Vec<bool>> = scale2x(&bitmap)
+        .into_iter()
+        .flat_map(|line| {
+            let scaled: Vec<bool> = line.into_iter().flat_map(|p| std::iter::repeat_n(p, sx)).collect();
+            std::iter::repeat_n(scaled, sy)
+        })
+        .collect();
+    fine.chunks(2)
+        .map(|pair| {
+            (0..pair[0].len() / 2)
+                .map(|i| {
+                    let bit = |row: &Vec<bool>, col: usize| usize::from(row[col]);
+                    let index = bit(&pair[0], 2 * i) << 3 | bit(&pair[0], 2 * i + 1) << 2 | bit(&pair[1], 2 * i) << 1 | bit(&pair[1], 2 * i + 1);
+                    QUADRANTS[index]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// 盲文点阵：每个单元格 2×4 个点，点阵大小为 default 单元格数的 (2, 4) 倍；
+// 反复 Scale2x 到不低于点阵分辨率后按比例取样，每 2×4 个点合成一个盲文字符（占用单元格数与 default 相同）
+fn braille_glyph(rows: &[&str], sx: usize, sy: usize) -> Vec<String> {
+    let bitmap: Vec<Vec<bool>> = rows.iter().map(|line| line.chars().map(|c| c != ' ').collect()).collect();
+    let height = bitmap.len() * sy * 4;
+    let width = bitmap.first().map_or(0, Vec::len) * sx * 2;
+    let mut fine = bitmap;
+    while fine.len() < height || fine.first().map_or(0, Vec::len) < width {
+        fine = scale2x(&fine);
+    }
+    let (fine_h, fine_w) = (fine.len(), fine.first().map_or(0, Vec::len));
+    let dot = |x: usize, y: usize| fine[y * fine_h / height][x * fine_w / width];
+    // 点位编号：左列自上而下 1 2 3 7，右列 4 5 6 8
+    const BITS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+    (0..height / 4)
+        .map(|cy| {
+            (0..width / 2)
+                .map(|cx| {
+                    let mut bits = 0;
+                    for (dx, column) in BITS.iter().enumerate() {
+                        for (dy, bit) in column.iter().enumerate() {
+                            if dot(cx * 2 + dx, cy * 4 + dy) {
+                                bits |= bit;
+                            }
+                        }
+                    }
+                    // 空白用空格而不是 U+2800，阴影 / 描边按空格判断
+                    if bits == 0 { ' ' } else { char::from_u32(0x2800 + bits).unwrap_or(' ') }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// 拼接各字符缩放后的字形行，字符间隔两列（随水平缩放）
+fn compose_big_time(time: &str, style: &BigTextStyle, glyphs: &mut HashMap<(char, TimeFont, usize, usize), Vec<String>>) -> Vec<String> {
     // 7-row big digits using a simple ASCII font
-    const FONT: [[&str; 7]; 12] = [
+    const FONT: [[&str; 7]; 14] = [
         // 0
         [
             "  ███  ",
@@ -311,54 +1923,86 @@ fn render_big_time(time: &str, scale_x: u16, scale_y: u16) -> Vec<String> {
             "       ",
             "       ",
         ],
+        // '-'
+        [
+            "       ",
+            "       ",
+            "       ",
+            " █████ ",
+            "       ",
+            "       ",
+            "       ",
+        ],
+        // '°'
+        [
+            "  ███  ",
+            "  █ █  ",
+            "  ███  ",
+            "       ",
+            "       ",
+            "       ",
+            "       ",
+        ],
     ];
 
-    let mut base_rows = vec![String::new(); 7];
+    // scale horizontally and vertically with independent factors
+    let sx = style.scale_x.max(1) as usize;
+    let sy = style.scale_y.max(1) as usize;
+    let gap = " ".repeat(2 * sx);
+    let mut rows: Vec<String> = Vec::new();
     for ch in time.chars() {
-        let idx = match ch {
-            '0' => 0,
-            '1' => 1,
-            '2' => 2,
-            '3' => 3,
-            '4' => 4,
-            '5' => 5,
-            '6' => 6,
-            '7' => 7,
-            '8' => 8,
-            '9' => 9,
-            ':' => 10,
-            _ => 11,
-        };
-        for (r, line) in FONT[idx].iter().enumerate() {
-            if !base_rows[r].is_empty() {
-                base_rows[r].push_str("  ");
+        let glyph = glyphs.entry((ch, style.font, sx, sy)).or_insert_with(|| {
+            let scaled = || -> Vec<Vec<char>> {
+                FONT[glyph_index(ch)]
+                    .iter()
+                    .flat_map(|line| {
+                        let scaled: Vec<char> = line.chars().flat_map(|c| std::iter::repeat_n(c, sx)).collect();
+                        std::iter::repeat_n(scaled, sy)
+                    })
+                    .collect()
+            };
+            match style.font {
+                TimeFont::Default => scaled().into_iter().map(String::from_iter).collect(),
+                TimeFont::Dots => scaled()
+                    .into_iter()
+                    .map(|line| line.into_iter().map(|c| if c == ' ' { DOT_OFF } else { DOT_ON }).collect())
+                    .collect(),
+                // 上下两行像素合并为一个单元格
+                TimeFont::Blocks => scaled()
+                    .chunks(2)
+                    .map(|pair| {
+                        let lower = pair.get(1);
+                        pair[0]
+                            .iter()
+                            .enumerate()
+                            .map(|(i, &top)| match (top != ' ', lower.is_some_and(|l| l[i] != ' ')) {
+                                (true, true) => '█',
+                                (true, false) => '▀',
+                                (false, true) => '▄',
+                                (false, false) => ' ',
+                            })
+                            .collect()
+                    })
+                    .collect(),
+                TimeFont::Smooth => smooth_glyph(&FONT[glyph_index(ch)], sx, sy),
+                TimeFont::Braille => braille_glyph(&FONT[glyph_index(ch)], sx, sy),
             }
-            base_rows[r].push_str(line);
+        });
+        if rows.is_empty() {
+            rows = vec![String::new(); glyph.len()];
         }
-    }
-    // scale horizontally and vertically with independent factors
-    let sx = scale_x.max(1) as usize;
-    let sy = scale_y.max(1) as usize;
-    let mut scaled_rows: Vec<String> = Vec::new();
-    for row in base_rows {
-        // Horizontal scaling
-        let mut hscaled = String::with_capacity(row.len() * sx);
-        for ch in row.chars() {
-            for _ in 0..sx {
-                hscaled.push(ch);
+        for (row, line) in rows.iter_mut().zip(glyph.iter()) {
+            if !row.is_empty() {
+                row.push_str(&gap);
             }
-        }
-        // Vertical scaling
-        for _ in 0..sy {
-            scaled_rows.push(hscaled.clone());
+            row.push_str(line);
         }
     }
-    scaled_rows
+    rows
 }
 
 // 格式化中文日期
-fn format_date_cn() -> String {
-    let now = chrono::Local::now();
+fn format_date_cn(now: chrono::DateTime<chrono::FixedOffset>) -> String {
     let weekday = match now.weekday().number_from_monday() {
         1 => "星期一",
         2 => "星期二",
@@ -370,8 +2014,8 @@ fn format_date_cn() -> String {
     };
     // mm/dd/yyyy 星期X
     format!("{}/{}/{} {}",
-        now.format("%m").to_string(),
-        now.format("%d").to_string(),
-        now.format("%Y").to_string(),
+        now.format("%m"),
+        now.format("%d"),
+        now.format("%Y"),
         weekday)
 }
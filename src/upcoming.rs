@@ -0,0 +1,191 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::conditional::Validators;
+use crate::jsonpath::{self, JsonPath};
+
+// “接下来”组件：从 ICS 文件或 JSON 接口读取带时间的条目（潮汐、公交发车），
+// 显示最近的 N 个并倒计时，各自按 refresh_secs 刷新
+
+#[derive(Debug, Clone)]
+pub struct UpcomingFeed {
+    pub name: String,
+    pub kind: FeedKind,
+    pub limit: usize,
+    pub refresh_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum FeedKind {
+    // 本地路径或 http(s) 地址；只读取 VEVENT 的 DTSTART 和 SUMMARY（不展开 RRULE）
+    Ics(String),
+    // items 选出条目，time / label 在每个条目内取值
+    Json { url: String, items: JsonPath, time: JsonPath, label: Option<JsonPath> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Occurrence {
+    pub at: DateTime<Local>,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FeedState {
+    Loading,
+    Ready(Vec<Occurrence>),
+    Error(String),
+}
+
+// 每次最多保留的条目数（显示时再过滤已过去的）
+const MAX_ITEMS: usize = 100;
+
+pub fn spawn_worker(feed: UpcomingFeed) -> Arc<Mutex<FeedState>> {
+    let state = Arc::new(Mutex::new(FeedState::Loading));
+    let shared = state.clone();
+    std::thread::spawn(move || {
+        let mut validators = Validators::default();
+        loop {
+            let next = match fetch(&feed, &mut validators) {
+                Ok(Some(items)) => Some(FeedState::Ready(items)),
+                // 304：沿用当前条目
+                Ok(None) => None,
+                Err(e) => {
+                    validators.clear();
+                    Some(FeedState::Error(e))
+                }
+            };
+            if let Some(next) = next
+                && let Ok(mut guard) = shared.lock()
+            {
+                *guard = next;
+            }
+            std::thread::sleep(crate::power::stretch(Duration::from_secs(feed.refresh_secs)));
+        }
+    });
+    state
+}
+
+// 远程数据没有变化（304）时返回 Ok(None)
+fn fetch(feed: &UpcomingFeed, validators: &mut Validators) -> Result<Option<Vec<Occurrence>>, String> {
+    let now = Local::now();
+    let mut items = match &feed.kind {
+        FeedKind::Ics(location) => {
+            let Some(text) = read_text(location, validators)? else { return Ok(None) };
+            parse_ics(&text)
+        }
+        FeedKind::Json { url, items, time, label } => {
+            let Some(text) = read_text(url, validators)? else { return Ok(None) };
+            let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| format!("invalid JSON: {}", e))?;
+            items
+                .select(&json)
+                .into_iter()
+                .filter_map(|item| {
+                    let at = parse_time(time.select(item).first()?)?;
+                    let label = label
+                        .as_ref()
+                        .and_then(|path| path.select(item).first().map(|v| jsonpath::display(v)))
+                        .unwrap_or_default();
+                    Some(Occurrence { at, label })
+                })
+                .collect()
+        }
+    };
+    items.retain(|item| item.at > now);
+    items.sort_by_key(|item| item.at);
+    items.truncate(MAX_ITEMS);
+    Ok(Some(items))
+}
+
+fn read_text(location: &str, validators: &mut Validators) -> Result<Option<String>, String> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| e.to_string())?;
+        let _permit = crate::ratelimit::permit();
+        match validators.send(client.get(location)).map_err(|e| e.to_string())? {
+            Some(response) => response.text().map(Some).map_err(|e| e.to_string()),
+            None => Ok(None),
+        }
+    } else {
+        let path = match location.strip_prefix("~/") {
+            Some(rest) => std::env::var("HOME").map(|home| format!("{}/{}", home, rest)).unwrap_or_else(|_| location.to_string()),
+            None => location.to_string(),
+        };
+        std::fs::read_to_string(&path).map(Some).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+// 时间值：Unix 秒数、RFC 3339、"YYYY-MM-DD HH:MM[:SS]"（本地时间）或 "HH:MM"（今天）
+fn parse_time(value: &serde_json::Value) -> Option<DateTime<Local>> {
+    if let Some(secs) = value.as_i64() {
+        return Local.timestamp_opt(secs, 0).single();
+    }
+    let text = value.as_str()?.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(text) {
+        return Some(at.with_timezone(&Local));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(text, format) {
+            return Local.from_local_datetime(&naive).earliest();
+        }
+    }
+    let time = NaiveTime::parse_from_str(text, "%H:%M").ok()?;
+    Local.from_local_datetime(&Local::now().date_naive().and_time(time)).earliest()
+}
+
+// 解析 ICS：展开折行，读取每个 VEVENT 的 DTSTART（UTC、TZID 或浮动时间，全天事件取当天 0 点）和 SUMMARY
+pub fn parse_ics(text: &str) -> Vec<Occurrence> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    let mut items = Vec::new();
+    let mut start: Option<DateTime<Local>> = None;
+    let mut summary = String::new();
+    let mut in_event = false;
+    for line in &lines {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let (name, params) = key.split_once(';').unwrap_or((key, ""));
+        match name.to_ascii_uppercase().as_str() {
+            "BEGIN" if value == "VEVENT" => {
+                in_event = true;
+                start = None;
+                summary.clear();
+            }
+            "END" if value == "VEVENT" => {
+                if let Some(at) = start.take() {
+                    items.push(Occurrence { at, label: std::mem::take(&mut summary) });
+                }
+                in_event = false;
+            }
+            "DTSTART" if in_event => start = parse_ics_time(value, params),
+            "SUMMARY" if in_event => summary = value.replace("\\,", ",").replace("\\;", ";").replace("\\n", " "),
+            _ => {}
+        }
+    }
+    items
+}
+
+fn parse_ics_time(value: &str, params: &str) -> Option<DateTime<Local>> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&naive).with_timezone(&Local));
+    }
+    let naive = match NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        Ok(naive) => naive,
+        Err(_) => NaiveDate::parse_from_str(value, "%Y%m%d").ok()?.and_hms_opt(0, 0, 0)?,
+    };
+    let tzid = params.split(';').find_map(|p| p.strip_prefix("TZID="));
+    match tzid.and_then(|name| name.trim_matches('"').parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => tz.from_local_datetime(&naive).earliest().map(|at| at.with_timezone(&Local)),
+        None => Local.from_local_datetime(&naive).earliest(),
+    }
+}
@@ -0,0 +1,235 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+// 降水预报：提供者给出分钟级（15 分钟）或逐小时的降水量，后台定期刷新，
+// 用于“即将下雨”提醒
+
+// 一个预报时段的降水量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForecastSlot {
+    pub start: DateTime<Local>,
+    pub minutes: i64,
+    pub precipitation_mm: f64,
+}
+
+impl ForecastSlot {
+    fn end(&self) -> DateTime<Local> {
+        self.start + chrono::Duration::minutes(self.minutes)
+    }
+}
+
+pub trait ForecastProvider {
+    // 状态文件中缓存结果使用的名称
+    fn name(&self) -> &str;
+    // 按时间排序的降水预报（分钟级优先，之后补逐小时）
+    fn precipitation(&self, latitude: f64, longitude: f64) -> Result<Vec<ForecastSlot>, String>;
+}
+
+// Open-Meteo（免费，无需 API key）；base_url 可指向自建实例
+pub struct OpenMeteo {
+    pub base_url: String,
+}
+
+pub const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+impl ForecastProvider for OpenMeteo {
+    fn name(&self) -> &str {
+        "open-meteo"
+    }
+
+    fn precipitation(&self, latitude: f64, longitude: f64) -> Result<Vec<ForecastSlot>, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| e.to_string())?;
+        let url = format!(
+            "{}?latitude={}&longitude={}&minutely_15=precipitation&hourly=precipitation&forecast_minutely_15=16&forecast_hours=12&timeformat=unixtime",
+            self.base_url, latitude, longitude
+        );
+        let _permit = crate::ratelimit::permit();
+        let json: serde_json::Value = client
+            .get(&url)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| format!("forecast: {}", e))?;
+        let series = |key: &str, minutes: i64| -> Vec<ForecastSlot> {
+            let times = json[key]["time"].as_array().cloned().unwrap_or_default();
+            let values = json[key]["precipitation"].as_array().cloned().unwrap_or_default();
+            times
+                .iter()
+                .zip(values.iter())
+                .filter_map(|(t, v)| {
+                    let start = Local.timestamp_opt(t.as_i64()?, 0).single()?;
+                    Some(ForecastSlot { start, minutes, precipitation_mm: v.as_f64()? })
+                })
+                .collect()
+        };
+        let mut slots = series("minutely_15", 15);
+        if slots.is_empty() && json.get("hourly").is_none() {
+            return Err("forecast: no precipitation data".to_string());
+        }
+        // 分钟级预报覆盖范围之后用逐小时补齐
+        let covered = slots.last().map(|s| s.end());
+        slots.extend(series("hourly", 60).into_iter().filter(|s| covered.is_none_or(|end| s.start >= end)));
+        Ok(slots)
+    }
+}
+
+// 当前气温（℃ 文本）和相对湿度，用于温度提供者链
+pub fn current(base_url: &str, latitude: f64, longitude: f64) -> Result<(String, Option<f64>), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = format!("{}?latitude={}&longitude={}&current=temperature_2m,relative_humidity_2m", base_url, latitude, longitude);
+    let _permit = crate::ratelimit::permit();
+    let json: serde_json::Value = client
+        .get(&url)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .map_err(|e| format!("open-meteo: {}", e))?;
+    let temp = json["current"]["temperature_2m"].as_f64().ok_or("open-meteo: no current temperature")?;
+    Ok((format!("{:.1}℃", temp), json["current"]["relative_humidity_2m"].as_f64()))
+}
+
+// 当前天气状况（Open-Meteo 的 WMO 天气代码），用于天气强调色
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    Clear,
+    Cloudy,
+    Fog,
+    Rain,
+    Snow,
+    Thunder,
+}
+
+impl Condition {
+    // WMO 4677：0 晴，1-3 多云，45/48 雾，51-67 毛毛雨和雨，71-77 雪，80-82 阵雨，85/86 阵雪，95-99 雷暴
+    pub fn from_wmo(code: u64) -> Self {
+        match code {
+            0 => Condition::Clear,
+            45 | 48 => Condition::Fog,
+            51..=67 | 80..=82 => Condition::Rain,
+            71..=77 | 85 | 86 => Condition::Snow,
+            95..=99 => Condition::Thunder,
+            _ => Condition::Cloudy,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Condition::Clear => "clear",
+            Condition::Cloudy => "cloudy",
+            Condition::Fog => "fog",
+            Condition::Rain => "rain",
+            Condition::Snow => "snow",
+            Condition::Thunder => "thunderstorm",
+        }
+    }
+}
+
+pub fn current_condition(base_url: &str, latitude: f64, longitude: f64) -> Result<Condition, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = format!("{}?latitude={}&longitude={}&current=weather_code", base_url, latitude, longitude);
+    let _permit = crate::ratelimit::permit();
+    let json: serde_json::Value = client
+        .get(&url)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .map_err(|e| format!("weather: {}", e))?;
+    let code = json["current"]["weather_code"].as_u64().ok_or("weather: no current weather code")?;
+    Ok(Condition::from_wmo(code))
+}
+
+// 定期查询当前天气状况；失败时保留上次的结果
+pub fn spawn_condition_worker(base_url: String, location: (f64, f64), refresh_secs: u64) -> Arc<Mutex<Option<Condition>>> {
+    let state = Arc::new(Mutex::new(None));
+    let shared = state.clone();
+    std::thread::spawn(move || loop {
+        if let Ok(condition) = current_condition(&base_url, location.0, location.1)
+            && let Ok(mut guard) = shared.lock()
+        {
+            *guard = Some(condition);
+        }
+        std::thread::sleep(crate::power::stretch(Duration::from_secs(refresh_secs)));
+    });
+    state
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ForecastState {
+    Loading,
+    Ready(Vec<ForecastSlot>),
+    Error(String),
+}
+
+// 成功的预报按提供者和位置缓存到状态文件；重启后缓存未过期时先使用缓存，到期再请求
+pub fn spawn_worker(
+    provider: Box<dyn ForecastProvider + Send>,
+    location: (f64, f64),
+    refresh_secs: u64,
+    cache: PathBuf,
+) -> Arc<Mutex<ForecastState>> {
+    let state = Arc::new(Mutex::new(ForecastState::Loading));
+    let shared = state.clone();
+    std::thread::spawn(move || {
+        let refresh = Duration::from_secs(refresh_secs);
+        let key = format!("{}@{:.3},{:.3}", provider.name(), location.0, location.1);
+        if let Some((slots, age)) = crate::state::cached_result(&cache, &key, refresh) {
+            if let Ok(mut guard) = shared.lock() {
+                *guard = ForecastState::Ready(slots);
+            }
+            std::thread::sleep(refresh - age);
+        }
+        loop {
+            let next = match provider.precipitation(location.0, location.1) {
+                Ok(slots) => {
+                    crate::state::store_result(&cache, &key, &slots);
+                    ForecastState::Ready(slots)
+                }
+                Err(e) => ForecastState::Error(e),
+            };
+            if let Ok(mut guard) = shared.lock() {
+                *guard = next;
+            }
+            std::thread::sleep(crate::power::stretch(refresh));
+        }
+    });
+    state
+}
+
+// 即将到来（或正在进行）的降水
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RainAlert {
+    pub start: DateTime<Local>,
+    pub precipitation_mm: f64,
+}
+
+// "rain in 20 min (1.2 mm)" / "raining now (0.4 mm)"
+pub fn describe(alert: &RainAlert, now: DateTime<Local>) -> String {
+    let mins = (alert.start - now).num_minutes();
+    match mins {
+        ..=0 => format!("☂ raining now ({:.1} mm)", alert.precipitation_mm),
+        _ => format!("☂ rain in {} min ({:.1} mm)", mins, alert.precipitation_mm),
+    }
+}
+
+// lookahead_mins 内第一个降水量达到 threshold_mm 的时段；正在下雨时 start 不晚于 now
+pub fn next_rain(slots: &[ForecastSlot], now: DateTime<Local>, lookahead_mins: i64, threshold_mm: f64) -> Option<RainAlert> {
+    let horizon = now.checked_add_signed(chrono::Duration::try_minutes(lookahead_mins)?)?;
+    slots
+        .iter()
+        .filter(|s| s.end() > now && s.start <= horizon)
+        .find(|s| s.precipitation_mm >= threshold_mm)
+        .map(|s| RainAlert { start: s.start, precipitation_mm: s.precipitation_mm })
+}
@@ -0,0 +1,209 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::ipc::{self, IpcCommand, IpcRequest, Push};
+
+// 推送请求须带此请求头，值与 web_push_secret 一致
+const SECRET_HEADER: &str = "x-termclock-secret";
+const MAX_BODY: usize = 64 * 1024;
+// 推送消息的显示时长上限（7 天），更大的值按上限处理
+const MAX_MESSAGE_SECS: u64 = 7 * 24 * 3600;
+
+// 网页镜像：浏览器每秒拉取 /state.json，时间按服务器时钟校正后在本地走秒
+const INDEX_HTML: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>TermClock</title>
+<style>
+  body { background: #000; color: #eee; font-family: monospace; text-align: center; margin: 0; padding: 4vh 2vw; }
+  #time { font-size: 22vw; font-weight: bold; color: #ff0; line-height: 1; }
+  #date { font-size: 4vw; color: #ff0; margin-bottom: 4vh; }
+  #temp { font-size: 6vw; color: #f88; }
+  #sources { font-size: 3vw; color: #0cc; margin-bottom: 2vh; }
+  #todos { list-style: none; padding: 0; font-size: 3.5vw; text-align: left; display: inline-block; }
+  #alarm { display: none; border: 2px solid #f55; color: #f55; font-size: 5vw; padding: 1vh; margin: 2vh auto; }
+  #muted { position: fixed; top: 1vh; right: 2vw; color: #666; }
+  #message { display: none; background: #0cc; color: #000; font-size: 4vw; padding: 1vh; margin: 0 auto 2vh; }
+</style>
+</head>
+<body>
+<div id="muted"></div>
+<div id="message"></div>
+<div id="time">--:--:--</div>
+<div id="date"></div>
+<div id="alarm"></div>
+<div id="temp">--</div>
+<div id="sources"></div>
+<ul id="todos"></ul>
+<script>
+const WEEKDAYS = ["星期日", "星期一", "星期二", "星期三", "星期四", "星期五", "星期六"];
+let offset = 0;
+const pad = n => String(n).padStart(2, "0");
+function tick() {
+  const d = new Date(Date.now() + offset);
+  document.getElementById("time").textContent = `${pad(d.getHours())}:${pad(d.getMinutes())}:${pad(d.getSeconds())}`;
+  document.getElementById("date").textContent = `${pad(d.getMonth() + 1)}/${pad(d.getDate())}/${d.getFullYear()} ${WEEKDAYS[d.getDay()]}`;
+}
+async function poll() {
+  try {
+    const s = await (await fetch("/state.json", { cache: "no-store" })).json();
+    if (s.now) offset = Date.parse(s.now) - Date.now();
+    document.getElementById("temp").textContent = s.temperature || "--";
+    const ul = document.getElementById("todos");
+    ul.replaceChildren(...(s.todos.length ? s.todos : ["(no todos)"]).map(t => {
+      const li = document.createElement("li"); li.textContent = t; return li;
+    }));
+    document.getElementById("sources").replaceChildren(...(s.sources || []).map(([name, st]) => {
+      const div = document.createElement("div");
+      const v = st.Ready ? (st.Ready.Value ?? st.Ready.List.join(" · ")) : (st.Error ? `error: ${st.Error}` : "…");
+      div.textContent = `${name}: ${v}`; return div;
+    }));
+    const alarm = document.getElementById("alarm");
+    alarm.style.display = s.alarm ? "block" : "none";
+    if (s.alarm) alarm.textContent = `⏰ ${s.alarm.label}` + (s.alarm.snoozed_secs != null ? " (snoozed)" : "");
+    document.getElementById("muted").textContent = s.muted ? "🔕" : "";
+    const message = document.getElementById("message");
+    message.style.display = s.message ? "block" : "none";
+    message.textContent = s.message || "";
+  } catch (e) {}
+}
+setInterval(tick, 250);
+setInterval(poll, 1000);
+tick(); poll();
+</script>
+</body>
+</html>
+"#;
+
+// 启动网页镜像服务（GET / 和 GET /state.json）；配置了 push_secret 时接受 POST /push/...
+pub fn spawn_server(addr: &str, tx: Sender<IpcRequest>, push_secret: Option<String>) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let tx = tx.clone();
+            let secret = push_secret.clone();
+            std::thread::spawn(move || {
+                let _ = handle_connection(stream, &tx, secret.as_deref());
+            });
+        }
+    });
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, tx: &Sender<IpcRequest>, push_secret: Option<&str>) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut content_length = 0;
+    let mut secret = None;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':') {
+            let name = name.trim().to_ascii_lowercase();
+            if name == "content-length" {
+                content_length = value.trim().parse().unwrap_or(0);
+            } else if name == SECRET_HEADER {
+                secret = Some(value.trim().to_string());
+            }
+        }
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = path.split('?').next().unwrap_or("");
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/") | ("GET", "/index.html") => ("200 OK", "text/html; charset=utf-8", INDEX_HTML.to_string()),
+        ("GET", "/state.json") => match ipc::send_request(tx, IpcCommand::Snapshot) {
+            Some(json) => ("200 OK", "application/json", json),
+            None => ("503 Service Unavailable", "text/plain", "busy".to_string()),
+        },
+        ("POST", path) if path.starts_with("/push/") => {
+            let Some(expected) = push_secret else {
+                return write_response(stream, "404 Not Found", "text/plain", "push is disabled (set web_push_secret)");
+            };
+            if !secret.is_some_and(|s| constant_time_eq(s.as_bytes(), expected.as_bytes())) {
+                return write_response(stream, "401 Unauthorized", "text/plain", "missing or wrong X-TermClock-Secret");
+            }
+            if content_length > MAX_BODY {
+                return write_response(stream, "413 Payload Too Large", "text/plain", "body too large");
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            match parse_push(path, &String::from_utf8_lossy(&body)) {
+                Ok(push) => match ipc::send_request(tx, IpcCommand::Push(push)) {
+                    Some(reply) => ("200 OK", "text/plain", reply),
+                    None => ("503 Service Unavailable", "text/plain", "busy".to_string()),
+                },
+                Err(e) => ("400 Bad Request", "text/plain", e),
+            }
+        }
+        _ => ("404 Not Found", "text/plain", "not found".to_string()),
+    };
+    write_response(stream, status, content_type, &body)
+}
+
+// 请求体为 JSON 或纯文本：
+// /push/temperature  21.5 | "21.5℃" | {"temperature": 21.5, "humidity": 40}
+// /push/todos        ["a", "b"] | {"todos": [...]} | 每行一条；null 恢复配置的来源
+// /push/message      "text" | {"text": "...", "secs": 60}；空文本清除
+fn parse_push(path: &str, body: &str) -> Result<Push, String> {
+    let json = serde_json::from_str(body.trim()).unwrap_or_else(|_| Value::String(body.trim().to_string()));
+    let number = |v: &Value| v.as_f64().or_else(|| v.as_str().and_then(crate::temperature::parse_celsius));
+    match path {
+        "/push/temperature" => {
+            let (temp, humidity) = match &json {
+                Value::Object(map) => (map.get("temperature").or(map.get("temp")), map.get("humidity")),
+                other => (Some(other), None),
+            };
+            let temp = temp.and_then(number).ok_or("expected a temperature such as 21.5 or {\"temperature\": 21.5}")?;
+            Ok(Push::Temperature { temp, humidity: humidity.and_then(Value::as_f64) })
+        }
+        "/push/todos" => {
+            let list = match &json {
+                Value::Object(map) => map.get("todos").cloned().unwrap_or(Value::Null),
+                other => other.clone(),
+            };
+            let todos = match list {
+                Value::Null => None,
+                Value::Array(items) => Some(items.iter().map(crate::jsonpath::display).filter(|t| !t.trim().is_empty()).collect()),
+                Value::String(text) => Some(crate::todo_store::parse_file(&text)),
+                _ => return Err("expected a list of todos".to_string()),
+            };
+            Ok(Push::Todos(todos))
+        }
+        "/push/message" => {
+            let (text, secs) = match &json {
+                Value::Object(map) => (map.get("text").filter(|v| !v.is_null()).map(crate::jsonpath::display), map.get("secs").and_then(Value::as_u64).map(|s| s.min(MAX_MESSAGE_SECS))),
+                Value::Null => (None, None),
+                other => (Some(crate::jsonpath::display(other)), None),
+            };
+            Ok(Push::Message { text: text.unwrap_or_default(), secs })
+        }
+        _ => Err(format!("unknown push endpoint {}", path)),
+    }
+}
+
+// 比较耗时与内容无关，不通过响应时间泄露密钥前缀
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn write_response(mut stream: TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}